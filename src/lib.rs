@@ -7,546 +7,8510 @@ use syn::parse::discouraged::Speculative;
 use syn::parse::ParseStream;
 use syn::{parse_macro_input, DataEnum, DeriveInput, Fields, Meta, Token};
 
-#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
 enum GetterKind {
+    // Order of declaration is the order of names in "as (a, b, ...)" syntax, so it follows the docs
     ReadOnly,
     Mutable,
     Owning,
+    Replacing,
+    Swapping,
+    Mapping,
+    Updating,
+    Cloning,
+    OwnedCloning,
+    Copying,
+    Cow,
+    Arc,
+    OptionDeref,
+    AsRef,
+    Expecting(String),
+    CellGetting,
+    AtomicLoading,
+    AtomicStoring,
+    TryOwning,
+    Resulting,
+    Indexed,
+    TryConverting(String), // Type of the field, as string since syn::Type can't be ordered
+    Pinning,
+    Bytes,
+    StrOwning,
+    Dereferencing,
 }
 
 impl GetterKind {
+    /// Parses modifiers joined by `+`, like `ref + own_only`, or returns just `ReadOnly` if there are none
     fn parse(input: ParseStream) -> syn::Result<Vec<Self>> {
-        if input.peek(syn::Ident) && input.peek2(Token![:]) {
+        let Some(mut kinds) = Self::parse_modifier(input)? else {
             return Ok(vec![Self::ReadOnly]);
+        };
+        while input.parse::<Token![+]>().is_ok() {
+            let Some(more_kinds) = Self::parse_modifier(input)? else {
+                return Err(input.error("Expected modifier after +"));
+            };
+            for kind in more_kinds {
+                if !kinds.contains(&kind) {
+                    kinds.push(kind);
+                }
+            }
+        }
+        Ok(kinds)
+    }
+
+    fn parse_modifier(input: ParseStream) -> syn::Result<Option<Vec<Self>>> {
+        if input.peek(syn::Ident) && input.peek2(Token![:]) {
+            return Ok(None);
         }
         let fork = input.fork();
         if fork.parse::<Token![mut]>().is_ok() {
             input.advance_to(&fork);
-            return Ok(vec![Self::ReadOnly, Self::Mutable]);
+            return Ok(Some(vec![Self::ReadOnly, Self::Mutable]));
+        }
+        if fork.parse::<Token![ref]>().is_ok() {
+            input.advance_to(&fork);
+            return Ok(Some(vec![Self::ReadOnly]));
         }
 
         if let Ok(indent) = fork.parse::<Ident>() {
             match indent.to_string().as_str() {
                 "mut_only" => {
                     input.advance_to(&fork);
-                    return Ok(vec![Self::Mutable]);
+                    return Ok(Some(vec![Self::Mutable]));
                 }
                 "all" | "own" => {
                     input.advance_to(&fork);
-                    return Ok(vec![Self::Owning, Self::Mutable, Self::ReadOnly]);
+                    return Ok(Some(vec![Self::Owning, Self::Mutable, Self::ReadOnly]));
                 }
                 "own_only" => {
                     input.advance_to(&fork);
-                    return Ok(vec![Self::Owning]);
+                    return Ok(Some(vec![Self::Owning]));
+                }
+                "replace" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::Replacing]));
+                }
+                "take_into" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::Swapping]));
+                }
+                "map" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::Mapping]));
+                }
+                "update" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::Updating]));
+                }
+                "clone" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::Cloning]));
+                }
+                "get_or_clone" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::ReadOnly, Self::OwnedCloning]));
+                }
+                "copy" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::Copying]));
+                }
+                "cow" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::Cow]));
+                }
+                "arc" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::Arc]));
+                }
+                "pin" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::Pinning]));
+                }
+                "as_deref" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::OptionDeref]));
+                }
+                "as_ref" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::AsRef]));
+                }
+                "deref" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::Dereferencing]));
+                }
+                "cell" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::CellGetting]));
+                }
+                "atomic" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::AtomicLoading, Self::AtomicStoring]));
+                }
+                "result" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::Resulting]));
+                }
+                "indexed" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::Indexed]));
+                }
+                "try_own" => {
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::TryOwning]));
+                }
+                "try_into" if fork.peek(syn::token::Paren) => {
+                    let source;
+                    syn::parenthesized!(source in fork);
+                    let source = source.parse::<syn::Type>()?;
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::TryConverting(quote!(#source).to_string())]));
+                }
+                "expect" if fork.peek(syn::token::Paren) => {
+                    let message;
+                    syn::parenthesized!(message in fork);
+                    let message = message.parse::<syn::LitStr>()?.value();
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::Expecting(message)]));
+                }
+                "bytes" => {
+                    if !cfg!(feature = "bytemuck") {
+                        return Err(syn::Error::new(
+                            indent.span(),
+                            "\"bytes\" modifier requires \"bytemuck\" feature of enum_common_fields",
+                        ));
+                    }
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::Bytes]));
+                }
+                "own_str" => {
+                    if !cfg!(feature = "alloc") {
+                        return Err(syn::Error::new(
+                            indent.span(),
+                            "\"own_str\" modifier requires \"alloc\" feature of enum_common_fields",
+                        ));
+                    }
+                    input.advance_to(&fork);
+                    return Ok(Some(vec![Self::StrOwning]));
                 }
                 _ => {}
             }
         }
 
-        Ok(vec![Self::ReadOnly])
+        Ok(None)
+    }
+
+    /// Returns true for kinds that can't work with `Deref` target instead of the actual field type
+    fn moves_field(&self) -> bool {
+        matches!(
+            self,
+            Self::Owning | Self::TryOwning | Self::Replacing | Self::Swapping | Self::Mapping
+        )
+    }
+
+    /// Returns true for kinds which accessors are useless if their result is ignored
+    fn must_use(&self) -> bool {
+        !matches!(
+            self,
+            Self::Mutable | Self::Swapping | Self::Updating | Self::AtomicStoring | Self::Pinning
+        )
+    }
+
+    /// Returns true for kinds which accessors return the value itself, so it can be replaced by a default
+    fn returns_value(&self) -> bool {
+        matches!(
+            self,
+            Self::Owning | Self::Cloning | Self::Copying | Self::CellGetting
+        )
+    }
+
+    /// Names of arguments of the accessor, passed to the accessor of the inner type by variants with
+    /// #[common_field(delegate)], or `None` if the result of inner accessor can't be returned as is
+    fn delegate_arguments(&self) -> Option<Vec<Ident>> {
+        let arguments: &[&str] = match self {
+            Self::TryOwning | Self::Resulting | Self::Indexed | Self::Mapping | Self::Pinning => {
+                return None
+            }
+            Self::Replacing => &["new"],
+            Self::Swapping => &["slot"],
+            Self::Updating => &["f"],
+            Self::AtomicLoading => &["order"],
+            Self::AtomicStoring => &["value", "order"],
+            _ => &[],
+        };
+        Some(
+            arguments
+                .iter()
+                .map(|argument| format_ident!("{argument}"))
+                .collect(),
+        )
+    }
+
+    /// Returns true for kinds that implement a standard trait instead of generating an inherent method
+    fn implements_trait(&self) -> bool {
+        matches!(self, Self::Dereferencing)
+    }
+
+    /// Returns true for kinds which accessors return `Option` when some variant skips the field
+    fn has_optional_form(&self) -> bool {
+        self.returns_value() || matches!(self, Self::ReadOnly | Self::Mutable | Self::OwnedCloning)
+    }
+
+    fn default_name(&self, field_name: &Ident) -> Ident {
+        match self {
+            Self::ReadOnly
+            | Self::Cloning
+            | Self::Copying
+            | Self::Cow
+            | Self::OptionDeref
+            | Self::AsRef
+            | Self::Expecting(_)
+            | Self::CellGetting
+            | Self::AtomicLoading
+            | Self::Resulting
+            | Self::TryConverting(_)
+            | Self::Dereferencing => field_name.clone(),
+            Self::Mutable => format_ident!("{field_name}_mut"),
+            Self::Owning | Self::StrOwning => format_ident!("into_{field_name}"),
+            Self::OwnedCloning => format_ident!("{field_name}_owned"),
+            Self::TryOwning => format_ident!("try_into_{field_name}"),
+            Self::AtomicStoring => format_ident!("set_{field_name}"),
+            Self::Replacing => format_ident!("replace_{field_name}"),
+            Self::Swapping => format_ident!("take_{field_name}_into"),
+            Self::Mapping => format_ident!("map_{field_name}"),
+            Self::Updating => format_ident!("update_{field_name}"),
+            Self::Bytes => format_ident!("{field_name}_bytes"),
+            Self::Arc => format_ident!("{field_name}_arc"),
+            Self::Pinning => format_ident!("{field_name}_pin"),
+            Self::Indexed => format_ident!("{field_name}_with_index"),
+        }
     }
 }
 
 /// Internal struct to store parameters for EnumCommonFields
 #[derive(Clone)]
 struct CommonField {
+    cfg: Option<syn::Attribute>, // #[cfg(...)] attribute of everything generated for the annotation, from "cfg(...)" prefix
     kinds: Vec<GetterKind>,
+    explicit_kinds: bool, // Whether the annotation has modifiers, so default kinds don't apply to it
     field_name: Ident,
-    field_type: Ident,
-    resulting_name: Option<Ident>, // Can have a value only if one function is generated
+    more_names: Vec<Ident>, // Other fields from "name, name: Type" syntax, that get the same accessors
+    field_type: syn::Type,
+    resulting_name: Option<Ident>, // Name of the accessor, or base for accessor names if more than one is generated
+    kind_names: Vec<Ident>, // Names from "as (a, b, ...)" syntax, one for each of kinds, in the same order
+    via: Option<proc_macro2::TokenStream>, // Method call from "via method()" syntax, used by tuple variants instead of the field
+    since: Option<String>,
+    no_coverage: bool,
+    default: Option<syn::Expr>, // Value returned by variants that don't have the field
+    get: Option<syn::Expr>,     // Conversion applied to the field by immutable accessor
+    collect: Option<Option<Ident>>, // Inner value is the name of the collecting function, if it's not default
+    redact: bool,                   // Hide the value of the field in generated Debug implementation
+    as_mut: bool,                   // Implement AsMut for the field type
+    borrow: bool,                   // Implement Borrow for the field type
+    borrow_mut: bool,               // Implement BorrowMut (and Borrow) for the field type
+    from: bool,    // Implement From<Enum> for the field type, using the owning accessor
+    display: bool, // Implement Display for the enum, forwarding to the field
+    eq: bool,      // Implement PartialEq between the enum and the field type, in both directions
+    implemented: Option<syn::Path>, // Path of the trait method that the accessor implements, from "impl = Trait::method"
+    vis: Option<syn::Visibility>,   // Visibility of the accessors, from "vis = pub(crate)"
+    inline: Option<syn::Attribute>, // #[inline] attribute of the accessors, from "inline", "inline(always)" or "inline(never)"
+    constant: bool,                 // Generate accessors as const fn, from "const" option
+    doc: Option<String>,            // Doc comment of the accessors, from "doc = ..."
+    aliases: Vec<String>, // Search aliases of the accessors in rustdoc, from "alias = ..." options
+    options: Vec<Ident>,  // Names of all options set in the annotation
+    flattened: Vec<(Ident, syn::Type)>, // Fields of the field type from "flatten" syntax, that get accessors too
+    through: Option<Ident>,             // Flattened field, that this field is reached through
+}
+
+impl CommonField {
+    /// Returns the annotation together with annotations for other fields from "name, name: Type" syntax,
+    /// and for fields from "flatten" syntax, that have the same kinds and options except those that name accessors or implement traits
+    fn flatten(mut self) -> Vec<Self> {
+        if !self.more_names.is_empty() {
+            let more_names = std::mem::take(&mut self.more_names);
+            return std::iter::once(self.clone())
+                .chain(more_names.into_iter().map(|field_name| Self {
+                    field_name,
+                    ..self.clone()
+                }))
+                .collect();
+        }
+        let inner_fields = self
+            .flattened
+            .iter()
+            .map(|(field_name, field_type)| Self {
+                field_name: field_name.clone(),
+                field_type: field_type.clone(),
+                resulting_name: None,
+                kind_names: Vec::new(),
+                via: None,
+                as_mut: false,
+                borrow: false,
+                borrow_mut: false,
+                from: false,
+                display: false,
+                eq: false,
+                implemented: None,
+                doc: None,
+                aliases: Vec::new(),
+                flattened: Vec::new(),
+                through: Some(self.field_name.clone()),
+                ..self.clone()
+            })
+            .collect::<Vec<_>>();
+        std::iter::once(self).chain(inner_fields).collect()
+    }
 }
 
 impl syn::parse::Parse for CommonField {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut options = Vec::new();
+        let mut cfg = None;
+        if input.peek(syn::Ident)
+            && input.peek2(syn::token::Paren)
+            && input.fork().parse::<Ident>()? == "cfg"
+        {
+            options.push(input.parse::<Ident>()?);
+            let content;
+            syn::parenthesized!(content in input);
+            let predicate = content.parse::<proc_macro2::TokenStream>()?;
+            cfg = Some(syn::parse_quote!(#[cfg(#predicate)]));
+        }
+        let fork = input.fork();
+        // Field can be named "flatten" too, so the keyword needs a field name after it
+        let flatten = fork.parse::<Ident>().is_ok_and(|ident| ident == "flatten")
+            && fork
+                .call(<Ident as syn::ext::IdentExt>::parse_any)
+                .is_ok_and(|next| next != "as" && next != "via");
+        if flatten {
+            input.parse::<Ident>()?;
+        }
+        let explicit_kinds = GetterKind::parse_modifier(&input.fork())?.is_some();
         let kinds = GetterKind::parse(input)?;
-        let field_name = input.parse()?;
-        let resulting_name = match input.parse::<Token![as]>() {
-            Ok(_) => Some(input.parse::<Ident>()?),
-            Err(_) => None,
-        };
+        let field_name: Ident = input.parse()?;
+        let mut more_names = Vec::new();
+        while input.parse::<Token![,]>().is_ok() {
+            more_names.push(input.parse::<Ident>()?);
+        }
+        let mut via = None;
+        if input.peek(syn::Ident) && input.fork().parse::<Ident>()? == "via" {
+            input.parse::<Ident>()?;
+            let method = input.parse::<Ident>()?;
+            let content;
+            syn::parenthesized!(content in input);
+            let arguments =
+                syn::punctuated::Punctuated::<syn::Expr, Token![,]>::parse_terminated(&content)?;
+            via = Some(quote!(#method(#arguments)));
+        }
+        let mut resulting_name = None;
+        let mut kind_names = Vec::new();
+        if input.parse::<Token![as]>().is_ok() {
+            if input.peek(syn::token::Paren) {
+                let content;
+                let paren = syn::parenthesized!(content in input);
+                let names: Vec<Ident> =
+                    syn::punctuated::Punctuated::<Ident, Token![,]>::parse_terminated(&content)?
+                        .into_iter()
+                        .collect();
+                if names.len() != kinds.len() {
+                    return Err(syn::Error::new(
+                        paren.span.join(),
+                        format!(
+                            "Expected {} names, one for each generated accessor",
+                            kinds.len()
+                        ),
+                    ));
+                }
+                // Names are listed in the order of GetterKind declaration (read, mut, own, ...), whatever the order of kinds is
+                let mut sorted_kinds = kinds.clone();
+                sorted_kinds.sort();
+                kind_names = kinds
+                    .iter()
+                    .map(|kind| {
+                        names[sorted_kinds
+                            .iter()
+                            .position(|sorted| sorted == kind)
+                            .unwrap()]
+                        .clone()
+                    })
+                    .collect();
+            } else {
+                resulting_name = Some(input.parse::<Ident>()?);
+            }
+        }
+        if !more_names.is_empty()
+            && (flatten || via.is_some() || resulting_name.is_some() || !kind_names.is_empty())
+        {
+            return Err(input.error(
+                "Annotation with several fields can't name accessors or use via and flatten",
+            ));
+        }
         input.parse::<Token![:]>()?;
         let field_type = input.parse()?;
+        let mut flattened = Vec::new();
+        if flatten {
+            let content;
+            syn::braced!(content in input);
+            flattened = syn::punctuated::Punctuated::<_, Token![,]>::parse_terminated_with(
+                &content,
+                |input| {
+                    let field_name = input.parse::<Ident>()?;
+                    input.parse::<Token![:]>()?;
+                    Ok((field_name, input.parse::<syn::Type>()?))
+                },
+            )?
+            .into_iter()
+            .collect();
+        }
+        let mut since = None;
+        let mut no_coverage = false;
+        let mut default = None;
+        let mut collect = None;
+        let mut get = None;
+        let mut redact = false;
+        let mut as_mut = false;
+        let mut borrow = false;
+        let mut borrow_mut = false;
+        let mut from = false;
+        let mut display = false;
+        let mut eq = false;
+        let mut implemented = None;
+        let mut vis = None;
+        let mut inline = None;
+        let mut constant = false;
+        let mut doc = None;
+        let mut aliases = Vec::new();
+        while input.parse::<Token![,]>().is_ok() && !input.is_empty() {
+            // Keyword options are kept in the list of options too, so they can be denied
+            if let Ok(token) = input.parse::<Token![impl]>() {
+                options.push(Ident::new("impl", token.span));
+                input.parse::<Token![=]>()?;
+                let path = input.parse::<syn::Path>()?;
+                if path.segments.len() < 2 {
+                    return Err(syn::Error::new_spanned(
+                        path,
+                        "Expected path to the trait method, like Trait::method",
+                    ));
+                }
+                implemented = Some(path);
+                continue;
+            }
+            if let Ok(token) = input.parse::<Token![const]>() {
+                options.push(Ident::new("const", token.span));
+                constant = true;
+                continue;
+            }
+            let option = input.parse::<Ident>()?;
+            options.push(option.clone());
+            match option.to_string().as_str() {
+                "since" => {
+                    input.parse::<Token![=]>()?;
+                    since = Some(input.parse::<syn::LitStr>()?.value());
+                }
+                "no_coverage" => no_coverage = true,
+                "redact" => redact = true,
+                "as_mut" => as_mut = true,
+                "borrow" => borrow = true,
+                "borrow_mut" => borrow_mut = true,
+                "from" => from = true,
+                "display" => display = true,
+                "eq" => eq = true,
+                "vis" => {
+                    input.parse::<Token![=]>()?;
+                    vis = Some(input.parse::<syn::Visibility>()?);
+                }
+                "inline" => inline = Some(parse_inline(input)?),
+                "doc" => {
+                    input.parse::<Token![=]>()?;
+                    doc = Some(input.parse::<syn::LitStr>()?.value());
+                }
+                "alias" => {
+                    input.parse::<Token![=]>()?;
+                    aliases.push(input.parse::<syn::LitStr>()?.value());
+                }
+                "default" => {
+                    input.parse::<Token![=]>()?;
+                    default = Some(input.parse::<syn::Expr>()?);
+                }
+                "get" => {
+                    input.parse::<Token![=]>()?;
+                    get = Some(input.parse::<syn::Expr>()?);
+                }
+                "collect" => {
+                    collect = Some(match input.parse::<Token![=]>() {
+                        Ok(_) => Some(input.parse::<Ident>()?),
+                        Err(_) => None,
+                    });
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        option.span(),
+                        "Unknown common_field option",
+                    ))
+                }
+            }
+        }
+        if let Some(option) = options
+            .iter()
+            .find(|option| *option == "const")
+            .filter(|_| get.is_some() || via.is_some())
+        {
+            return Err(syn::Error::new(
+                option.span(),
+                "\"const\" option can't be used with get option and via method, since const fn can't call them",
+            ));
+        }
+        if !more_names.is_empty() {
+            // Every field of the annotation would implement the same trait for the enum
+            let trait_option = options.iter().find(|option| {
+                [
+                    "as_mut",
+                    "borrow",
+                    "borrow_mut",
+                    "from",
+                    "display",
+                    "eq",
+                    "impl",
+                ]
+                .contains(&option.to_string().as_str())
+            });
+            let span = match trait_option {
+                Some(option) => Some(option.span()),
+                None if kinds.iter().any(GetterKind::implements_trait) => Some(field_name.span()),
+                None => None,
+            };
+            if let Some(span) = span {
+                return Err(syn::Error::new(
+                    span,
+                    "Annotation with several fields can't implement traits with deref modifier or as_mut, borrow, borrow_mut, from, display, eq and impl options",
+                ));
+            }
+        }
         Ok(Self {
+            cfg,
             kinds,
+            explicit_kinds,
             field_name,
+            more_names,
             field_type,
             resulting_name,
+            kind_names,
+            via,
+            since,
+            no_coverage,
+            default,
+            get,
+            collect,
+            redact,
+            as_mut,
+            borrow,
+            borrow_mut,
+            from,
+            display,
+            eq,
+            implemented,
+            vis,
+            inline,
+            constant,
+            doc,
+            aliases,
+            options,
+            flattened,
+            through: None,
         })
     }
 }
 
+/// Parses optional `(always)` or `(never)` after "inline" option into #[inline] attribute
+fn parse_inline(input: ParseStream) -> syn::Result<syn::Attribute> {
+    if !input.peek(syn::token::Paren) {
+        return Ok(syn::parse_quote!(#[inline]));
+    }
+    let content;
+    syn::parenthesized!(content in input);
+    let hint = content.parse::<Ident>()?;
+    if hint != "always" && hint != "never" {
+        return Err(syn::Error::new(
+            hint.span(),
+            "Expected inline, inline(always) or inline(never)",
+        ));
+    }
+    Ok(syn::parse_quote!(#[inline(#hint)]))
+}
+
+/// Internal struct to store enum-level parameters from #[common_fields_config(...)]
+#[derive(Clone, Default)]
+struct CommonFieldsConfig {
+    proptest: bool,
+    arbitrary: bool, // Implement Arbitrary for the owned common struct
+    columns: bool,
+    common: bool,
+    common_iter: bool,  // Implement IntoIterator for the CommonRef struct
+    common_serde: bool, // Implement Serialize for the CommonRef struct
+    common_mut: bool,
+    into_common: bool,
+    common_owned: bool,
+    split: bool,
+    vis: Option<syn::Visibility>, // Visibility of generated methods, unless set for the field
+    inline: Option<syn::Attribute>, // #[inline] attribute of all generated functions, unless set for the field
+    auto: bool,                     // Declare fields that all variants have, from "auto" option
+    exclude: Vec<Ident>,            // Fields that auto option doesn't declare
+    name_templates: Vec<(GetterKind, String)>, // Templates of accessor names with {field} placeholder, from getter, mutter and owner options
+    audit_hook: Option<syn::Path>,
+    frozen: Option<Vec<String>>, // Names of all methods that the derive must generate
+    display: Option<syn::LitStr>, // Format template of the generated Display implementation
+    debug: bool,
+    metadata: bool,
+    constructors: bool,
+    no_std: bool, // Name allocating types through ::alloc instead of ::std
+    default_since: Option<String>, // Defaults for options of #[common_field] annotations
+    default_kinds: Option<Vec<GetterKind>>,
+    default_no_coverage: bool,
+    default_collect: bool,
+    default_doc: Option<String>, // Doc comment template of accessors with {field} placeholder
+    default_cfg: Option<syn::Attribute>,
+    deny: Vec<Ident>, // Options of #[common_field] annotations that can't be used
+    rename_map: Vec<(Ident, Ident)>, // Pairs of field name and the name to use for its accessors instead
+    remote: Option<syn::Path>,       // Enum from other crate, that the annotated enum is a copy of
+}
+
+impl CommonFieldsConfig {
+    fn parse_options(&mut self, input: ParseStream) -> syn::Result<()> {
+        while !input.is_empty() {
+            let option = input.parse::<Ident>()?;
+            match option.to_string().as_str() {
+                "proptest" => {
+                    if !cfg!(feature = "proptest") {
+                        return Err(syn::Error::new(
+                            option.span(),
+                            "\"proptest\" option requires \"proptest\" feature of enum_common_fields",
+                        ));
+                    }
+                    self.proptest = true;
+                }
+                "arbitrary" => {
+                    if !cfg!(feature = "arbitrary") {
+                        return Err(syn::Error::new(
+                            option.span(),
+                            "\"arbitrary\" option requires \"arbitrary\" feature of enum_common_fields",
+                        ));
+                    }
+                    self.arbitrary = true;
+                }
+                "audit_hook" => {
+                    if !cfg!(feature = "audit") {
+                        return Err(syn::Error::new(
+                            option.span(),
+                            "\"audit_hook\" option requires \"audit\" feature of enum_common_fields",
+                        ));
+                    }
+                    input.parse::<Token![=]>()?;
+                    self.audit_hook = Some(input.parse()?);
+                }
+                "display" => {
+                    input.parse::<Token![=]>()?;
+                    self.display = Some(input.parse()?);
+                }
+                "remote" => {
+                    input.parse::<Token![=]>()?;
+                    self.remote = Some(input.parse::<syn::LitStr>()?.parse()?);
+                }
+                "frozen" => {
+                    input.parse::<Token![=]>()?;
+                    let frozen = input.parse::<syn::LitStr>()?.value();
+                    self.frozen = Some(
+                        frozen
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|name| !name.is_empty())
+                            .map(String::from)
+                            .collect(),
+                    );
+                }
+                "defaults" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    while !content.is_empty() {
+                        let option = content.parse::<Ident>()?;
+                        match option.to_string().as_str() {
+                            "since" => {
+                                content.parse::<Token![=]>()?;
+                                self.default_since = Some(content.parse::<syn::LitStr>()?.value());
+                            }
+                            "no_coverage" => self.default_no_coverage = true,
+                            "collect" => self.default_collect = true,
+                            "doc" => {
+                                content.parse::<Token![=]>()?;
+                                self.default_doc = Some(content.parse::<syn::LitStr>()?.value());
+                            }
+                            "cfg" => {
+                                let predicate;
+                                syn::parenthesized!(predicate in content);
+                                let predicate = predicate.parse::<proc_macro2::TokenStream>()?;
+                                self.default_cfg = Some(syn::parse_quote!(#[cfg(#predicate)]));
+                            }
+                            "kinds" => {
+                                content.parse::<Token![=]>()?;
+                                if GetterKind::parse_modifier(&content.fork())?.is_none() {
+                                    return Err(content.error("Expected modifiers, like mut + own"));
+                                }
+                                self.default_kinds = Some(GetterKind::parse(&content)?);
+                            }
+                            _ => {
+                                return Err(syn::Error::new(
+                                    option.span(),
+                                    "Unknown option, only kinds, since, no_coverage, collect, doc and cfg can have defaults",
+                                ))
+                            }
+                        }
+                        if !content.is_empty() {
+                            content.parse::<Token![,]>()?;
+                        }
+                    }
+                }
+                "deny" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    // Keywords are accepted too, for impl and const options
+                    self.deny.extend(
+                        syn::punctuated::Punctuated::<Ident, Token![,]>::parse_terminated_with(
+                            &content,
+                            <Ident as syn::ext::IdentExt>::parse_any,
+                        )?,
+                    );
+                }
+                "columns" => self.columns = true,
+                "debug" => self.debug = true,
+                "metadata" => self.metadata = true,
+                "constructors" => self.constructors = true,
+                "no_std" => self.no_std = true,
+                "common" => {
+                    self.common = true;
+                    if input.peek(syn::token::Paren) {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        let flags =
+                            syn::punctuated::Punctuated::<Ident, Token![,]>::parse_terminated(
+                                &content,
+                            )?;
+                        for flag in flags {
+                            match flag.to_string().as_str() {
+                                "iter" => self.common_iter = true,
+                                "serde" => {
+                                    if !cfg!(feature = "serde") {
+                                        return Err(syn::Error::new(
+                                            flag.span(),
+                                            "\"common(serde)\" option requires \"serde\" feature of enum_common_fields",
+                                        ));
+                                    }
+                                    self.common_serde = true;
+                                }
+                                _ => {
+                                    return Err(syn::Error::new(
+                                        flag.span(),
+                                        "Unknown common option",
+                                    ))
+                                }
+                            }
+                        }
+                    }
+                }
+                "common_mut" => self.common_mut = true,
+                "into_common" => self.into_common = true,
+                "common_owned" => self.common_owned = true,
+                "split" => self.split = true,
+                "auto" => self.auto = true,
+                "inline" => self.inline = Some(parse_inline(input)?),
+                "exclude" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    self.exclude.extend(
+                        syn::punctuated::Punctuated::<Ident, Token![,]>::parse_terminated(
+                            &content,
+                        )?,
+                    );
+                }
+                "vis" => {
+                    input.parse::<Token![=]>()?;
+                    self.vis = Some(input.parse()?);
+                }
+                "getter" | "mutter" | "owner" => {
+                    input.parse::<Token![=]>()?;
+                    let template = input.parse::<syn::LitStr>()?;
+                    if !template.value().contains("{field}") {
+                        return Err(syn::Error::new(
+                            template.span(),
+                            "Expected accessor name template with {field} placeholder, like \"get_{field}\"",
+                        ));
+                    }
+                    let kind = match option.to_string().as_str() {
+                        "getter" => GetterKind::ReadOnly,
+                        "mutter" => GetterKind::Mutable,
+                        _ => GetterKind::Owning,
+                    };
+                    self.name_templates.push((kind, template.value()));
+                }
+                "rename_map" => {
+                    input.parse::<Token![=]>()?;
+                    let rename_map = input.parse::<syn::LitStr>()?;
+                    for rename in rename_map.value().split(',') {
+                        let Some((field_name, base_name)) = rename.split_once("=>") else {
+                            return Err(syn::Error::new(
+                                rename_map.span(),
+                                "Expected rename_map format: \"field_name => accessor_name, ...\"",
+                            ));
+                        };
+                        self.rename_map.push((
+                            syn::parse_str(field_name.trim())?,
+                            syn::parse_str(base_name.trim())?,
+                        ));
+                    }
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        option.span(),
+                        "Unknown common_fields_config option",
+                    ))
+                }
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fills options that the annotation doesn't set with defaults. Panics if the annotation uses a denied option
+    fn apply_defaults(&self, common_field: &mut CommonField) {
+        if let Some(option) = common_field
+            .options
+            .iter()
+            .find(|option| self.deny.contains(option))
+        {
+            panic!(
+                "Option {option} of #[common_field] annotation for {} is denied by #[common_fields_config(deny(...))]",
+                common_field.field_name
+            )
+        }
+        if let Some(kinds) = self
+            .default_kinds
+            .as_ref()
+            .filter(|_| !common_field.explicit_kinds)
+        {
+            common_field.kinds.clone_from(kinds);
+        }
+        if common_field.since.is_none() {
+            common_field.since.clone_from(&self.default_since);
+        }
+        common_field.no_coverage |= self.default_no_coverage;
+        if common_field.collect.is_none() && self.default_collect {
+            common_field.collect = Some(None);
+        }
+    }
+
+    /// Returns default doc comment of accessors of the field, made from the template set with "defaults(doc = ...)" option
+    fn default_doc(&self, field_name: &Ident) -> Option<String> {
+        self.default_doc
+            .as_ref()
+            .map(|template| template.replace("{field}", &field_name.to_string()))
+    }
+
+    /// Returns path to an item of the alloc crate, that is named through `::std` unless "no_std" option is set
+    fn alloc_path(&self, path: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        if self.no_std {
+            quote!(::alloc::#path)
+        } else {
+            quote!(::std::#path)
+        }
+    }
+
+    /// Returns visibility of generated methods, that is `pub` unless set with "vis" option
+    fn vis(&self) -> syn::Visibility {
+        self.vis.clone().unwrap_or_else(|| syn::parse_quote!(pub))
+    }
+
+    /// Returns statement that reports access to the field to the audit hook, or nothing if there is no hook
+    fn audit_call(&self, field_name: &Ident) -> proc_macro2::TokenStream {
+        match &self.audit_hook {
+            Some(hook) => {
+                let field_name = field_name.to_string();
+                quote!(#hook(#field_name);)
+            }
+            None => quote!(),
+        }
+    }
+
+    /// Returns default name of the accessor of given kind, made from the template for the kind, if there is one
+    fn accessor_name(&self, kind: &GetterKind, base_name: &Ident) -> Ident {
+        self.name_templates
+            .iter()
+            .find(|(template_kind, _)| template_kind == kind)
+            .map_or_else(
+                || kind.default_name(base_name),
+                |(_, template)| {
+                    format_ident!("{}", template.replace("{field}", &base_name.to_string()))
+                },
+            )
+    }
+
+    /// Returns field name that should be used in accessor names for the field
+    fn base_name(&self, field_name: &Ident) -> Ident {
+        self.rename_map
+            .iter()
+            .find(|(renamed, _)| renamed == field_name)
+            .map_or_else(|| field_name.clone(), |(_, base_name)| base_name.clone())
+    }
+}
+
+/// Internal struct to store a group of fields from #[common_fields_group(...)]
 #[derive(Clone)]
-struct EnumVariantInfo {
+struct CommonFieldsGroup {
     name: Ident,
-    is_struct: bool,
+    fields: Vec<(Ident, syn::Type)>,
+    mutable: bool, // Whether to generate mutable accessor too
 }
 
-fn parse_enum_variants(enum_info: DataEnum) -> Vec<EnumVariantInfo> {
-    enum_info
-        .variants
-        .into_iter()
-        .map(|variant| EnumVariantInfo {
-            is_struct: match variant.fields {
-                Fields::Named(_) => true,
-                Fields::Unnamed(_) => false,
-                Fields::Unit => panic!(
-                    "Variant {} is a unit variant, which is not supported",
-                    variant.ident
-                ),
-            },
-            name: variant.ident,
+impl syn::parse::Parse for CommonFieldsGroup {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mutable = input.parse::<Token![mut]>().is_ok();
+        let name = input.parse()?;
+        let content;
+        syn::parenthesized!(content in input);
+        let mut fields = Vec::new();
+        while !content.is_empty() {
+            let field_name = content.parse()?;
+            content.parse::<Token![:]>()?;
+            fields.push((field_name, content.parse()?));
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?;
+            }
+        }
+        Ok(Self {
+            name,
+            fields,
+            mutable,
         })
-        .collect()
+    }
 }
 
-/// Macro to generate getters/setters for common fields in an enum.
-/// Meaning, that if every variant of your enum has some field,
-/// you could access it though field(), field_mut() or into_field() accessors respectively.
-///
-/// For example if you have common field `key` of type String you'll use the macro like this:
-/// ```
-/// use enum_common_fields::EnumCommonFields;
-///
-/// #[derive(EnumCommonFields)]
-/// #[common_field(mut key: String)]
-/// enum MyEnum {
-/// #   _V{ key: String }
-///     // Some variants
-/// }
-/// ```
-/// and will have methods `.key()` and `.key_mut()` that return `&String` and `&mut String` respectively.
-/// If you don't want to create `.key_mut()` accessor, you can omit mut in the declaration like this:
-/// ```
-/// # use enum_common_fields::EnumCommonFields;
-/// #[derive(EnumCommonFields)]
-/// #[common_field(key: String)]
-/// enum MyEnum {
-/// #   _V{ key: String }
-///     // Some variants
-/// }
-/// ```
-/// Generated accessor functions contain only match statement on current enum instance
-/// and extraction of the field in each branch.
-///
-/// ### Basic Usage
-/// Add `#[derive(EnumCommonFields)]` above your enum and `#[common_field(field_name: Type)]` after it for every common field you need to generate accessors for:
-/// ```rust
-/// # use enum_common_fields::EnumCommonFields;
-/// struct VariantOne {
-///     key: String
-/// }
-///
-/// #[derive(EnumCommonFields)]
-/// #[common_field(key: String)]
-/// enum MyEnum {
-///     VariantOne(VariantOne),
-///     VariantTwo {
-///         key: String
-///     },
-/// }
-/// let my_enum = MyEnum::VariantOne (VariantOne { key: "Example".into() });
-/// assert_eq!(my_enum.key(), "Example");
-/// ```
-/// You can add `mut` to `common_field` annotation to also generate `<field_name>_mut()` accessor that returns mutable reference and `own` to also add `into_<field_name>()` accessor that consumes original instance:
-/// ```rust
-/// # use enum_common_fields::EnumCommonFields;
-///
-/// struct VariantTwo {
-///     key: String
-/// }
-///
-/// #[derive(EnumCommonFields)]
-/// #[common_field(own key: String)] // Generates read-only, mutable and owning accessors
-/// enum MyEnum {
-///     VariantOne {
-///         key: String
-///     },
-///     VariantTwo(VariantTwo),
-/// }
-///
-/// let mut my_enum = MyEnum::VariantOne { key: "Example".into() };
-/// assert_eq!(my_enum.key(), "Example");
-///
-/// my_enum.key_mut().push_str(" Mutated"); // Mutable access
-/// assert_eq!(my_enum.key(), "Example Mutated");
-///
-/// let key: String = my_enum.into_key(); // Consuming MyEnum instance, and getting owned String instance
-/// assert_eq!(key, "Example Mutated".to_string())
-/// ```
-/// As you can see, both struct variants and tuple variants with a single struct are supported.
-/// Enums with unit variants or multiple things in a tuple variant are not.
-/// ### Modifiers
-/// `common_field` annotation without access modifier generates only immutable accessor.
-/// `mut_only` generates only mutable one, and `own_only` only owning one.
-/// `mut` generates both mutable and immutable accessors, and `own` (and it's alias `all`) generate both of those and also the owning one.
-/// If you need only mutable and owning accessor, or only immutable and owning you'll need to add more than one accessor per field:
-/// ```rust
-/// # use enum_common_fields::EnumCommonFields;
-/// struct VariantOne {
-///     key: String
-/// }
-///
-/// struct VariantTwo {
-///     key: String
-/// }
-///
-/// #[derive(EnumCommonFields)]
-/// #[common_field(key: String)] // Generate only immutable accessor
-/// #[common_field(own_only key: String)] // And only owning accessor
-/// enum MyEnum {
-///     VariantOne(VariantOne),
-///     VariantTwo(VariantTwo),
+/// Internal struct to store a method delegated to a common field from #[common_delegate(...)]
+#[derive(Clone)]
+struct CommonDelegate {
+    name: Ident,
+    receiver: syn::Receiver,
+    arguments: Vec<(Ident, syn::Type)>, // Arguments passed to the method of the field as is
+    output: syn::ReturnType,
+    field_name: Ident,
+}
+
+impl syn::parse::Parse for CommonDelegate {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let content;
+        syn::parenthesized!(content in input);
+        let receiver = content.parse()?;
+        let mut arguments = Vec::new();
+        while !content.is_empty() {
+            content.parse::<Token![,]>()?;
+            if content.is_empty() {
+                break;
+            }
+            let argument_name = content.parse()?;
+            content.parse::<Token![:]>()?;
+            arguments.push((argument_name, content.parse()?));
+        }
+        let output = input.parse()?;
+        let via = input.parse::<Ident>()?;
+        if via != "via" {
+            return Err(syn::Error::new(via.span(), "Expected \"via field_name\""));
+        }
+        Ok(Self {
+            name,
+            receiver,
+            arguments,
+            output,
+            field_name: input.parse()?,
+        })
+    }
+}
+
+/// Internal struct to store parameters from #[common_fields_trait(...)]
+struct CommonFieldsTrait {
+    name: Ident,
+    payloads: bool,    // Implement the trait for payload types of tuple variants too
+    object_safe: bool, // Check that the trait can be used as a trait object
+}
+
+impl syn::parse::Parse for CommonFieldsTrait {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let mut payloads = false;
+        let mut object_safe = false;
+        while input.parse::<Token![,]>().is_ok() && !input.is_empty() {
+            let option = input.parse::<Ident>()?;
+            match option.to_string().as_str() {
+                "payloads" => payloads = true,
+                "object_safe" => object_safe = true,
+                _ => {
+                    return Err(syn::Error::new(
+                        option.span(),
+                        "Unknown common_fields_trait option",
+                    ))
+                }
+            }
+        }
+        Ok(Self {
+            name,
+            payloads,
+            object_safe,
+        })
+    }
+}
+
+/// Internal struct to store a tuple element shared by all variants from #[common_tuple(...)]
+#[derive(Clone)]
+struct CommonTuple {
+    index: usize,
+    name: Ident,
+    element_type: syn::Type,
+}
+
+impl syn::parse::Parse for CommonTuple {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let index = input.parse::<syn::LitInt>()?.base10_parse()?;
+        input.parse::<Token![as]>()?;
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        Ok(Self {
+            index,
+            name,
+            element_type: input.parse()?,
+        })
+    }
+}
+
+/// Internal struct to store an enum from other crate and its common fields, from `enum_common_fields!` macro
+struct ForeignEnum {
+    vis: syn::Visibility,
+    path: syn::Path,
+    trait_name: Ident, // Name of the generated extension trait, from "as TraitName" syntax or `<EnumName>CommonFields`
+    fields: Vec<ForeignField>,
+}
+
+impl syn::parse::Parse for ForeignEnum {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let vis = input.parse()?;
+        input.parse::<Token![for]>()?;
+        let path: syn::Path = input.parse()?;
+        let trait_name = if input.parse::<Token![as]>().is_ok() {
+            input.parse()?
+        } else {
+            format_ident!("{}CommonFields", path.segments.last().unwrap().ident)
+        };
+        let content;
+        syn::braced!(content in input);
+        let fields =
+            syn::punctuated::Punctuated::<ForeignField, Token![;]>::parse_terminated(&content)?
+                .into_iter()
+                .collect();
+        Ok(Self {
+            vis,
+            path,
+            trait_name,
+            fields,
+        })
+    }
+}
+
+/// Internal struct to store a common field of a foreign enum, like `mut key: String in Created { key }, Deleted(d => d.key)`
+struct ForeignField {
+    mutable: bool,
+    field_name: Ident,
+    field_type: syn::Type,
+    branches: Vec<ForeignBranch>,
+}
+
+impl syn::parse::Parse for ForeignField {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mutable = input.parse::<Token![mut]>().is_ok();
+        let field_name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let field_type = input.parse()?;
+        input.parse::<Token![in]>()?;
+        let mut branches = vec![input.parse::<ForeignBranch>()?];
+        while input.parse::<Token![,]>().is_ok() && !input.is_empty() && !input.peek(Token![;]) {
+            branches.push(input.parse()?);
+        }
+        Ok(Self {
+            mutable,
+            field_name,
+            field_type,
+            branches,
+        })
+    }
+}
+
+/// Internal struct to store the way to get a common field from a variant of a foreign enum
+struct ForeignBranch {
+    variant: Ident,
+    binding: Ident, // Field of a struct variant, or binding of the first element of a tuple variant
+    expression: Option<syn::Expr>, // Expression with the binding, that evaluates to the field of a tuple variant payload
+}
+
+impl syn::parse::Parse for ForeignBranch {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let variant = input.parse()?;
+        let content;
+        if input.peek(syn::token::Brace) {
+            syn::braced!(content in input);
+            Ok(Self {
+                variant,
+                binding: content.parse()?,
+                expression: None,
+            })
+        } else {
+            syn::parenthesized!(content in input);
+            let binding = content.parse()?;
+            content.parse::<Token![=>]>()?;
+            Ok(Self {
+                variant,
+                binding,
+                expression: Some(content.parse()?),
+            })
+        }
+    }
+}
+
+/// Internal struct to store an enum definition with hoisted common fields, from `common_fields_enum!` macro
+struct HoistedEnum {
+    attrs: Vec<syn::Attribute>,
+    vis: syn::Visibility,
+    name: Ident,
+    common_fields: Vec<HoistedField>,
+    variants: Vec<syn::Variant>,
+}
+
+impl syn::parse::Parse for HoistedEnum {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![enum]>()?;
+        let name = input.parse()?;
+        let content;
+        syn::braced!(content in input);
+        let common = content.parse::<Ident>()?;
+        if common != "common" {
+            return Err(syn::Error::new(
+                common.span(),
+                "Expected common { ... } block before the variants",
+            ));
+        }
+        let common_content;
+        syn::braced!(common_content in content);
+        let common_fields =
+            syn::punctuated::Punctuated::<HoistedField, Token![,]>::parse_terminated(
+                &common_content,
+            )?
+            .into_iter()
+            .collect();
+        let variants =
+            syn::punctuated::Punctuated::<syn::Variant, Token![,]>::parse_terminated(&content)?
+                .into_iter()
+                .collect();
+        Ok(Self {
+            attrs,
+            vis,
+            name,
+            common_fields,
+            variants,
+        })
+    }
+}
+
+/// Internal struct to store arguments of `#[with_common_fields(...)]` attribute
+struct HoistedArguments {
+    common_fields: Vec<HoistedField>, // Fields from "fields(...)" argument, added to every variant
+    config: Vec<proc_macro2::TokenStream>, // Other arguments, passed to #[common_fields(...)] as they are
+}
+
+impl syn::parse::Parse for HoistedArguments {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut common_fields = Vec::new();
+        let mut config = Vec::new();
+        while !input.is_empty() {
+            if input.peek(syn::Ident)
+                && input.peek2(syn::token::Paren)
+                && input.fork().parse::<Ident>()? == "fields"
+            {
+                input.parse::<Ident>()?;
+                let content;
+                syn::parenthesized!(content in input);
+                common_fields.extend(
+                    syn::punctuated::Punctuated::<HoistedField, Token![,]>::parse_terminated(
+                        &content,
+                    )?,
+                );
+            } else {
+                let mut option = proc_macro2::TokenStream::new();
+                while !input.is_empty() && !input.peek(Token![,]) {
+                    option.extend([input.parse::<proc_macro2::TokenTree>()?]);
+                }
+                config.push(option);
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(Self {
+            common_fields,
+            config,
+        })
+    }
+}
+
+/// Internal struct to store a field of `common { ... }` block, like `mut + own id: Uuid`
+struct HoistedField {
+    modifiers: proc_macro2::TokenStream, // Modifiers of the #[common_field] annotation, as they are written
+    field_name: Ident,
+    field_type: syn::Type,
+}
+
+impl syn::parse::Parse for HoistedField {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Field name is the last token before the colon, and modifiers are checked by the derive
+        let mut tokens = Vec::new();
+        while !input.peek(Token![:]) || input.peek(Token![::]) {
+            tokens.push(input.parse::<proc_macro2::TokenTree>()?);
+        }
+        let field_name = match tokens.pop() {
+            Some(proc_macro2::TokenTree::Ident(field_name)) => field_name,
+            _ => return Err(input.error("Expected field name")),
+        };
+        input.parse::<Token![:]>()?;
+        Ok(Self {
+            modifiers: tokens.into_iter().collect(),
+            field_name,
+            field_type: input.parse()?,
+        })
+    }
+}
+
+#[derive(Clone)]
+struct EnumVariantInfo {
+    name: Ident,
+    is_struct: bool,
+    is_unit: bool,
+    fields: Vec<Ident>, // Names of all fields of a struct variant, empty for tuple and unit variants
+    cfgs: Vec<syn::Attribute>, // #[cfg(...)] attributes of the variant, copied to every match branch for it
+    skipped_fields: Vec<Ident>, // Fields from #[common_field_skip(...)], that the variant is excluded from
+    renamed_fields: Vec<(Ident, Ident)>, // Pairs of common field name and the name of the field in this variant
+    overridden_fields: Vec<(Ident, syn::Expr)>, // Expressions from #[common_field_via(...)], used instead of field access
+    default_fields: Vec<(Ident, syn::Expr)>, // Values from #[common_field_default(...)], used instead of the default option
+    tuple_len: usize,                        // Number of elements of a tuple variant
+    field_types: Vec<syn::Type>, // Types of fields of a struct variant or elements of a tuple variant, in order of declaration
+    field_docs: Vec<Vec<syn::Attribute>>, // Doc comments of fields of a struct variant, in order of declaration
+    tuple_index: usize, // Index of the element of a tuple variant, that holds the fields
+    delegated: bool, // Whether accessors call accessors of the inner type, from #[common_field(delegate)]
+}
+
+impl EnumVariantInfo {
+    /// Pattern that matches the variant whatever its fields are
+    fn pattern(&self) -> proc_macro2::TokenStream {
+        let name = &self.name;
+        if self.is_struct {
+            quote!(Self::#name { .. })
+        } else if self.is_unit {
+            quote!(Self::#name)
+        } else {
+            quote!(Self::#name(..))
+        }
+    }
+
+    /// Pattern of tuple variant, that binds the element holding the fields with `binding`
+    fn tuple_pattern(&self, binding: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let name = &self.name;
+        if self.tuple_len == 1 {
+            quote!(Self::#name(#binding))
+        } else {
+            let skipped = (0..self.tuple_index).map(|_| quote!(_));
+            quote!(Self::#name(#(#skipped,)* #binding, ..))
+        }
+    }
+
+    /// Payloads of tuple variants are not visible to the macro, so they are assumed to have every field
+    fn has_field(&self, field_name: &Ident) -> bool {
+        if self.skipped_fields.contains(field_name) {
+            false
+        } else if self.field_override(field_name).is_some() {
+            true
+        } else if self.is_struct {
+            self.fields.contains(self.field_ident(field_name))
+        } else {
+            !self.is_unit
+        }
+    }
+
+    /// Name of the field that holds the common field in this variant, renamed by #[common_field_rename(...)]
+    fn field_ident<'a>(&'a self, field_name: &'a Ident) -> &'a Ident {
+        self.renamed_fields
+            .iter()
+            .find(|(common_name, _)| common_name == field_name)
+            .map_or(field_name, |(_, variant_name)| variant_name)
+    }
+
+    /// Doc comment of the field in this variant, empty if the variant doesn't have the field or it isn't documented
+    fn field_doc(&self, field_name: &Ident) -> &[syn::Attribute] {
+        let field_ident = self.field_ident(field_name);
+        self.fields
+            .iter()
+            .position(|field| field == field_ident)
+            .map_or(&[], |index| &self.field_docs[index])
+    }
+
+    /// Expression from #[common_field_via(...)], that the variant uses instead of the field
+    fn field_override(&self, field_name: &Ident) -> Option<&syn::Expr> {
+        self.overridden_fields
+            .iter()
+            .find(|(common_name, _)| common_name == field_name)
+            .map(|(_, expression)| expression)
+    }
+
+    /// Value from #[common_field_default(...)], that the variant returns if it doesn't have the field
+    fn field_default(&self, field_name: &Ident) -> Option<&syn::Expr> {
+        self.default_fields
+            .iter()
+            .find(|(common_name, _)| common_name == field_name)
+            .map(|(_, value)| value)
+    }
+
+    /// Panics if the variant overrides the field, for generators that need to move or rebuild the field itself
+    fn assert_not_overridden(&self, field_name: &Ident) {
+        if self.field_override(field_name).is_some() {
+            panic!(
+                "Variant {} overrides field {field_name} with #[common_field_via], which is not supported by map and pin accessors, proptest, arbitrary and split options",
+                self.name
+            )
+        }
+    }
+
+    /// Part of struct variant pattern, that binds the field to a variable with the name of the common field
+    fn field_binding(&self, field_name: &Ident) -> proc_macro2::TokenStream {
+        let variant_name = self.field_ident(field_name);
+        if variant_name == field_name {
+            quote!(#field_name)
+        } else {
+            quote!(#variant_name: #field_name)
+        }
+    }
+
+    /// Pattern that binds the fields to variables with the names of common fields,
+    /// and expressions to access them in the match branch (with `ref_token` applied to fields of tuple variant payload)
+    fn bind_fields(
+        &self,
+        field_names: &[&Ident],
+        ref_token: &proc_macro2::TokenStream,
+    ) -> (proc_macro2::TokenStream, Vec<proc_macro2::TokenStream>) {
+        let name = &self.name;
+        let overridden = |field_name: &Ident| {
+            self.field_override(field_name)
+                .map(|expression| quote!(#ref_token (#expression)))
+        };
+        if self.is_struct
+            && field_names
+                .iter()
+                .any(|field_name| self.field_override(field_name).is_some())
+        {
+            // Overriding expressions can use any field of the variant, so all of them are bound by their names
+            let fields = &self.fields;
+            (
+                quote!(#[allow(unused_variables)] Self::#name{#(#fields),*}),
+                field_names
+                    .iter()
+                    .map(|field_name| {
+                        overridden(field_name).unwrap_or_else(|| {
+                            let variant_name = self.field_ident(field_name);
+                            quote!(#variant_name)
+                        })
+                    })
+                    .collect(),
+            )
+        } else if self.is_struct {
+            let bindings = field_names
+                .iter()
+                .map(|field_name| self.field_binding(field_name));
+            (
+                quote!(Self::#name{#(#bindings,)* ..}),
+                field_names
+                    .iter()
+                    .map(|field_name| quote!(#field_name))
+                    .collect(),
+            )
+        } else {
+            (
+                self.tuple_pattern(quote!(v)),
+                field_names
+                    .iter()
+                    .map(|field_name| {
+                        overridden(field_name).unwrap_or_else(|| {
+                            let variant_name = self.field_ident(field_name);
+                            quote!(#ref_token v.#variant_name)
+                        })
+                    })
+                    .collect(),
+            )
+        }
+    }
+
+    /// Pattern of tuple variant, that binds element with given index with `binding`
+    fn element_pattern(&self, index: usize, binding: &Ident) -> proc_macro2::TokenStream {
+        if self.is_struct || self.is_unit {
+            panic!(
+                "Variant {} is not a tuple variant, which is not supported by #[common_tuple]",
+                self.name
+            )
+        }
+        if index >= self.tuple_len {
+            panic!(
+                "#[common_tuple({index} as {binding}: ...)] doesn't point to an element of variant {}",
+                self.name
+            )
+        }
+        let name = &self.name;
+        let skipped = (0..index).map(|_| quote!(_));
+        quote!(Self::#name(#(#skipped,)* #binding, ..))
+    }
+
+    /// Panics if the variant is a unit variant or skips one of the fields, for generators that need the fields in every variant
+    fn assert_has_fields(&self, field_names: &[&Ident]) {
+        if self.is_unit {
+            panic!(
+                "Variant {} is a unit variant, which is not supported",
+                self.name
+            )
+        }
+        if self.delegated {
+            panic!(
+                "Variant {} delegates to the inner type with #[common_field(delegate)], which is supported only by accessors",
+                self.name
+            )
+        }
+        if let Some(field_name) = field_names
+            .iter()
+            .find(|field_name| self.skipped_fields.contains(field_name))
+        {
+            panic!(
+                "Variant {} skips field {field_name}, which is supported only by accessors that return Option, Result or the default value",
+                self.name
+            )
+        }
+    }
+}
+
+/// `common_name = variant_name` pair from #[common_field_rename(...)]
+struct FieldRename(Ident, Ident);
+
+impl syn::parse::Parse for FieldRename {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let common_name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        Ok(Self(common_name, input.parse()?))
+    }
+}
+
+/// `common_name = expression` pair from #[common_field_via(...)] or #[common_field_default(...)]
+struct FieldExpression(Ident, syn::Expr);
+
+impl syn::parse::Parse for FieldExpression {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let common_name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        Ok(Self(common_name, input.parse()?))
+    }
+}
+
+/// Generates match branch for every variant, under the same `#[cfg(...)]` attributes as the variant itself,
+/// so variants with payload types that exist only under some feature don't break the match
+fn variant_branches(
+    variants: &[EnumVariantInfo],
+    mut branch: impl FnMut(&EnumVariantInfo) -> proc_macro2::TokenStream,
+) -> Vec<proc_macro2::TokenStream> {
+    variants
+        .iter()
+        .map(|variant| {
+            let cfgs = &variant.cfgs;
+            let branch = branch(variant);
+            quote!(#(#cfgs)* #branch)
+        })
+        .collect()
+}
+
+/// Match over the variants, that works for enums without variants too, where a reference can't be matched with no branches
+fn match_variants(
+    scrutinee: proc_macro2::TokenStream,
+    match_branches: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    if match_branches.is_empty() {
+        // Borrow makes a reference to Self out of both Self and references to it
+        quote!(match *::core::borrow::Borrow::<Self>::borrow(&#scrutinee) {})
+    } else {
+        quote!(match #scrutinee { #(#match_branches,)* })
+    }
+}
+
+fn parse_enum_variants(enum_info: DataEnum) -> syn::Result<Vec<EnumVariantInfo>> {
+    enum_info
+        .variants
+        .into_iter()
+        .map(|variant| {
+            let mut delegated = false;
+            for attr in &variant.attrs {
+                if !attr.path().is_ident("common_field") {
+                    continue;
+                }
+                let option = attr.parse_args::<Ident>()?;
+                if option != "delegate" {
+                    return Err(syn::Error::new(
+                        option.span(),
+                        "Expected format of variant annotation: #[common_field(delegate)]",
+                    ));
+                }
+                if !matches!(variant.fields, Fields::Unnamed(_)) {
+                    return Err(syn::Error::new(
+                        variant.ident.span(),
+                        format!(
+                            "Variant {} is not a tuple variant, so it can't delegate to the inner type",
+                            variant.ident
+                        ),
+                    ));
+                }
+                delegated = true;
+            }
+            Ok(EnumVariantInfo {
+                tuple_len: match &variant.fields {
+                    Fields::Unnamed(fields) => fields.unnamed.len(),
+                    _ => 0,
+                },
+                field_types: variant
+                    .fields
+                    .iter()
+                    .map(|field| field.ty.clone())
+                    .collect(),
+                field_docs: variant
+                    .fields
+                    .iter()
+                    .filter(|field| field.ident.is_some())
+                    .map(|field| {
+                        field
+                            .attrs
+                            .iter()
+                            .filter(|attr| attr.path().is_ident("doc"))
+                            .cloned()
+                            .collect()
+                    })
+                    .collect(),
+                tuple_index: variant
+                    .attrs
+                    .iter()
+                    .find(|attr| attr.path().is_ident("common_field_at"))
+                    .map_or(Ok(0), |attr| {
+                        let index = attr
+                            .parse_args::<syn::LitInt>()
+                            .and_then(|index| index.base10_parse())?;
+                        if !matches!(&variant.fields, Fields::Unnamed(fields) if index < fields.unnamed.len()) {
+                            return Err(syn::Error::new_spanned(
+                                attr,
+                                format!(
+                                    "#[common_field_at({index})] of variant {} doesn't point to an element of the tuple variant",
+                                    variant.ident
+                                ),
+                            ));
+                        }
+                        Ok(index)
+                    })?,
+                delegated,
+                is_struct: matches!(variant.fields, Fields::Named(_)),
+                is_unit: matches!(variant.fields, Fields::Unit),
+                fields: variant
+                    .fields
+                    .iter()
+                    .filter_map(|field| field.ident.clone())
+                    .collect(),
+                skipped_fields: variant
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path().is_ident("common_field_skip"))
+                    .map(|attr| {
+                        attr.parse_args_with(
+                            syn::punctuated::Punctuated::<Ident, Token![,]>::parse_terminated,
+                        )
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?
+                    .into_iter()
+                    .flatten()
+                    .collect(),
+                overridden_fields: variant
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path().is_ident("common_field_via"))
+                    .map(|attr| {
+                        attr.parse_args_with(
+                            syn::punctuated::Punctuated::<FieldExpression, Token![,]>::parse_terminated,
+                        )
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?
+                    .into_iter()
+                    .flatten()
+                    .map(|FieldExpression(common_name, expression)| (common_name, expression))
+                    .collect(),
+                default_fields: variant
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path().is_ident("common_field_default"))
+                    .map(|attr| {
+                        attr.parse_args_with(
+                            syn::punctuated::Punctuated::<FieldExpression, Token![,]>::parse_terminated,
+                        )
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?
+                    .into_iter()
+                    .flatten()
+                    .map(|FieldExpression(common_name, expression)| (common_name, expression))
+                    .collect(),
+                renamed_fields: variant
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path().is_ident("common_field_rename"))
+                    .map(|attr| {
+                        attr.parse_args_with(
+                            syn::punctuated::Punctuated::<FieldRename, Token![,]>::parse_terminated,
+                        )
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?
+                    .into_iter()
+                    .flatten()
+                    .map(|FieldRename(common_name, variant_name)| (common_name, variant_name))
+                    .collect(),
+                cfgs: variant
+                    .attrs
+                    .into_iter()
+                    .filter(|attr| attr.path().is_ident("cfg"))
+                    .collect(),
+                name: variant.ident,
+            })
+        })
+        .collect()
+}
+
+/// Macro to generate getters/setters for common fields in an enum.
+/// Meaning, that if every variant of your enum has some field,
+/// you could access it though field(), field_mut() or into_field() accessors respectively.
+///
+/// For example if you have common field `key` of type String you'll use the macro like this:
+/// ```
+/// use enum_common_fields::EnumCommonFields;
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String)]
+/// enum MyEnum {
+/// #   _V{ key: String }
+///     // Some variants
 /// }
 /// ```
-/// ### Types
-/// Type in the `#[common_field]` annotation is used only as a return type of the accessor.
-/// So you if you generate only reference accessors (or you generate owning accessor in a different annotation)
-/// you can use type that `Deref`s from the original field type instead of it itself.
-/// Classic example is using `str` instead of `String` for reference accessors:
-/// ```rust
+/// and will have methods `.key()` and `.key_mut()` that return `&String` and `&mut String` respectively.
+/// If you don't want to create `.key_mut()` accessor, you can omit mut in the declaration like this:
+/// ```
 /// # use enum_common_fields::EnumCommonFields;
 /// #[derive(EnumCommonFields)]
-/// #[common_field(mut key: str)]
-/// #[common_field(own_only key: String)]
+/// #[common_field(key: String)]
 /// enum MyEnum {
-///     One { key: String }
+/// #   _V{ key: String }
+///     // Some variants
 /// }
-/// let mut e = MyEnum::One { key: "k".to_string() };
-/// let key_ref = e.key(); // returns "k" as &str instead or &String
-/// let key_mut_ref = e.key_mut(); // returns "k" as &mut str instead or &mut String
-/// let key = e.into_key(); // consumes e and returns "k" as actual String
 /// ```
-/// ### Renaming
-/// You can use `as getter_name` in the `common_field` annotation to rename generated function name. You can use `as` only in `common_field` annotations with modifiers that generate only one accessor (`own_only`/`mut_only`/no modifier). If you need to rename more than one accessor for one field you once more will need to add more than one annotation per field:
+/// Generated accessor functions contain only match statement on current enum instance
+/// and extraction of the field in each branch.
+///
+/// ### Basic Usage
+/// Add `#[derive(EnumCommonFields)]` above your enum and `#[common_field(field_name: Type)]` after it for every common field you need to generate accessors for:
 /// ```rust
 /// # use enum_common_fields::EnumCommonFields;
 /// struct VariantOne {
 ///     key: String
 /// }
 ///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// enum MyEnum {
+///     VariantOne(VariantOne),
+///     VariantTwo {
+///         key: String
+///     },
+/// }
+/// let my_enum = MyEnum::VariantOne (VariantOne { key: "Example".into() });
+/// assert_eq!(my_enum.key(), "Example");
+/// ```
+/// You can add `mut` to `common_field` annotation to also generate `<field_name>_mut()` accessor that returns mutable reference and `own` to also add `into_<field_name>()` accessor that consumes original instance:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+///
 /// struct VariantTwo {
 ///     key: String
 /// }
 ///
 /// #[derive(EnumCommonFields)]
-/// #[common_field(key as k: String)]
-/// #[common_field(mut_only key as k_mut: String)]
-/// #[common_field(own_only key as into_k: String)]
+/// #[common_field(own key: String)] // Generates read-only, mutable and owning accessors
 /// enum MyEnum {
-///     VariantOne(VariantOne),
+///     VariantOne {
+///         key: String
+///     },
 ///     VariantTwo(VariantTwo),
 /// }
 ///
-/// let mut my_enum = MyEnum::VariantOne(VariantOne { key: "Example".into() });
-/// assert_eq!(my_enum.k(), "Example");
+/// let mut my_enum = MyEnum::VariantOne { key: "Example".into() };
+/// assert_eq!(my_enum.key(), "Example");
 ///
-/// my_enum.k_mut().push_str(" Mutated"); // Mutable access
-/// assert_eq!(my_enum.k(), "Example Mutated");
+/// my_enum.key_mut().push_str(" Mutated"); // Mutable access
+/// assert_eq!(my_enum.key(), "Example Mutated");
 ///
-/// let key: String = my_enum.into_k(); // Consuming MyEnum instance, and getting owned String instance
+/// let key: String = my_enum.into_key(); // Consuming MyEnum instance, and getting owned String instance
 /// assert_eq!(key, "Example Mutated".to_string())
 /// ```
-/// If you want, you can generate multiple accessors with different names for the same field:
+/// As you can see, both struct variants and tuple variants with a struct are supported.
+/// ### Tuple variants
+/// Tuple variants can have more than one element. The fields are looked up in the first one, unless
+/// the variant is marked with `#[common_field_at(index)]`:
 /// ```rust
 /// # use enum_common_fields::EnumCommonFields;
+/// struct Meta { key: String }
+///
 /// #[derive(EnumCommonFields)]
-/// #[common_field(key: String)] // Generates accessor named key()
-/// #[common_field(key as k: String)] // Generates accessor named k()
-/// #[common_field(key as get_key: String)] // Generates accessor named get_key()
+/// #[common_field(mut key: String)]
 /// enum MyEnum {
-///     VariantOne { key: String, /* other fields */ },
-///     VariantTwo { key: String, /* other fields */ },
+///     First(Meta, u32),
+///     #[common_field_at(1)]
+///     Second(u32, Meta),
 /// }
+///
+/// let my_enum = MyEnum::Second(0, Meta { key: "key".to_string() });
+/// assert_eq!(my_enum.key(), "key");
 /// ```
-#[proc_macro_derive(EnumCommonFields, attributes(common_field))]
-pub fn common_fields_derive(input: TokenStream) -> TokenStream {
-    let ast = parse_macro_input!(input as syn::DeriveInput);
-
-    let common_fields = parse_common_fields_attributes(&ast);
+/// ### Newtype variants
+/// If the common field is the whole element of tuple variants, rather than a field of it,
+/// `#[common_tuple(index as name: Type)]` generates `name()`, `name_mut()` and `into_name()` accessors of the element:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_tuple(0 as id: u64)]
+/// enum Id {
+///     User(u64),
+///     Org(u64, String),
+/// }
+///
+/// let mut id = Id::Org(1, "org".to_string());
+/// *id.id_mut() += 1;
+/// assert_eq!(id.id(), &2);
+/// assert_eq!(id.into_id(), 2);
+/// ```
+/// ### Modifiers
+/// `common_field` annotation without access modifier generates only immutable accessor.
+/// `mut_only` generates only mutable one, and `own_only` only owning one.
+/// `mut` generates both mutable and immutable accessors, and `own` (and it's alias `all`) generate both of those and also the owning one.
+/// If you need only mutable and owning accessor, or only immutable and owning, join modifiers with `+`
+/// (`ref` stands for the immutable accessor), or add more than one annotation per field:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// struct VariantOne {
+///     key: String,
+///     value: String,
+/// }
+///
+/// struct VariantTwo {
+///     key: String,
+///     value: String,
+/// }
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(ref + own_only key: String)] // Generate only immutable and owning accessors
+/// #[common_field(value: str)] // Same, but the immutable accessor returns &str
+/// #[common_field(own_only value: String)]
+/// enum MyEnum {
+///     VariantOne(VariantOne),
+///     VariantTwo(VariantTwo),
+/// }
+/// ```
+/// Any modifiers can be joined this way, as long as the same type works for all of them.
+/// `replace` generates only `replace_<field_name>()` accessor, that puts a new value into the field
+/// and returns the previous one (like `std::mem::replace`), without consuming the enum instance:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_field(replace key: String)]
+/// enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String },
+/// }
+///
+/// let mut my_enum = MyEnum::VariantOne { key: "Old".into() };
+/// let old_key = my_enum.replace_key("New".into());
+/// assert_eq!(old_key, "Old");
+/// assert_eq!(my_enum.key(), "New");
+/// ```
+/// `take_into` generates only `take_<field_name>_into(slot)` accessor, that swaps the field with the value in `slot`.
+/// It lets you move the value out into a buffer you already own and put that buffer's old content in its place,
+/// so hot loops can reuse allocations instead of creating a new value for every `replace`:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_field(take_into key: String)]
+/// enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String },
+/// }
+///
+/// let mut my_enum = MyEnum::VariantOne { key: "Parsed".into() };
+/// let mut buffer = String::with_capacity(64);
+/// my_enum.take_key_into(&mut buffer);
+/// assert_eq!(buffer, "Parsed");
+/// assert_eq!(my_enum.key(), "");
+/// ```
+/// `map` generates only `map_<field_name>(f)` accessor, that consumes the enum instance and returns the same variant
+/// with the field transformed by `f`:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_field(map key: String)]
+/// enum MyEnum {
+///     VariantOne { key: String, value: u32 },
+///     VariantTwo { key: String },
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { key: "key".into(), value: 42 };
+/// let my_enum = my_enum.map_key(|key| key.to_uppercase());
+/// assert!(matches!(my_enum, MyEnum::VariantOne { value: 42, .. }));
+/// assert_eq!(my_enum.key(), "KEY");
+/// ```
+/// Payloads of tuple variants are updated in place, so their type must not implement `Drop`.
+///
+/// `update` generates only `update_<field_name>(f)` accessor, that calls `f` with a mutable reference to the field:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(counter: u32)]
+/// #[common_field(update counter: u32)]
+/// enum MyEnum {
+///     VariantOne { counter: u32 },
+///     VariantTwo { counter: u32 },
+/// }
+///
+/// let mut my_enum = MyEnum::VariantOne { counter: 1 };
+/// my_enum.update_counter(|counter| *counter += 1);
+/// assert_eq!(*my_enum.counter(), 2);
+/// ```
+///
+/// `clone` generates only `<field_name>()` accessor, that returns a clone of the field instead of a reference to it.
+/// It's handy for cheaply clonable fields like `Arc`:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// # use std::sync::Arc;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(clone config: Arc<str>)]
+/// enum MyEnum {
+///     VariantOne { config: Arc<str> },
+///     VariantTwo { config: Arc<str> },
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { config: "config".into() };
+/// let config: Arc<str> = my_enum.config();
+/// assert_eq!(Arc::strong_count(&config), 2);
+/// ```
+///
+/// `get_or_clone` generates `<field_name>()` accessor, and `<field_name>_owned()` accessor that returns a clone of the field.
+/// It saves writing `.key().clone()` where the borrow doesn't live long enough, like across `.await` points.
+/// Since the clone is of the annotation type, it has to be the actual type of the field (or another `Clone` type it `Deref`s to):
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(get_or_clone key: String)]
+/// enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String },
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { key: "key".to_string() };
+/// assert_eq!(my_enum.key(), "key");
+/// let key: String = my_enum.key_owned();
+/// drop(my_enum);
+/// assert_eq!(key, "key");
+/// ```
+///
+/// `copy` generates only `<field_name>()` accessor, that returns a copy of the field. It's less noisy than getting `&u64` or `&bool` for `Copy` fields:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(copy id: u64)]
+/// enum MyEnum {
+///     VariantOne { id: u64 },
+///     VariantTwo { id: u64 },
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { id: 42 };
+/// assert_eq!(my_enum.id(), 42);
+/// ```
+///
+/// `cow` generates only `<field_name>()` accessor, that returns `Cow<'_, Type>` borrowing the field.
+/// This way variants can later switch between borrowed and owned storage without changing the accessor signature:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// # use std::borrow::Cow;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(cow name: str)]
+/// enum MyEnum {
+///     VariantOne { name: String },
+///     VariantTwo { name: String },
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { name: "name".into() };
+/// let name: Cow<'_, str> = my_enum.name();
+/// assert_eq!(name, "name");
+/// ```
+///
+/// `arc` is meant for `Arc<Type>` fields and generates only `<field_name>_arc()` accessor, that returns a new `Arc<Type>` pointing to the same value.
+/// Note that the annotation contains type that `Arc` points to, not the `Arc` itself:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// # use std::sync::Arc;
+/// struct Config {
+///     name: String,
+/// }
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(arc config: Config)]
+/// enum MyEnum {
+///     VariantOne { config: Arc<Config> },
+///     VariantTwo { config: Arc<Config> },
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { config: Arc::new(Config { name: "config".into() }) };
+/// let config: Arc<Config> = my_enum.config_arc(); // Can be moved into a spawned thread or task
+/// std::thread::spawn(move || assert_eq!(config.name, "config")).join().unwrap();
+/// ```
+///
+/// `as_deref` is meant for `Option<FieldType>` fields and generates only `<field_name>()` accessor returning `Option<&Type>`.
+/// Like with read-only accessor, `Type` can be either `FieldType` itself, or a type it `Deref`s to, so for `Option<String>`
+/// field you'll get `Option<&str>` (like from `Option::as_deref`) instead of `&Option<String>`:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(as_deref comment: str)]
+/// #[common_field(as_deref priority: u8)]
+/// enum MyEnum {
+///     VariantOne { comment: Option<String>, priority: Option<u8> },
+///     VariantTwo { comment: Option<String>, priority: Option<u8> },
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { comment: Some("comment".into()), priority: None };
+/// assert_eq!(my_enum.comment(), Some("comment"));
+/// assert_eq!(my_enum.priority(), None);
+/// ```
+///
+/// `as_ref` generates only `<field_name>()` accessor, that returns `&Type` using `AsRef<Type>` implementation of the field,
+/// for conversions that `Deref` doesn't cover:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// # use std::path::{Path, PathBuf};
+/// #[derive(EnumCommonFields)]
+/// #[common_field(as_ref path: Path)]
+/// enum MyEnum {
+///     VariantOne { path: String },
+///     VariantTwo { path: PathBuf },
+/// }
+///
+/// assert_eq!(MyEnum::VariantOne { path: "/tmp".into() }.path(), Path::new("/tmp"));
+/// ```
+///
+/// `deref` doesn't generate an accessor, but implements `Deref<Target = Type>` for the enum instead,
+/// so methods of the field can be called on the enum directly. It suits wrapper-like enums, where the field is what the enum is about:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(deref text: String)]
+/// enum Message {
+///     Incoming { text: String, from: u32 },
+///     Outgoing { text: String, to: u32 },
+/// }
+///
+/// let message = Message::Incoming { text: "hello".to_string(), from: 1 };
+/// assert_eq!(message.len(), 5);
+/// assert!(message.starts_with("he"));
+/// ```
+/// If the field has a mutable accessor too, `DerefMut` is implemented as well:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(deref + mut_only items: Vec<u32>)]
+/// enum MyEnum {
+///     VariantOne { items: Vec<u32> },
+///     VariantTwo { items: Vec<u32>, total: u32 },
+/// }
+///
+/// let mut my_enum = MyEnum::VariantOne { items: vec![2, 1] };
+/// my_enum.sort();
+/// assert_eq!(*my_enum, [1, 2]);
+/// ```
+///
+/// `expect("message")` is meant for `Option<FieldType>` fields that are always `Some` after construction.
+/// It generates only `<field_name>()` accessor returning `&Type`, that panics with the message when the field is `None`.
+/// Like with `as_deref`, `Type` can be a type that `FieldType` `Deref`s to:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// struct Session {
+///     user: String,
+/// }
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(expect("session must be set") session: Session)]
+/// enum MyEnum {
+///     VariantOne { session: Option<Session> },
+///     VariantTwo { session: Option<Session> },
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { session: Some(Session { user: "user".into() }) };
+/// assert_eq!(my_enum.session().user, "user");
+/// ```
+///
+/// `cell` is meant for `Cell<Type>` fields and generates only `<field_name>()` accessor, that returns a copy of the value in the cell
+/// (using `Cell::get`), instead of a reference to the cell itself. Like with `arc`, the annotation contains the type of the value:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// # use std::cell::Cell;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(cell hits: u32)]
+/// #[common_field(hits as hits_cell: Cell<u32>)]
+/// enum MyEnum {
+///     VariantOne { hits: Cell<u32> },
+///     VariantTwo { hits: Cell<u32> },
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { hits: Cell::new(0) };
+/// my_enum.hits_cell().set(my_enum.hits() + 1);
+/// assert_eq!(my_enum.hits(), 1);
+/// ```
+///
+/// `atomic` is meant for fields of atomic types like `AtomicU64` and generates `<field_name>(order)` accessor, that loads
+/// the value, and `set_<field_name>(value, order)` accessor, that stores it. The annotation contains the type of the value:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// # use std::sync::atomic::{AtomicU64, Ordering};
+/// #[derive(EnumCommonFields)]
+/// #[common_field(atomic hits: u64)]
+/// enum MyEnum {
+///     VariantOne { hits: AtomicU64 },
+///     VariantTwo { hits: AtomicU64 },
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { hits: AtomicU64::new(0) };
+/// my_enum.set_hits(my_enum.hits(Ordering::Relaxed) + 1, Ordering::Relaxed);
+/// assert_eq!(my_enum.hits(Ordering::Relaxed), 1);
+/// ```
+///
+/// `try_own` generates only `try_into_<field_name>()` accessor, that returns `Ok` with the field if current variant has it,
+/// and gives the enum back in `Err` otherwise. Unlike other modifiers, it works with unit variants and with struct variants
+/// that don't have the field (payloads of tuple variants are always expected to have it):
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(Debug, PartialEq, EnumCommonFields)]
+/// #[common_field(try_own key: String)]
+/// enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { other: u32 },
+///     Empty,
+/// }
+///
+/// assert_eq!(MyEnum::VariantOne { key: "a".into() }.try_into_key(), Ok("a".to_string()));
+/// assert_eq!(MyEnum::Empty.try_into_key(), Err(MyEnum::Empty));
+/// ```
+///
+/// `result` generates only `<field_name>()` accessor, that returns `Result<&Type, MyEnumMissingFieldError>`. Like `try_own`, it works
+/// with variants that don't have the field, returning an error that holds names of the field and the variant. The error type
+/// implements `Display` and `Error`, and is generated next to the enum with the same visibility:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(result key: str)]
+/// enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { other: u32 },
+/// }
+///
+/// assert_eq!(MyEnum::VariantOne { key: "a".into() }.key(), Ok("a"));
+/// let error = MyEnum::VariantTwo { other: 1 }.key().unwrap_err();
+/// assert_eq!(error, MyEnumMissingFieldError { field: "key", variant: "VariantTwo" });
+/// assert_eq!(error.to_string(), "variant VariantTwo of MyEnum doesn't have field key");
+/// ```
+///
+/// `indexed` generates only `<field_name>_with_index()` accessor, that returns position of the current variant in the enum declaration
+/// together with the reference to the field, so code dispatching on the variant can read the field in the same match:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(indexed key: str)]
+/// enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String },
+/// }
+///
+/// assert_eq!(MyEnum::VariantTwo { key: "a".into() }.key_with_index(), (1, "a"));
+/// ```
+///
+/// `try_into(FieldType)` generates only `<field_name>()` accessor, that converts a clone of the field with `TryInto`
+/// to the type in the annotation, for fields that are stored wider than they are exposed:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(try_into(u64) seq: u32)]
+/// enum MyEnum {
+///     VariantOne { seq: u64 },
+///     VariantTwo { seq: u64 },
+/// }
+///
+/// assert_eq!(MyEnum::VariantOne { seq: 1 }.seq(), Ok(1u32));
+/// assert!(MyEnum::VariantTwo { seq: u64::MAX }.seq().is_err());
+/// ```
+///
+/// `pin` generates only `<field_name>_pin()` accessor, that projects `Pin<&mut MyEnum>` to `Pin<&mut Type>` of the field,
+/// which is what you need for futures and other self-referential state stored in enum variants.
+/// Such structural pinning is sound only if pinned fields are never moved, so `pin` modifier comes with some restrictions:
+/// * All variants must be struct variants, since payload structs of tuple variants could move the field in their `Drop` impl.
+/// * The enum can't implement `Drop`.
+/// * The macro implements `Unpin` for the enum when all pinned fields are `Unpin`, so you can't implement it yourself.
+/// * Type in the annotation must be exactly the type of the field.
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// use std::future::{ready, Future, Ready};
+/// use std::pin::pin;
+/// use std::task::{Context, Poll, Waker};
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(pin future: Ready<u32>)]
+/// enum MyEnum {
+///     VariantOne { future: Ready<u32> },
+///     VariantTwo { future: Ready<u32>, retries: u8 },
+/// }
+///
+/// let mut my_enum = pin!(MyEnum::VariantOne { future: ready(42) });
+/// let mut context = Context::from_waker(Waker::noop());
+/// assert_eq!(my_enum.as_mut().future_pin().poll(&mut context), Poll::Ready(42));
+/// ```
+///
+/// With `bytemuck` feature enabled, `bytes` generates only `<field_name>_bytes()` accessor, that returns the field
+/// as a byte slice using `bytemuck::bytes_of`. The field type must implement `bytemuck::Pod`,
+/// and your crate needs to depend on `bytemuck` itself. It's useful for zero-copy encoding of `repr(C)` headers:
+/// ```ignore
+/// #[derive(EnumCommonFields)]
+/// #[common_field(bytes header: Header)] // Header is a #[repr(C)] struct implementing bytemuck::Pod
+/// enum Message {
+///     Ping { header: Header },
+///     Data { header: Header, payload: Vec<u8> },
+/// }
+///
+/// socket.write_all(message.header_bytes())?;
+/// ```
+///
+/// With `alloc` feature enabled, `own_str` generates only `into_<field_name>()` accessor, that converts the field
+/// to `String` with `.as_str().into()`. It's meant for fixed-capacity string types like `heapless::String<N>`,
+/// that can be exposed as `&str` by reference accessors (since they `Deref` to `str`), but can't be moved out as `String`.
+/// Firmware crates are usually `no_std`, so the example takes `String` from `::alloc` with `no_std` option
+/// (see [`no_std` crates](#no_std-crates)), that needs `extern crate alloc;` in the crate root:
+/// ```ignore
+/// extern crate alloc;
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(name: str)]
+/// #[common_field(own_str name: str)]
+/// #[common_fields_config(no_std)]
+/// enum Message {
+///     Ping { name: heapless::String<16> },
+///     Data { name: heapless::String<16>, payload: heapless::Vec<u8, 64> },
+/// }
+///
+/// let name: &str = message.name();
+/// let name: alloc::string::String = message.into_name();
+/// ```
+/// ### Groups
+/// Calling two accessors to get two fields runs two matches, and you can't hold results of `a_mut()` and `b()` at the same time.
+/// `#[common_fields_group(name(field_name: Type, ...), ...)]` generates `name()` accessor returning a tuple
+/// with references to all listed fields, produced by a single match:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_fields_group(coords(x: f32, y: f32))]
+/// enum MyEnum {
+///     VariantOne { x: f32, y: f32 },
+///     VariantTwo { x: f32, y: f32, name: String },
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { x: 1.0, y: 2.0 };
+/// assert_eq!(my_enum.coords(), (&1.0, &2.0));
+/// ```
+/// With `mut` before the group name, `name_mut()` accessor returning mutable references is generated too,
+/// which lets you modify several common fields at once:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_fields_group(mut key_and_seq(key: String, seq: u64))]
+/// enum MyEnum {
+///     VariantOne { key: String, seq: u64 },
+///     VariantTwo { key: String, seq: u64 },
+/// }
+///
+/// let mut my_enum = MyEnum::VariantOne { key: "a".into(), seq: 1 };
+/// let (key, seq) = my_enum.key_and_seq_mut();
+/// key.push_str(&seq.to_string());
+/// *seq += 1;
+/// assert_eq!(my_enum.key_and_seq(), (&"a1".to_string(), &2));
+/// ```
+/// ### Delegation
+/// If the field should stay private, you can share its behavior instead, with
+/// `#[common_delegate(method(receiver, arguments...) -> Type via field_name)]`. It generates
+/// a method that calls the method with the same name on the field of the current variant:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_delegate(len(&self) -> usize via items, push(&mut self, item: u32) via items)]
+/// enum MyEnum {
+///     VariantOne { items: Vec<u32> },
+///     VariantTwo { items: Vec<u32>, total: u32 },
+/// }
+///
+/// let mut my_enum = MyEnum::VariantOne { items: vec![1] };
+/// my_enum.push(2);
+/// assert_eq!(my_enum.len(), 2);
+/// ```
+/// ### Accessor trait
+/// To write code generic over several types with the same common fields, `#[common_fields_trait(TraitName)]` generates
+/// a trait with all generated methods that take `self` (in any form), and implements it for the enum. The trait has the visibility of the enum:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String)]
+/// #[common_fields_trait(HasKey)]
+/// enum Request {
+///     Create { key: String },
+///     Delete { key: String },
+/// }
+///
+/// struct Response { key: String }
+///
+/// impl HasKey for Response {
+///     fn key(&self) -> &String { &self.key }
+///     fn key_mut(&mut self) -> &mut String { &mut self.key }
+/// }
+///
+/// fn log_key(value: &impl HasKey) -> String {
+///     format!("key={}", value.key())
+/// }
+///
+/// assert_eq!(log_key(&Request::Delete { key: "a".to_string() }), "key=a");
+/// assert_eq!(log_key(&Response { key: "b".to_string() }), "key=b");
+/// ```
+/// `#[common_fields_trait(TraitName, payloads)]` also implements the trait for the payload types of tuple variants
+/// (except delegated ones), so code can work the same way with the enum and with a payload of a concrete variant.
+/// Methods of the trait get the code of the match branch for the variant, so accessors that construct the enum,
+/// like `map` ones, can't be used with it:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String)]
+/// #[common_fields_trait(HasKey, payloads)]
+/// enum Request {
+///     Create(CreateRequest),
+///     Delete(DeleteRequest),
+/// }
+///
+/// struct CreateRequest { key: String, body: Vec<u8> }
+/// struct DeleteRequest { key: String }
+///
+/// fn log_key(value: &impl HasKey) -> String {
+///     format!("key={}", value.key())
+/// }
+///
+/// let create = CreateRequest { key: "a".to_string(), body: Vec::new() };
+/// assert_eq!(log_key(&create), "key=a");
+/// assert_eq!(log_key(&Request::Create(create)), "key=a");
+/// ```
+/// Whether the trait can be used as a trait object depends on the accessors it gets: `map` and `update` ones are generic,
+/// and `try_own` ones return `Self`. `#[common_fields_trait(TraitName, object_safe)]` fails compilation if the trait isn't
+/// object safe, so adding such accessor later doesn't silently break code storing `Box<dyn TraitName>`:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String)]
+/// #[common_fields_trait(HasKey, object_safe)]
+/// enum Request {
+///     Create { key: String },
+///     Delete { key: String },
+/// }
+///
+/// let requests: Vec<Box<dyn HasKey>> = vec![Box::new(Request::Create { key: "a".to_string() })];
+/// assert_eq!(requests[0].key(), "a");
+/// ```
+/// ```compile_fail
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(update key: String)]
+/// #[common_fields_trait(HasKey, object_safe)]
+/// enum Request {
+///     Create { key: String },
+///     Delete { key: String },
+/// }
+/// ```
+/// ### Hashing
+/// `#[common_fields_hash(field, ...)]` implements `Hash` for the enum, that hashes only the listed common fields, in the listed order.
+/// With a single field the enum hashes exactly like the field, as the `borrow` option requires:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// # use std::hash::{BuildHasher, RandomState};
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_fields_hash(key)]
+/// enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String, value: u32 },
+/// }
+///
+/// let state = RandomState::new();
+/// let my_enum = MyEnum::VariantTwo { key: "a".to_string(), value: 1 };
+/// assert_eq!(state.hash_one(&my_enum), state.hash_one("a".to_string()));
+/// ```
+/// ### Equality
+/// `#[common_fields_eq(field, ...)]` implements `PartialEq` and `Eq` for the enum, that compare only the listed common fields,
+/// so the types of the fields need to implement `Eq`. Values of different variants are equal if their fields are,
+/// unless `variant` is listed too:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_fields_eq(key)]
+/// enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String, value: u32 },
+/// }
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_fields_eq(variant, key)]
+/// enum OtherEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String, value: u32 },
+/// }
+///
+/// assert!(MyEnum::VariantOne { key: "a".into() } == MyEnum::VariantTwo { key: "a".into(), value: 1 });
+/// assert!(OtherEnum::VariantOne { key: "a".into() } != OtherEnum::VariantTwo { key: "a".into(), value: 1 });
+/// assert!(OtherEnum::VariantTwo { key: "a".into(), value: 1 } == OtherEnum::VariantTwo { key: "a".into(), value: 2 });
+/// ```
+/// ### Ordering
+/// `#[common_fields_ord(field, ...)]` implements `PartialOrd` and `Ord` for the enum, that compare the listed common fields
+/// in the listed order, so values of different variants can be sorted together. `Ord` has to agree with `PartialEq`,
+/// so it's meant to be used with `#[common_fields_eq(...)]` listing the same fields:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(copy timestamp: u64)]
+/// #[common_fields_eq(timestamp)]
+/// #[common_fields_ord(timestamp)]
+/// enum Event {
+///     Created { timestamp: u64, name: String },
+///     Deleted { timestamp: u64 },
+/// }
+///
+/// let mut events = vec![
+///     Event::Deleted { timestamp: 3 },
+///     Event::Created { timestamp: 1, name: "a".into() },
+/// ];
+/// events.sort();
+/// assert_eq!(events[0].timestamp(), 1);
+/// ```
+/// ### Types
+/// Type in the `#[common_field]` annotation is used only as a return type of the accessor.
+/// So you if you generate only reference accessors (or you generate owning accessor in a different annotation)
+/// you can use type that `Deref`s from the original field type instead of it itself.
+/// Classic example is using `str` instead of `String` for reference accessors:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: str)]
+/// #[common_field(own_only key: String)]
+/// enum MyEnum {
+///     One { key: String }
+/// }
+/// let mut e = MyEnum::One { key: "k".to_string() };
+/// let key_ref = e.key(); // returns "k" as &str instead or &String
+/// let key_mut_ref = e.key_mut(); // returns "k" as &mut str instead or &mut String
+/// let key = e.into_key(); // consumes e and returns "k" as actual String
+/// ```
+/// Several fields of the same type can share one annotation, that generates the same accessors with the same options for each of them.
+/// Such annotation can't name the accessors with `as`, and can't implement traits (with `deref` modifier, or `as_mut`, `borrow`,
+/// `borrow_mut`, `from`, `display`, `eq` and `impl` options), since every field would implement the same trait:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut width, height: u32)]
+/// enum Shape {
+///     Rectangle { width: u32, height: u32 },
+///     Ellipse { width: u32, height: u32 },
+/// }
+/// let mut shape = Shape::Ellipse { width: 2, height: 1 };
+/// *shape.height_mut() *= 2;
+/// assert_eq!((shape.width(), shape.height()), (&2, &2));
+/// ```
+/// ### Field markers
+/// Instead of an annotation on the enum, a field of a struct variant can be marked with `#[common]`. It works like
+/// `#[common_field(name: Type)]` with name and type of the marked field, so the type isn't repeated.
+/// Modifiers, `as` names and options go to `#[common(...)]`, like `#[common(mut as k, since = "1.0")]`.
+/// One marker per field is enough, the field still has to be in every variant. Marking it in other variants too is fine,
+/// as long as the markers are the same or bare `#[common]`:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// enum MyEnum {
+///     VariantOne {
+///         #[common(mut)]
+///         key: String,
+///         #[common(copy)]
+///         seq: u64,
+///     },
+///     VariantTwo { key: String, seq: u64, value: u32 },
+/// }
+///
+/// let mut my_enum = MyEnum::VariantTwo { key: "a".into(), seq: 1, value: 2 };
+/// my_enum.key_mut().push('b');
+/// assert_eq!((my_enum.key().as_str(), my_enum.seq()), ("ab", 1));
+/// ```
+/// ### Documentation
+/// Accessors get doc comment of the field from the first struct variant where the field has one, so rustdoc
+/// and IDE hovers show it. `doc = "text"` option sets doc comment of the accessors explicitly, instead of the one of the field:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String)]
+/// pub enum MyEnum {
+///     /// First variant
+///     VariantOne {
+///         /// Stable identifier of the record
+///         key: String,
+///     },
+///     /// Second variant
+///     VariantTwo {
+///         /// Not used, since the first variant has a doc comment for the field already
+///         key: String,
+///     },
+/// }
+/// ```
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String, doc = "Stable identifier of the record")]
+/// pub enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String },
+/// }
+/// ```
+/// `alias = "name"` adds `#[doc(alias = "name")]` to the accessors, so searching rustdoc for the name finds them.
+/// Like the other options, it can be repeated to add several aliases:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String, alias = "identifier", alias = "id")]
+/// pub enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String },
+/// }
+/// ```
+/// ### Nested enums
+/// A tuple variant holding another enum with the same common fields can be marked with `#[common_field(delegate)]`.
+/// Then accessors call the accessor with the same name of the inner type, instead of accessing a field of it:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String)]
+/// enum Inner {
+///     One { key: String },
+///     Two { key: String },
+/// }
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String)]
+/// enum Outer {
+///     Own { key: String },
+///     #[common_field(delegate)]
+///     Nested(Inner),
+/// }
+///
+/// let mut outer = Outer::Nested(Inner::Two { key: "key".to_string() });
+/// outer.key_mut().push('!');
+/// assert_eq!(outer.key(), "key!");
+/// ```
+/// `try_own`, `result`, `indexed`, `map` and `pin` accessors, groups and enum options don't support such variants,
+/// since results of the inner type can't be used for them as is.
+/// ### `no_std` crates
+/// Generated code refers to `::core`, so the accessors work in `no_std` crates too. Only the options that need an allocator
+/// name types outside of it: `Cow` of `cow` modifier, `Arc` of `arc` modifier, `Vec` of `collect` and `columns` options,
+/// and `String` of `own_str` modifier. They come from `::std`, or from `::alloc` with `no_std` option
+/// of `#[common_fields_config(...)]`, so `no_std` crates with an allocator can use them after adding `extern crate alloc;`
+/// to their root. `MissingFieldError` of `result` accessors implements `core::error::Error`, that needs Rust 1.81:
+/// ```rust
+/// # extern crate alloc;
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(cow key: str, collect)]
+/// #[common_fields_config(no_std)]
+/// enum MyEnum {
+///     VariantOne { key: alloc::string::String },
+///     VariantTwo { key: alloc::string::String },
+/// }
+///
+/// let items = [MyEnum::VariantOne { key: "a".into() }, MyEnum::VariantTwo { key: "b".into() }];
+/// assert_eq!(MyEnum::collect_keys(&items), ["a", "b"]);
+/// ```
+/// ### Feature-gated variants
+/// `#[cfg(...)]` attributes of a variant are copied to every match branch generated for it,
+/// so variants holding payload types that exist only under some cargo feature work as any other variant:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// # struct SqliteMeta { key: String }
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// enum Backend {
+///     Sqlite(SqliteMeta),
+///     #[cfg(feature = "postgres")]
+///     Postgres(PgMeta), // PgMeta doesn't exist without the feature
+/// }
+/// ```
+/// The derive and its annotations can be made optional as well, with `#[cfg_attr(...)]`. Either put them in the same `cfg_attr`,
+/// or keep at least one annotation unconditional, since the derive fails without any:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[cfg_attr(feature = "accessors", derive(EnumCommonFields), common_field(key: String))]
+/// #[cfg_attr(feature = "accessors", common_field(copy seq: u64))]
+/// enum Message {
+///     Ping { key: String, seq: u64 },
+///     Pong { key: String, seq: u64 },
+/// }
+/// ```
+/// To keep the field but gate only its accessors, start the annotation with `cfg(...)`. Every method and trait implementation
+/// generated for the annotation gets the `#[cfg(...)]` attribute, so builds without the feature don't see them.
+/// Methods of the trait from `#[common_fields_trait(...)]` are gated the same way, while `impl = Trait::method` option
+/// can't be used with the prefix, since the implementation of the trait needs the method either way:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_field(cfg(feature = "admin") secret: String)]
+/// enum Account {
+///     User { key: String, secret: String },
+///     Service { key: String, secret: String },
+/// }
+/// ```
+/// Since the field may not be common without the feature, it is left out of the enum-wide items: common structs, `split`,
+/// `Debug`, hashing and comparisons, `COMMON_FIELDS_SINCE` and `COMMON_FIELDS_METADATA` don't include it,
+/// and `frozen` option doesn't count its methods. So `common` and `common_mut` need at least one field without the prefix:
+/// ```compile_fail
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(cfg(feature = "admin") secret: String)]
+/// #[common_fields_config(common)] // Fails, since there are no fields for the struct of references
+/// enum Account {
+///     User { key: String, secret: String },
+///     Service { key: String, secret: String },
+/// }
+/// ```
+/// ### Renamed fields
+/// If a variant stores the common field under another name, map it with `#[common_field_rename(field_name = variant_field_name, ...)]`.
+/// It works for tuple variants too, renaming the field of the payload:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// struct Order { order_id: u32 }
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(copy id: u32)]
+/// enum MyEnum {
+///     #[common_field_rename(id = user_id)]
+///     User { user_id: u32 },
+///     #[common_field_rename(id = order_id)]
+///     Order(Order),
+///     Other { id: u32 },
+/// }
+///
+/// assert_eq!(MyEnum::User { user_id: 1 }.id(), 1);
+/// assert_eq!(MyEnum::Order(Order { order_id: 2 }).id(), 2);
+/// ```
+/// ### Nested fields
+/// If a variant keeps the field deeper, like in a nested struct, tell where it is with
+/// `#[common_field_via(field_name = expression, ...)]`. The expression is used in place of the field access,
+/// so it should be a place expression like `v.meta.key`. In it `v` is the element of a tuple variant
+/// that normally holds the fields, and fields of a struct variant are available by their names:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// struct Meta { key: String }
+/// struct Legacy { meta: Meta }
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String)]
+/// enum MyEnum {
+///     Current { key: String },
+///     #[common_field_via(key = v.meta.key)]
+///     Legacy(Legacy),
+///     #[common_field_via(key = meta.key)]
+///     Wrapped { meta: Meta },
+/// }
+///
+/// let mut my_enum = MyEnum::Legacy(Legacy { meta: Meta { key: "key".to_string() } });
+/// my_enum.key_mut().push('!');
+/// assert_eq!(my_enum.key(), "key!");
+/// ```
+/// `map` and `pin` accessors and `proptest` and `arbitrary` options don't support overridden fields.
+/// ### Flattened fields
+/// If every variant keeps common data in one struct field, `flatten` before the annotation generates accessors for the field
+/// and for the fields of its struct listed in braces after the type (the macro can't see the struct, so they have to be listed).
+/// Accessors of the listed fields reach them through the flattened field, the same way as `#[common_field_via]` does,
+/// and they get the modifiers and options of the annotation, except those that name accessors or implement traits:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// struct CommonData { id: u64, created_at: u64 }
+/// struct Job { common: CommonData, pid: u32 }
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(flatten mut common: CommonData { id: u64, created_at: u64 })]
+/// enum MyEnum {
+///     Pending { common: CommonData },
+///     Running(Job),
+/// }
+///
+/// let mut my_enum = MyEnum::Running(Job { common: CommonData { id: 1, created_at: 100 }, pid: 42 });
+/// *my_enum.id_mut() += 1;
+/// assert_eq!(my_enum.id(), &2);
+/// assert_eq!(my_enum.common().created_at, 100);
+/// ```
+/// ### Private fields
+/// Payloads from other crates often keep their fields private and expose getters instead. `via method(arguments)` after the field name
+/// makes accessors of the annotation call the method on payloads of tuple variants instead of accessing the field.
+/// The method needs to return a reference to the field (a mutable one for mutable accessors), while struct variants still access the field itself:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// mod external {
+///     pub struct Request { key: String }
+///     impl Request {
+///         pub fn new(key: &str) -> Self { Self { key: key.to_string() } }
+///         pub fn key(&self) -> &String { &self.key }
+///     }
+/// }
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key via key(): String)]
+/// #[common_field(clone key via key() as key_owned: String)]
+/// enum MyEnum {
+///     External(external::Request),
+///     Local { key: String },
+/// }
+///
+/// let my_enum = MyEnum::External(external::Request::new("key"));
+/// assert_eq!(my_enum.key(), "key");
+/// assert_eq!(my_enum.key_owned(), "key");
+/// ```
+/// `own`, `try_own`, `map` and `pin` accessors can't move the field out of a reference, so they don't support `via`.
+/// ### Skipping variants
+/// If some variant legitimately lacks a common field (like an error or empty variant), mark it with
+/// `#[common_field_skip(field_name, ...)]`. Accessors of the skipped fields return `Option` then,
+/// which is `None` for the marked variants:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(own key: String)]
+/// #[common_field(copy seq: u64)]
+/// enum MyEnum {
+///     Data { key: String, seq: u64 },
+///     #[common_field_skip(key, seq)]
+///     Empty,
+/// }
+///
+/// let mut my_enum = MyEnum::Data { key: "k".to_string(), seq: 1 };
+/// assert_eq!(my_enum.key(), Some(&"k".to_string()));
+/// my_enum.key_mut().unwrap().push('!');
+/// assert_eq!(my_enum.into_key(), Some("k!".to_string()));
+/// assert_eq!(MyEnum::Empty.seq(), None);
+/// ```
+/// Only read-only, mutable, `own_only`, `clone`, `copy` and `cell` accessors have `Option` form. Annotations with
+/// `default` option return the default value instead, and `try_own` and `result` accessors already handle missing fields.
+/// Other accessors, groups and enum options that need the field in every variant don't support skipping it:
+/// ```rust,compile_fail
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(replace key: String)] // Fails, since replace_key() can't skip Empty
+/// enum MyEnum {
+///     Data { key: String },
+///     #[common_field_skip(key)]
+///     Empty,
+/// }
+/// ```
+/// ### Renaming
+/// You can use `as getter_name` in the `common_field` annotation to rename generated function name. In annotations with modifiers
+/// that generate more than one accessor (like `mut` or `own`) the name replaces the field name in all of them, so `mut key as k`
+/// generates `k()` and `k_mut()`. If you need names that don't follow the convention, you can add more than one annotation per field:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// struct VariantOne {
+///     key: String
+/// }
+///
+/// struct VariantTwo {
+///     key: String
+/// }
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key as k: String)]
+/// #[common_field(mut_only key as k_mut: String)]
+/// #[common_field(own_only key as into_k: String)]
+/// enum MyEnum {
+///     VariantOne(VariantOne),
+///     VariantTwo(VariantTwo),
+/// }
+///
+/// let mut my_enum = MyEnum::VariantOne(VariantOne { key: "Example".into() });
+/// assert_eq!(my_enum.k(), "Example");
+///
+/// my_enum.k_mut().push_str(" Mutated"); // Mutable access
+/// assert_eq!(my_enum.k(), "Example Mutated");
+///
+/// let key: String = my_enum.into_k(); // Consuming MyEnum instance, and getting owned String instance
+/// assert_eq!(key, "Example Mutated".to_string())
+/// ```
+/// Or list names of all accessors with `as (name, ...)`. Names go in the order immutable, mutable, owning, followed by the rest
+/// in the order modifiers are described above, whatever the order of modifiers in the annotation is:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(all key as (k, k_mut, into_k): String)]
+/// enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String },
+/// }
+///
+/// let mut my_enum = MyEnum::VariantOne { key: "Example".into() };
+/// my_enum.k_mut().push_str(" Mutated");
+/// assert_eq!(my_enum.k(), "Example Mutated");
+/// assert_eq!(my_enum.into_k(), "Example Mutated");
+/// ```
+/// If you want, you can generate multiple accessors with different names for the same field:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)] // Generates accessor named key()
+/// #[common_field(key as k: String)] // Generates accessor named k()
+/// #[common_field(key as get_key: String)] // Generates accessor named get_key()
+/// enum MyEnum {
+///     VariantOne { key: String, /* other fields */ },
+///     VariantTwo { key: String, /* other fields */ },
+/// }
+/// ```
+/// ### Options
+/// After the type you can add comma separated options that apply to all accessors generated by the annotation.
+///
+/// `since = "version"` documents when the accessors became available. The version is added to the doc comment of every accessor,
+/// and the enum gets a `COMMON_FIELDS_SINCE` constant listing `(accessor_name, version)` pairs for all annotations that have one:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String, since = "1.2")]
+/// #[common_field(value: u32)]
+/// enum MyEnum {
+///     VariantOne { key: String, value: u32 },
+///     VariantTwo { key: String, value: u32 },
+/// }
+///
+/// assert_eq!(MyEnum::COMMON_FIELDS_SINCE, &[("key", "1.2"), ("key_mut", "1.2")]);
+/// ```
+///
+/// `no_coverage` excludes the accessors from code coverage reports, so hundreds of generated one-line methods don't dilute them.
+/// The accessors get `#[cfg_attr(coverage_nightly, coverage(off))]`, so it takes effect when coverage is collected on nightly
+/// with `coverage_nightly` cfg set (`cargo llvm-cov` does it by default) and `#![cfg_attr(coverage_nightly, feature(coverage_attribute))]` in your crate root.
+/// Also declare the cfg in your `Cargo.toml`, so rustc doesn't warn about it:
+/// ```toml
+/// [lints.rust]
+/// unexpected_cfgs = { level = "warn", check-cfg = ["cfg(coverage_nightly)"] }
+/// ```
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String, no_coverage)]
+/// enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String },
+/// }
+/// ```
+///
+/// `vis = visibility` sets visibility of the accessors, that are `pub` otherwise, so internal fields don't become a part of public API.
+/// Any visibility Rust allows can be used, including none at all (`vis = `) for accessors private to the module of the enum:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// mod jobs {
+///     # use enum_common_fields::EnumCommonFields;
+///     #[derive(EnumCommonFields)]
+///     #[common_field(id: u64)]
+///     #[common_field(mut retries: u32, vis = pub(crate))]
+///     pub enum Job {
+///         Queued { id: u64, retries: u32 },
+///         Running { id: u64, retries: u32 },
+///     }
+/// }
+///
+/// let mut job = jobs::Job::Queued { id: 1, retries: 0 };
+/// *job.retries_mut() += 1;
+/// assert_eq!((job.id(), job.retries()), (&1, &1));
+/// ```
+///
+/// `inline`, `inline(always)` or `inline(never)` adds the same attribute to the accessors (and methods of traits implemented with them):
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(copy id: u64, inline(always))]
+/// enum MyEnum {
+///     VariantOne { id: u64 },
+///     VariantTwo { id: u64 },
+/// }
+///
+/// assert_eq!(MyEnum::VariantTwo { id: 1 }.id(), 1);
+/// ```
+///
+/// `const` makes the accessors `const fn`, so they can be used in const contexts. It's supported for `ref` and `copy` accessors,
+/// and the type in the annotation has to be the actual type of the field, since `Deref` can't be called in const functions.
+/// For the same reason it can't be used with `get` option, `via` method, `audit_hook` enum option and delegated variants:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(copy id: u64, const)]
+/// #[common_field(name: &'static str, const)]
+/// enum MyEnum {
+///     VariantOne { id: u64, name: &'static str },
+///     VariantTwo { id: u64, name: &'static str },
+/// }
+///
+/// const DEFAULT: MyEnum = MyEnum::VariantOne { id: 1, name: "default" };
+/// const DEFAULT_ID: u64 = DEFAULT.id();
+/// const DEFAULT_NAME: &str = DEFAULT.name();
+/// assert_eq!((DEFAULT_ID, DEFAULT_NAME), (1, "default"));
+/// ```
+///
+/// `redact` hides the value of the field in `Debug` implementation generated by `debug` enum option (see below).
+///
+/// `as_mut` implements `AsMut<Type>` for the enum, returning the field like the mutable accessor does,
+/// so the enum can be passed to functions generic over `AsMut`:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut buffer: Vec<u8>, as_mut)]
+/// enum MyEnum {
+///     VariantOne { buffer: Vec<u8> },
+///     VariantTwo { buffer: Vec<u8> },
+/// }
+///
+/// fn fill(mut target: impl AsMut<Vec<u8>>) {
+///     target.as_mut().push(1);
+/// }
+///
+/// let mut my_enum = MyEnum::VariantOne { buffer: Vec::new() };
+/// fill(&mut my_enum);
+/// assert_eq!(my_enum.buffer(), &[1]);
+/// ```
+///
+/// `display` implements `Display` for the enum, that formats just the field. It can be set for one field only,
+/// and can't be combined with `display` enum option (see below):
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(id: u64, display)]
+/// enum MyEnum {
+///     VariantOne { id: u64 },
+///     VariantTwo { id: u64, name: String },
+/// }
+///
+/// let my_enum = MyEnum::VariantTwo { id: 42, name: "a".to_string() };
+/// assert_eq!(my_enum.to_string(), "42");
+/// ```
+///
+/// `eq` implements `PartialEq` between the enum and the field type (and references to it) in both directions,
+/// that compares the field:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: str, eq)]
+/// enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String, value: u32 },
+/// }
+///
+/// let my_enum = MyEnum::VariantTwo { key: "expected".to_string(), value: 1 };
+/// assert!(my_enum == "expected");
+/// assert!("other" != my_enum);
+/// ```
+///
+/// `from` implements `From<MyEnum>` for the field type with the owning accessor, so the enum can be passed where `Into<Type>` is expected.
+/// It needs `own` or `own_only` modifier in the same annotation:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(own_only key: String, from)]
+/// enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String, value: u32 },
+/// }
+///
+/// fn store(key: impl Into<String>) -> String {
+///     key.into()
+/// }
+///
+/// assert_eq!(store(MyEnum::VariantOne { key: "a".to_string() }), "a");
+/// ```
+///
+/// `borrow` implements `Borrow<Type>` for the enum, so enums can be stored in a `HashSet` or a `BTreeSet`
+/// and looked up by the field alone. `Borrow` requires `Hash`, `Eq` and `Ord` of the enum to behave like those of the field,
+/// so the option fails compilation if the enum implements `Hash`, `PartialEq` or `PartialOrd` (derived in any `#[derive(...)]`
+/// attribute or written by hand) other than with `#[common_fields_hash(field)]` (see [Hashing](#hashing)),
+/// `#[common_fields_eq(field)]` (see [Equality](#equality)) or `#[common_fields_ord(field)]`, that list the borrowed field only.
+/// The same goes for `borrow_mut`:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// # use std::collections::HashSet;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String, borrow)]
+/// #[common_fields_hash(key)]
+/// #[common_fields_eq(key)]
+/// enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String, value: u32 },
+/// }
+///
+/// let set = HashSet::from([MyEnum::VariantTwo { key: "a".to_string(), value: 1 }]);
+/// assert!(set.contains(&"a".to_string()));
+/// ```
+/// ```compile_fail
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(Hash, PartialEq, Eq, EnumCommonFields)] // Fails, since derived Hash hashes the whole enum
+/// #[common_field(key: String, borrow)]
+/// enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String, value: u32 },
+/// }
+/// ```
+///
+/// `borrow_mut` implements `BorrowMut<Type>` together with `Borrow<Type>`, for generic code parameterized over `BorrowMut`:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// # use std::borrow::BorrowMut;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut items: Vec<u32>, borrow_mut)]
+/// enum MyEnum {
+///     VariantOne { items: Vec<u32> },
+///     VariantTwo { items: Vec<u32> },
+/// }
+///
+/// fn push_one(target: &mut impl BorrowMut<Vec<u32>>) {
+///     target.borrow_mut().push(1);
+/// }
+///
+/// let mut my_enum = MyEnum::VariantTwo { items: Vec::new() };
+/// push_one(&mut my_enum);
+/// assert_eq!(my_enum.items(), &[1]);
+/// ```
+///
+/// `default = expression` lets variants that don't have the field (including unit variants) take part in the accessors,
+/// which return value of the expression for them. Since there is nothing to borrow from, it's supported only with `own_only`,
+/// `clone`, `copy` and `cell` modifiers:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(copy priority: u8, default = 0)]
+/// enum MyEnum {
+///     VariantOne { priority: u8 },
+///     VariantTwo { other: String },
+///     VariantThree,
+/// }
+///
+/// assert_eq!(MyEnum::VariantOne { priority: 5 }.priority(), 5);
+/// assert_eq!(MyEnum::VariantThree.priority(), 0);
+/// ```
+///
+/// Variants can have their own defaults with `#[common_field_default(field_name = expression, ...)]`, which take precedence
+/// over the `default` option, or work without it:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(copy priority: u8, default = 0)]
+/// enum MyEnum {
+///     VariantOne { priority: u8 },
+///     #[common_field_default(priority = 255)]
+///     Error,
+///     Empty,
+/// }
+///
+/// assert_eq!(MyEnum::Error.priority(), 255);
+/// assert_eq!(MyEnum::Empty.priority(), 0);
+/// ```
+///
+/// `get = expression` makes the immutable accessor pass a reference to the field to the expression (usually a closure),
+/// and return its result. The annotation then contains the type of the result. Combined with `default`, it's a way to
+/// support variants without the field in accessors that don't return the field itself:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key as key_len: usize, get = |key: &String| key.len())]
+/// #[common_field(comment as has_comment: bool, get = Option::is_some, default = false)]
+/// enum MyEnum {
+///     VariantOne { key: String, comment: Option<String> },
+///     VariantTwo { key: String },
+/// }
+///
+/// assert_eq!(MyEnum::VariantTwo { key: "abc".into() }.key_len(), 3);
+/// assert!(!MyEnum::VariantTwo { key: "abc".into() }.has_comment());
+/// ```
+///
+/// `collect` generates `collect_<field_name>s(items)` function, that collects references to the field of every item of a slice.
+/// Use `collect = function_name` to choose the name yourself:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: str, collect)]
+/// #[common_field(value: u32, collect = all_values)]
+/// enum MyEnum {
+///     VariantOne { key: String, value: u32 },
+///     VariantTwo { key: String, value: u32 },
+/// }
+///
+/// let items = [
+///     MyEnum::VariantOne { key: "a".into(), value: 1 },
+///     MyEnum::VariantTwo { key: "b".into(), value: 2 },
+/// ];
+/// assert_eq!(MyEnum::collect_keys(&items), vec!["a", "b"]);
+/// assert_eq!(MyEnum::all_values(&items), vec![&1, &2]);
+/// ```
+///
+/// `impl = Trait::method` makes the accessor a method of your own trait instead of an inherent method.
+/// Annotations pointing at the same trait share one `impl` block, so together they need to cover all required methods of the trait.
+/// The annotation needs to generate exactly one accessor:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// mod model {
+///     pub trait Identifiable {
+///         fn id(&self) -> u64;
+///         fn name(&self) -> &str;
+///     }
+/// }
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(copy id: u64, impl = model::Identifiable::id)]
+/// #[common_field(key: str, impl = model::Identifiable::name)]
+/// enum MyEnum {
+///     VariantOne { id: u64, key: String },
+///     VariantTwo { id: u64, key: String },
+/// }
+///
+/// use model::Identifiable;
+/// let my_enum = MyEnum::VariantOne { id: 1, key: "a".into() };
+/// assert_eq!(my_enum.id(), 1);
+/// assert_eq!(my_enum.name(), "a");
+/// ```
+/// ### Enum options
+/// Options that apply to the whole enum go to `#[common_fields_config(...)]` annotation, or its shorter alias `#[common_fields(...)]`.
+///
+/// `defaults(...)` sets options for all `#[common_field]` annotations of the enum. It supports `since = "version"`, `no_coverage`,
+/// `collect`, `doc = "text"` and `cfg(...)`. Options set in an annotation take precedence over the defaults, so `since`
+/// of an annotation replaces the default one, and `cfg(...)` prefix of an annotation replaces the default cfg.
+/// Default `doc` is used only for fields without a doc comment, and `{field}` placeholder in it is replaced by the name of the field.
+/// Default `cfg` gates methods and trait implementations of annotations like the prefix does, with two differences:
+/// accessors from `impl = Trait::method` option aren't gated (the trait implementation needs them either way),
+/// and the fields stay common for enum options like `common`, `#[common_fields_hash(...)]` or `debug`,
+/// since the default cfg hides accessors, not the fields.
+/// `kinds = modifiers` sets modifiers of annotations that don't have any, so `defaults(kinds = mut)` makes every field mutable
+/// unless its annotation says otherwise (say, with `ref`).
+/// `deny(option, ...)` fails compilation if any `#[common_field]` annotation uses one of listed options,
+/// which is handy to make sure that, say, no field gets a `default` value by accident:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String)]
+/// #[common_field(seq: u64, since = "1.1")]
+/// #[common_fields_config(defaults(since = "1.0", no_coverage), deny(default, get))]
+/// enum MyEnum {
+///     VariantOne { key: String, seq: u64 },
+///     VariantTwo { key: String, seq: u64 },
+/// }
+///
+/// assert_eq!(MyEnum::COMMON_FIELDS_SINCE, &[("key", "1.0"), ("key_mut", "1.0"), ("seq", "1.1")]);
+/// ```
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_field(ref seq: u64)]
+/// #[common_fields_config(defaults(kinds = mut + own))]
+/// enum MyEnum {
+///     VariantOne { key: String, seq: u64 },
+///     VariantTwo { key: String, seq: u64 },
+/// }
+///
+/// let mut my_enum = MyEnum::VariantOne { key: "a".into(), seq: 1 };
+/// my_enum.key_mut().push('b');
+/// assert_eq!(my_enum.seq(), &1);
+/// assert_eq!(my_enum.into_key(), "ab");
+/// ```
+///
+/// `rename_map = "field_name => name, ..."` changes the name used for accessors of the listed fields, the same way
+/// as adding `as` to every annotation of the field would do, but in one place:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(own identifier: String)]
+/// #[common_field(copy sequence_number: u64)]
+/// #[common_fields_config(rename_map = "identifier => id, sequence_number => seq")]
+/// enum MyEnum {
+///     VariantOne { identifier: String, sequence_number: u64 },
+///     VariantTwo { identifier: String, sequence_number: u64 },
+/// }
+///
+/// let mut my_enum = MyEnum::VariantOne { identifier: "id".into(), sequence_number: 1 };
+/// my_enum.id_mut().push_str("entifier");
+/// assert_eq!(my_enum.seq(), 1);
+/// assert_eq!(my_enum.into_id(), "identifier");
+/// ```
+///
+/// `getter = "template"`, `mutter = "template"` and `owner = "template"` change default names of immutable, mutable and owning
+/// accessors respectively, for all fields at once. `{field}` in the template is replaced with the field name (after `rename_map`).
+/// Names set with `as` are used as they are:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut + own key: String)]
+/// #[common_field(seq: u64)]
+/// #[common_field(payload as data: Vec<u8>)]
+/// #[common_fields_config(getter = "get_{field}", mutter = "get_{field}_mut", owner = "take_{field}")]
+/// enum MyEnum {
+///     VariantOne { key: String, seq: u64, payload: Vec<u8> },
+///     VariantTwo { key: String, seq: u64, payload: Vec<u8> },
+/// }
+///
+/// let mut my_enum = MyEnum::VariantOne { key: "a".into(), seq: 1, payload: vec![] };
+/// my_enum.get_key_mut().push('b');
+/// assert_eq!((my_enum.get_seq(), my_enum.data().len()), (&1, 0));
+/// assert_eq!(my_enum.take_key(), "ab");
+/// ```
+///
+/// `auto` declares every field that all variants have with the same type, as if it had a `#[common_field(name: Type)]` annotation
+/// (so `defaults(...)` apply to it too). Fields with annotations or markers keep them, and `exclude(field_name, ...)` lists fields
+/// that shouldn't get accessors. It needs all variants to be struct variants, since fields of tuple variant payloads are not visible:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String)]
+/// #[common_fields_config(auto, exclude(cache))]
+/// enum MyEnum {
+///     VariantOne { key: String, seq: u64, cache: Vec<u8> },
+///     VariantTwo { seq: u64, key: String, cache: Vec<u8>, value: u32 },
+/// }
+///
+/// let mut my_enum = MyEnum::VariantOne { key: "a".into(), seq: 1, cache: Vec::new() };
+/// my_enum.key_mut().push('b');
+/// assert_eq!((my_enum.key().as_str(), my_enum.seq()), ("ab", &1));
+/// ```
+///
+/// `vis = visibility` sets visibility of all generated methods and constants, that are `pub` otherwise.
+/// `vis` option of a `#[common_field]` annotation takes precedence over it. Generated types have the visibility of the enum:
+/// ```rust
+/// mod jobs {
+///     # use enum_common_fields::EnumCommonFields;
+///     #[derive(EnumCommonFields)]
+///     #[common_field(id: u64, vis = pub)]
+///     #[common_field(mut retries: u32)]
+///     #[common_fields(vis = pub(crate), common)]
+///     pub enum Job {
+///         Queued { id: u64, retries: u32 },
+///         Running { id: u64, retries: u32 },
+///     }
+/// }
+///
+/// let mut job = jobs::Job::Running { id: 1, retries: 0 };
+/// *job.retries_mut() += 1;
+/// assert_eq!(job.common().retries, &1);
+/// ```
+///
+/// `inline`, `inline(always)` or `inline(never)` adds the same attribute to all generated functions, including methods
+/// of generated trait implementations. Functions generated for a `#[common_field]` annotation with its own `inline` option keep that one:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String, inline(never))]
+/// #[common_field(copy seq: u64)]
+/// #[common_fields_config(inline, common)]
+/// enum MyEnum {
+///     VariantOne { key: String, seq: u64 },
+///     VariantTwo { key: String, seq: u64 },
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { key: "a".into(), seq: 1 };
+/// assert_eq!((my_enum.key().as_str(), my_enum.common().seq), ("a", &1));
+/// ```
+///
+/// `common` generates `MyEnumCommonRef<'a>` struct with a reference to every common field, and `common()` accessor that fills it
+/// in a single match, so the common part of the enum can be passed around as one value. Type of a field is taken the same way
+/// as for `columns`, and a field is named like its accessors:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: str)]
+/// #[common_field(seq: u64)]
+/// #[common_fields_config(common)]
+/// enum MyEnum {
+///     VariantOne { key: String, seq: u64 },
+///     VariantTwo { key: String, seq: u64 },
+/// }
+///
+/// fn describe(common: MyEnumCommonRef<'_>) -> String {
+///     format!("{}#{}", common.key, common.seq)
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { key: "a".into(), seq: 1 };
+/// assert_eq!(describe(my_enum.common()), "a#1");
+/// ```
+///
+/// `common(iter)` also implements `IntoIterator` for `MyEnumCommonRef`, that yields `(name, value)` pairs of all common fields
+/// as `(&'static str, &dyn Display)`, so generic code can walk the fields without knowing their names.
+/// All common fields need to implement `Display`, and their types need to be sized (so `String` instead of `str`):
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_field(seq: u64)]
+/// #[common_fields_config(common(iter))]
+/// enum MyEnum {
+///     VariantOne { key: String, seq: u64 },
+///     VariantTwo { key: String, seq: u64 },
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { key: "a".into(), seq: 1 };
+/// let rendered: Vec<_> = my_enum.common().into_iter().map(|(name, value)| format!("{name}={value}")).collect();
+/// assert_eq!(rendered, ["key=a", "seq=1"]);
+/// ```
+///
+/// With `serde` feature enabled, `common(serde)` implements `Serialize` for `MyEnumCommonRef`, so the common fields of any variant
+/// can be logged or exported with `my_enum.common()`. It's serialized as a struct named like the enum, with fields named like the accessors.
+/// Your crate needs to depend on `serde` itself, and common fields need to implement `Serialize`:
+/// ```ignore
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: str)]
+/// #[common_field(seq: u64)]
+/// #[common_fields_config(common(serde))]
+/// enum MyEnum {
+///     VariantOne { key: String, seq: u64 },
+///     VariantTwo { key: String, seq: u64, payload: Vec<u8> },
+/// }
+///
+/// let my_enum = MyEnum::VariantTwo { key: "a".into(), seq: 1, payload: vec![0; 1024] };
+/// assert_eq!(serde_json::to_string(&my_enum.common()).unwrap(), r#"{"key":"a","seq":1}"#);
+/// ```
+///
+/// `common_mut` does the same for mutable references, generating `MyEnumCommonMut<'a>` and `common_mut()`.
+/// Unlike calling several `_mut()` accessors, it lets you modify several common fields at once:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_field(seq: u64)]
+/// #[common_fields_config(common_mut)]
+/// enum MyEnum {
+///     VariantOne { key: String, seq: u64 },
+///     VariantTwo { key: String, seq: u64 },
+/// }
+///
+/// let mut my_enum = MyEnum::VariantOne { key: "a".into(), seq: 1 };
+/// let common = my_enum.common_mut();
+/// common.key.push_str(&common.seq.to_string());
+/// *common.seq += 1;
+/// assert_eq!(my_enum.key(), "a1");
+/// assert_eq!(*my_enum.seq(), 2);
+/// ```
+///
+/// `into_common` generates `MyEnumCommon` struct and `into_common()` accessor, that moves all common fields into it.
+/// It's the only way to take ownership of several common fields, since owning accessors consume the enum:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_field(payload: Vec<u8>)]
+/// #[common_fields_config(into_common)]
+/// enum MyEnum {
+///     VariantOne { key: String, payload: Vec<u8> },
+///     VariantTwo { key: String, payload: Vec<u8> },
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { key: "a".into(), payload: vec![1] };
+/// let MyEnumCommon { key, payload } = my_enum.into_common();
+/// assert_eq!((key.as_str(), payload.as_slice()), ("a", &[1][..]));
+/// ```
+///
+/// `common_owned` generates the same `MyEnumCommon` struct and `common_owned()` accessor, that clones all common fields into it
+/// without consuming the enum (so all common fields have to implement `Clone`):
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_field(payload: Vec<u8>)]
+/// #[common_fields_config(common_owned)]
+/// enum MyEnum {
+///     VariantOne { key: String, payload: Vec<u8> },
+///     VariantTwo { key: String, payload: Vec<u8> },
+/// }
+///
+/// let my_enum = MyEnum::VariantOne { key: "a".into(), payload: vec![1] };
+/// let snapshot = my_enum.common_owned();
+/// let handle = std::thread::spawn(move || snapshot.key.len() + snapshot.payload.len());
+/// assert_eq!(handle.join().unwrap(), 2);
+/// assert_eq!(my_enum.key(), "a");
+/// ```
+///
+/// `split` generates the same `MyEnumCommon` struct, `MyEnumRest` enum with the same variants holding other fields, and `split()`
+/// method, that moves the enum into both of them. Variants without other fields become unit variants of `MyEnumRest`.
+/// It only supports struct variants, since fields of tuple variant payloads are not visible to the macro:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(id: u64)]
+/// #[common_fields_config(split)]
+/// enum Job {
+///     Queued { id: u64 },
+///     Running { id: u64, pid: u32 },
+/// }
+///
+/// let (common, rest) = Job::Running { id: 1, pid: 42 }.split();
+/// assert_eq!(common.id, 1);
+/// assert!(matches!(rest, JobRest::Running { pid: 42 }));
+/// assert!(matches!(Job::Queued { id: 2 }.split().1, JobRest::Queued));
+/// ```
+///
+/// `columns` generates `MyEnumCommonColumns` struct with a `<field_name>s` vector for every common field, and `columns(items)` function,
+/// that moves common fields of all items into it. Like with `proptest`, type of a field is taken from its first owning annotation,
+/// or from its first annotation if there are none, so it has to be the actual type of the field:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: str)]
+/// #[common_field(own_only key: String)]
+/// #[common_field(seq: u64)]
+/// #[common_fields_config(columns)]
+/// enum MyEnum {
+///     VariantOne { key: String, seq: u64 },
+///     VariantTwo { key: String, seq: u64 },
+/// }
+///
+/// let columns = MyEnum::columns([
+///     MyEnum::VariantOne { key: "a".into(), seq: 1 },
+///     MyEnum::VariantTwo { key: "b".into(), seq: 2 },
+/// ]);
+/// assert_eq!(columns.keys, vec!["a", "b"]);
+/// assert_eq!(columns.seqs, vec![1, 2]);
+/// ```
+///
+/// `display = "template"` implements `Display` for the enum, formatting the template with `{variant}` as the variant name
+/// and `{field_name}` as the common field. Format specs like `{seq:>4}` work as in `format!`:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_field(seq: u64)]
+/// #[common_fields_config(display = "{variant}({key}, seq={seq})")]
+/// enum MyEnum {
+///     Ping { key: String, seq: u64 },
+///     Data { key: String, seq: u64, payload: Vec<u8> },
+/// }
+///
+/// let my_enum = MyEnum::Ping { key: "a".into(), seq: 1 };
+/// assert_eq!(my_enum.to_string(), "Ping(a, seq=1)");
+/// ```
+///
+/// `debug` implements `Debug` for the enum, that prints the variant name and common fields (but not other fields of the variant).
+/// Values of fields with `redact` option in any of their annotations are replaced with `<redacted>`,
+/// so logging the enum with `{:?}` doesn't leak secrets. Fields don't need to implement `Debug` to be redacted:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_field(token: String, redact)]
+/// #[common_fields_config(debug)]
+/// enum MyEnum {
+///     Login { key: String, token: String },
+///     #[common_field_skip(key, token)]
+///     Logout,
+/// }
+///
+/// let my_enum = MyEnum::Login { key: "a".into(), token: "secret".into() };
+/// assert_eq!(format!("{my_enum:?}"), r#"Login { key: "a", token: <redacted>, .. }"#);
+/// assert_eq!(format!("{:?}", MyEnum::Logout), "Logout");
+/// ```
+///
+/// `metadata` option adds `COMMON_FIELDS_METADATA` constant with JSON description of the enum for external tooling
+/// (API docs or client generators). It contains names of the variants, names and types of the common fields (as written in annotations),
+/// and sorted names of all generated methods. A test or a build step of your crate can write it to a file:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String)]
+/// #[common_field(copy seq: u64)]
+/// #[common_fields_config(metadata)]
+/// enum MyEnum {
+///     VariantOne { key: String, seq: u64 },
+///     VariantTwo { key: String, seq: u64 },
+/// }
+///
+/// assert_eq!(
+///     MyEnum::COMMON_FIELDS_METADATA,
+///     r#"{"enum":"MyEnum","variants":["VariantOne","VariantTwo"],"fields":[{"name":"key","type":"String"},{"name":"seq","type":"u64"}],"methods":["key","key_mut","seq"]}"#
+/// );
+/// ```
+///
+/// `remote = "path::to::Enum"` generates accessors for an enum from other crate, that the annotated enum is a copy of
+/// (with the same variants and fields), like `#[serde(remote = "...")]` does. Since inherent methods can't be added to foreign types,
+/// methods that take `self` become methods of an extension trait implemented for the remote enum, named `<EnumName>CommonFields`
+/// or given with `#[common_fields_trait(...)]`. Everything else (like trait implementations) is still generated for the local copy:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// mod other_crate {
+///     pub enum Event {
+///         Created { key: String, size: u64 },
+///         Deleted { key: String },
+///     }
+/// }
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut key: String)]
+/// #[common_fields_config(remote = "other_crate::Event")]
+/// #[allow(dead_code)]
+/// enum Event {
+///     Created { key: String, size: u64 },
+///     Deleted { key: String },
+/// }
+///
+/// let mut event = other_crate::Event::Deleted { key: "a".to_string() };
+/// event.key_mut().push('b');
+/// assert_eq!(event.key(), "ab");
+/// ```
+/// For enums with shapes that are not practical to copy, see [`enum_common_fields!`](macro@crate::enum_common_fields).
+///
+/// `constructors` generates `new_<variant_name>()` constructor for every struct and unit variant, that takes the common fields
+/// (in order of their first annotations, named like in them) and then other fields of the variant (in order of declaration).
+/// Fields overridden with `#[common_field_via]` are taken as other fields of the variant:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(id: u64)]
+/// #[common_field(name: String)]
+/// #[common_fields_config(constructors)]
+/// enum Job {
+///     Pending { name: String, id: u64 },
+///     Running { id: u64, pid: u32, name: String },
+/// }
+///
+/// let job = Job::new_running(1, "build".to_string(), 42);
+/// assert!(matches!(job, Job::Running { id: 1, pid: 42, .. }));
+/// assert_eq!(Job::new_pending(2, "test".to_string()).name(), "test");
+/// ```
+///
+/// `frozen = "name, ..."` lists names of all methods the derive generates (accessors, `collect` functions, group accessors,
+/// and functions generated by enum options), and fails compilation if they are not exactly the listed ones.
+/// It's a cheap guard for library authors against accidentally changing public API when editing annotations:
+/// ```rust
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(own key: String)]
+/// #[common_field(copy seq: u64)]
+/// #[common_fields(frozen = "key, key_mut, into_key, seq")]
+/// enum MyEnum {
+///     VariantOne { key: String, seq: u64 },
+///     VariantTwo { key: String, seq: u64 },
+/// }
+/// ```
+/// ```compile_fail
+/// # use enum_common_fields::EnumCommonFields;
+/// #[derive(EnumCommonFields)]
+/// #[common_field(own key: String)]
+/// #[common_fields(frozen = "key, into_key")] // Fails, since key_mut() is generated too
+/// enum MyEnum {
+///     VariantOne { key: String },
+///     VariantTwo { key: String },
+/// }
+/// ```
+///
+/// With `audit` feature enabled, `audit_hook = path::to::function` option makes every accessor of every field (and every item
+/// read by `collect` functions) call the function with the field name before accessing the field. The function needs to accept `&'static str`:
+/// ```ignore
+/// fn on_access(field_name: &'static str) {
+///     log::info!("accessed {field_name}");
+/// }
+///
+/// #[derive(EnumCommonFields)]
+/// #[common_field(mut token: String)]
+/// #[common_fields_config(audit_hook = on_access)]
+/// enum Credentials {
+///     Basic { token: String, user: String },
+///     Bearer { token: String },
+/// }
+/// ```
+///
+/// With `proptest` feature enabled, `proptest` option generates `common_strategy()` function for property tests over the common fields.
+/// It takes a strategy for every common field, in order of their first `#[common_field]` annotation, and a strategy for the variants,
+/// and returns a strategy that generates values with the variants strategy and sets their common fields to values from the field strategies.
+/// Your crate needs to depend on `proptest` itself, and the enum and the fields need to implement `Debug`:
+/// ```ignore
+/// #[derive(Clone, Debug, EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_field(seq: u64)]
+/// #[common_fields_config(proptest)]
+/// enum Message {
+///     Ping { key: String, seq: u64 },
+///     Data { key: String, seq: u64, payload: Vec<u8> },
+/// }
+///
+/// let variants = prop_oneof![
+///     Just(Message::Ping { key: String::new(), seq: 0 }),
+///     any::<Vec<u8>>().prop_map(|payload| Message::Data { key: String::new(), seq: 0, payload }),
+/// ];
+/// let strategy = Message::common_strategy("[a-z]{8}", 1..100u64, variants);
+/// ```
+///
+/// With `arbitrary` feature enabled, `arbitrary` option implements `arbitrary::Arbitrary` for `MyEnumCommon` struct
+/// generated by `into_common` or `common_owned` option (so one of them is required), and generates `with_arbitrary_common(u)` method,
+/// that replaces every common field with an arbitrary value. Fuzz harnesses can use it to mutate only the envelope of otherwise
+/// valid values. Your crate needs to depend on `arbitrary` itself, and the field types need to implement `Arbitrary`:
+/// ```ignore
+/// #[derive(EnumCommonFields)]
+/// #[common_field(key: String)]
+/// #[common_field(copy seq: u64)]
+/// #[common_fields_config(into_common, arbitrary)]
+/// enum Message {
+///     Ping { key: String, seq: u64 },
+///     Data { key: String, seq: u64, payload: Vec<u8> },
+/// }
+///
+/// fuzz_target!(|data: &[u8]| {
+///     let mut u = Unstructured::new(data);
+///     let message = Message::Data { key: String::new(), seq: 0, payload: valid_payload() };
+///     if let Ok(message) = message.with_arbitrary_common(&mut u) {
+///         handle(message);
+///     }
+/// });
+/// ```
+#[proc_macro_derive(
+    EnumCommonFields,
+    attributes(
+        common_field,
+        common_fields_config,
+        common_fields,
+        common_fields_group,
+        common_delegate,
+        common_tuple,
+        common_fields_trait,
+        common_fields_hash,
+        common_fields_eq,
+        common_fields_ord,
+        common_field_skip,
+        common_field_rename,
+        common_field_at,
+        common_field_via,
+        common_field_default,
+        common
+    )
+)]
+pub fn common_fields_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+
+    let mut common_fields = parse_common_fields_attributes(&ast);
+    common_fields.extend(parse_common_markers(&ast, &common_fields));
+    let config = parse_config_attributes(&ast);
+    if config.auto {
+        common_fields.extend(detect_common_fields(&ast, &config.exclude, &common_fields));
+    } else if !config.exclude.is_empty() {
+        panic!(
+            "exclude option of #[common_fields_config] is supported only together with auto option"
+        )
+    }
+    let groups = parse_group_attributes(&ast);
+    let delegates = parse_delegate_attributes(&ast);
+    let tuples = parse_tuple_attributes(&ast);
+    let common_trait = parse_trait_attribute(&ast);
+    let hashed_fields = parse_field_list_attribute(&ast, "common_fields_hash");
+    let compared_fields = parse_field_list_attribute(&ast, "common_fields_eq");
+    let ordered_fields = parse_field_list_attribute(&ast, "common_fields_ord");
+    for common_field in &mut common_fields {
+        config.apply_defaults(common_field);
+    }
+
+    if common_fields.is_empty() && groups.is_empty() && delegates.is_empty() && tuples.is_empty() {
+        panic!("EnumCommonFields requires at least one #[common_field], #[common_fields_group], #[common_delegate] or #[common_tuple] annotation, or #[common] field marker")
+    }
+    let distinct_fields = distinct_common_fields(&common_fields);
+    for common_field in common_fields
+        .iter()
+        .filter(|common_field| common_field.borrow || common_field.borrow_mut)
+    {
+        for (attribute, listed_fields) in [
+            ("common_fields_hash", &hashed_fields),
+            ("common_fields_eq", &compared_fields),
+            ("common_fields_ord", &ordered_fields),
+        ] {
+            if let Some(listed_fields) = listed_fields {
+                if listed_fields.len() != 1 || listed_fields[0] != common_field.field_name {
+                    panic!(
+                        "\"borrow\" option requires Hash, Eq and Ord of the enum to behave like those of the field, so it needs #[{attribute}({})]",
+                        common_field.field_name
+                    )
+                }
+            }
+        }
+        // Derives below #[derive(EnumCommonFields)] are visible here and get a clearer message,
+        // the ones in the same attribute are caught by the checks generated below
+        if let Some(derived) = parse_derived_traits(&ast).into_iter().find(|derived| {
+            ["Hash", "PartialEq", "Eq", "PartialOrd", "Ord"].contains(&derived.to_string().as_str())
+        }) {
+            panic!("\"borrow\" option requires Hash, Eq and Ord of the enum to behave like those of the field, so it can't be used with derived {derived}")
+        }
+    }
+    // Implementations of Hash, PartialEq or PartialOrd that aren't generated from the borrowed field fail compilation:
+    // if the enum implements the trait, both implementations of the check trait apply and the call is ambiguous
+    let mut borrow_checks = quote!();
+    if let Some(common_field) = common_fields
+        .iter()
+        .find(|common_field| common_field.borrow || common_field.borrow_mut)
+    {
+        let enum_name = &ast.ident;
+        for (listed_fields, trait_path, check_name) in [
+            (
+                &hashed_fields,
+                quote!(::core::hash::Hash),
+                "BorrowNeedsCommonFieldsHash",
+            ),
+            (
+                &compared_fields,
+                quote!(::core::cmp::PartialEq),
+                "BorrowNeedsCommonFieldsEq",
+            ),
+            (
+                &ordered_fields,
+                quote!(::core::cmp::PartialOrd),
+                "BorrowNeedsCommonFieldsOrd",
+            ),
+        ] {
+            if listed_fields.is_none() {
+                let check_name = Ident::new(check_name, common_field.field_name.span());
+                borrow_checks.extend(quote! {
+                    const _: fn() = || {
+                        trait #check_name<Marker> {
+                            fn check() {}
+                        }
+                        impl<T: ?Sized> #check_name<()> for T {}
+                        struct Implemented;
+                        impl<T: ?Sized + #trait_path> #check_name<Implemented> for T {}
+                        let _ = <#enum_name as #check_name<_>>::check;
+                    };
+                });
+            }
+        }
+    }
+    let redacted_fields: Vec<_> = common_fields
+        .iter()
+        .filter(|common_field| common_field.redact)
+        .map(|common_field| common_field.field_name.clone())
+        .collect();
+    if !redacted_fields.is_empty() && !config.debug {
+        panic!("\"redact\" option requires \"debug\" option of #[common_fields_config]")
+    }
+    for (renamed, _) in &config.rename_map {
+        if !common_fields
+            .iter()
+            .any(|common_field| common_field.field_name == *renamed)
+        {
+            panic!("rename_map renames field {renamed}, which has no #[common_field] annotation")
+        }
+    }
+
+    let enum_name = ast.ident;
+    let enum_vis = ast.vis;
+    let mut variants: Vec<_> = match ast.data {
+        syn::Data::Enum(e) => match parse_enum_variants(e) {
+            Ok(variants) => variants,
+            Err(error) => return error.to_compile_error().into(),
+        },
+        _ => panic!("EnumCommonFields can only be applied to enums"),
+    };
+    // Fields from "flatten" syntax are reached through the flattened field, the same way as fields overridden by #[common_field_via]
+    for common_field in &common_fields {
+        let Some(outer) = &common_field.through else {
+            continue;
+        };
+        let inner = &common_field.field_name;
+        for variant in &mut variants {
+            if variant.skipped_fields.contains(outer) {
+                if !variant.skipped_fields.contains(inner) {
+                    variant.skipped_fields.push(inner.clone());
+                }
+                continue;
+            }
+            if variant.is_unit || variant.delegated || variant.field_override(inner).is_some() {
+                continue;
+            }
+            let outer_ident = variant.field_ident(outer);
+            let expression: syn::Expr = match variant.field_override(outer) {
+                Some(expression) => syn::parse_quote!((#expression).#inner),
+                None if variant.is_struct => {
+                    if !variant.fields.contains(outer_ident) {
+                        continue;
+                    }
+                    syn::parse_quote!(#outer_ident.#inner)
+                }
+                None => syn::parse_quote!(v.#outer_ident.#inner),
+            };
+            variant.overridden_fields.push((inner.clone(), expression));
+        }
+    }
+    // Fields used by any annotation, that variant attributes can refer to
+    let annotated_fields: Vec<_> = common_fields
+        .iter()
+        .map(|common_field| &common_field.field_name)
+        .chain(
+            groups
+                .iter()
+                .flat_map(|group| group.fields.iter().map(|(field_name, _)| field_name)),
+        )
+        .chain(delegates.iter().map(|delegate| &delegate.field_name))
+        .collect();
+    for variant in &variants {
+        for skipped in &variant.skipped_fields {
+            if !annotated_fields.contains(&skipped) {
+                panic!(
+                    "Variant {} skips field {skipped}, which is not used by any annotation",
+                    variant.name
+                )
+            }
+        }
+        for (renamed, _) in &variant.renamed_fields {
+            if !annotated_fields.contains(&renamed) {
+                panic!(
+                    "Variant {} renames field {renamed}, which is not used by any annotation",
+                    variant.name
+                )
+            }
+        }
+        for (defaulted, _) in &variant.default_fields {
+            if !annotated_fields.contains(&defaulted) {
+                panic!(
+                    "Variant {} sets default of field {defaulted}, which is not used by any annotation",
+                    variant.name
+                )
+            }
+        }
+        for (overridden, _) in &variant.overridden_fields {
+            if !annotated_fields.contains(&overridden) {
+                panic!(
+                    "Variant {} overrides field {overridden}, which is not used by any annotation",
+                    variant.name
+                )
+            }
+        }
+    }
+
+    let mut stream = quote!();
+    let mut since_table = Vec::new();
+    let mut pinned_fields = Vec::new();
+    let mut missing_field_error = None;
+    let mut method_names = Vec::new(); // Names of all generated methods, for the frozen option
+    let methods_vis = config.vis();
+    let mutable_fields: Vec<_> = common_fields // Fields with a mutable accessor, that get DerefMut together with Deref
+        .iter()
+        .filter(|common_field| common_field.kinds.contains(&GetterKind::Mutable))
+        .map(|common_field| common_field.field_name.clone())
+        .collect();
+    let mut trait_impls: Vec<(syn::Path, Vec<syn::ImplItem>)> = Vec::new(); // Methods of user traits from "impl" option, grouped by the trait
+    let mut display_field: Option<Ident> = None; // Field with "display" option
+
+    for CommonField {
+        cfg,
+        kinds,
+        field_name,
+        field_type,
+        resulting_name,
+        kind_names,
+        via,
+        since,
+        no_coverage,
+        default,
+        get,
+        collect,
+        as_mut,
+        borrow,
+        borrow_mut,
+        from,
+        display,
+        eq,
+        implemented,
+        vis,
+        inline,
+        constant,
+        doc,
+        aliases,
+        options,
+        ..
+    } in common_fields
+    {
+        if implemented.is_some() && kinds.len() != 1 {
+            panic!("\"impl\" option is supported only for annotations that generate one accessor")
+        }
+        if implemented.is_some() && cfg.is_some() {
+            panic!("\"impl\" option can't be used with cfg(...) prefix, since the trait implementation needs the method either way")
+        }
+        // Default cfg from "defaults(cfg(...))" gates accessors like the prefix, except the ones implementing trait methods,
+        // since the trait implementation needs them either way
+        let cfg = cfg.or_else(|| config.default_cfg.clone().filter(|_| implemented.is_none()));
+        if via.is_some()
+            && kinds.iter().any(|kind| {
+                matches!(
+                    kind,
+                    GetterKind::Owning
+                        | GetterKind::TryOwning
+                        | GetterKind::Mapping
+                        | GetterKind::Pinning
+                )
+            })
+        {
+            panic!("\"via\" method is supported only for accessors that borrow the field, so it can't be used with own, try_own, map and pin modifiers")
+        }
+        if let Some(option) = options.iter().find(|option| *option == "const") {
+            let conflict = if !kinds
+                .iter()
+                .all(|kind| matches!(kind, GetterKind::ReadOnly | GetterKind::Copying))
+            {
+                Some("\"const\" option is supported only for annotations that generate ref and copy accessors")
+            } else if config.audit_hook.is_some() {
+                Some("\"const\" option can't be used with audit_hook, since const fn can't call the hook")
+            } else if variants.iter().any(|variant| variant.delegated) {
+                Some("\"const\" option can't be used with delegated variants, since const fn can't call accessors of the inner type")
+            } else {
+                None
+            };
+            if let Some(conflict) = conflict {
+                return syn::Error::new(option.span(), conflict)
+                    .to_compile_error()
+                    .into();
+            }
+        }
+        if get.is_some() && kinds != [GetterKind::ReadOnly] {
+            panic!("\"get\" option is supported only for annotations that generate just the immutable accessor")
+        }
+        if default.is_some() && get.is_none() && !kinds.iter().all(GetterKind::returns_value) {
+            panic!("\"default\" option is supported only for annotations with own_only, clone, copy or cell modifier, since other accessors can't return a value (or with get option)")
+        }
+        // Items of the annotation are collected separately, so that cfg(...) prefix can gate all of them
+        let preceding = std::mem::take(&mut stream);
+        let mut attributes = quote!();
+        // Without doc option accessors get doc comment of the field from the first variant that has one,
+        // and without that the default one from "defaults(doc = ...)" option
+        if let Some(doc) = &doc {
+            let doc = format!(" {doc}");
+            attributes.extend(quote!(#[doc = #doc]));
+        } else if let Some(doc) = variants
+            .iter()
+            .map(|variant| variant.field_doc(&field_name))
+            .find(|doc| !doc.is_empty())
+        {
+            attributes.extend(quote!(#(#doc)*));
+        } else if let Some(doc) = config.default_doc(&field_name) {
+            let doc = format!(" {doc}");
+            attributes.extend(quote!(#[doc = #doc]));
+        }
+        attributes.extend(aliases.iter().map(|alias| quote!(#[doc(alias = #alias)])));
+        if let Some(version) = &since {
+            if !attributes.is_empty() {
+                attributes.extend(quote!(#[doc = ""]));
+            }
+            let doc = format!(" Available since {version}.");
+            attributes.extend(quote!(#[doc = #doc]));
+        }
+        if no_coverage {
+            attributes.extend(quote!(#[cfg_attr(coverage_nightly, coverage(off))]));
+        }
+        if let Some(inline) = &inline {
+            attributes.extend(quote!(#inline));
+        }
+        let mut context = AccessorContext {
+            enum_name: &enum_name,
+            variants: &variants,
+            field_name: &field_name,
+            field_type: &field_type,
+            default: default.as_ref(),
+            attributes,
+            audit: config.audit_call(&field_name),
+            vis: vis.unwrap_or_else(|| methods_vis.clone()),
+            constant,
+            must_use: false,
+            delegate_call: None,
+            via: via.as_ref(),
+        };
+        // Variants skipping the field make accessors return Option, unless there is a value to use instead
+        let skipped = get.is_none()
+            && variants
+                .iter()
+                .any(|variant| variant.skipped_fields.contains(&field_name));
+        let defaults_cover_skipped = default.is_some()
+            || variants.iter().all(|variant| {
+                !variant.skipped_fields.contains(&field_name)
+                    || variant.field_default(&field_name).is_some()
+            });
+        // With more than one accessor "as name" replaces the field name in their names, instead of the whole name
+        let (resulting_name, base_name) = match resulting_name {
+            Some(name) if kinds.len() > 1 => (None, name),
+            resulting_name => (resulting_name, config.base_name(&field_name)),
+        };
+        let mut owning_call = None; // Path to call the owning accessor with, for the from option
+        for (index, kind) in kinds.into_iter().enumerate() {
+            if kind.implements_trait() {
+                if implemented.is_some() {
+                    panic!("\"impl\" option can't be used with deref modifier, which implements Deref itself")
+                }
+                context.must_use = false;
+                context.delegate_call = Some(quote!(::core::ops::Deref::deref(v)));
+                stream.extend(context.trait_impl(
+                    quote!(::core::ops::Deref),
+                    quote!(type Target = #field_type;),
+                    quote!(&),
+                    quote!(fn deref(&self) -> &#field_type),
+                ));
+                if mutable_fields.contains(&field_name) {
+                    context.delegate_call = Some(quote!(::core::ops::DerefMut::deref_mut(v)));
+                    stream.extend(context.trait_impl(
+                        quote!(::core::ops::DerefMut),
+                        quote!(),
+                        quote!(&mut),
+                        quote!(fn deref_mut(&mut self) -> &mut #field_type),
+                    ));
+                }
+                continue;
+            }
+            // Accessor implementing a trait method gets the name of the method
+            let name = implemented
+                .as_ref()
+                .map(|path| path.segments.last().unwrap().ident.clone())
+                .or_else(|| kind_names.get(index).or(resulting_name.as_ref()).cloned())
+                .unwrap_or_else(|| config.accessor_name(&kind, &base_name));
+            // Methods gated by cfg(...) prefix may not exist, so they aren't listed
+            if cfg.is_none() {
+                method_names.push(name.to_string());
+                if let Some(version) = &since {
+                    let name = name.to_string();
+                    since_table.push(quote!((#name, #version)));
+                }
+            }
+            if kind == GetterKind::Owning {
+                owning_call = Some(match &implemented {
+                    Some(path) => quote!(#path),
+                    None => quote!(#enum_name::#name),
+                });
+            }
+            context.must_use = kind.must_use();
+            context.delegate_call = kind
+                .delegate_arguments()
+                .map(|arguments| quote!(v.#name(#(#arguments),*)));
+            let accessor = match kind {
+                kind if skipped
+                    && kind.has_optional_form()
+                    && !(kind.returns_value() && defaults_cover_skipped) =>
+                {
+                    context.optional_accessor(&kind, name)
+                }
+                GetterKind::ReadOnly => match &get {
+                    Some(get) => context.value_accessor(
+                        quote!(&),
+                        quote!(fn #name(&self) -> #field_type),
+                        |field| quote!((#get)(#field)),
+                    ),
+                    None => context.accessor(
+                        quote!(&),
+                        quote!(fn #name(&self) -> &#field_type),
+                        |field| field,
+                    ),
+                },
+                GetterKind::Mutable => context.accessor(
+                    quote!(&mut),
+                    quote!(fn #name(&mut self) -> &mut #field_type),
+                    |field| field,
+                ),
+                GetterKind::Owning => context.value_accessor(
+                    quote!(),
+                    quote!(fn #name(self) -> #field_type),
+                    |field| field,
+                ),
+                GetterKind::Replacing => context.accessor(
+                    quote!(&mut),
+                    quote!(fn #name(&mut self, new: #field_type) -> #field_type),
+                    |field| quote!(::core::mem::replace(#field, new)),
+                ),
+                GetterKind::Swapping => context.accessor(
+                    quote!(&mut),
+                    quote!(fn #name(&mut self, slot: &mut #field_type)),
+                    |field| quote!(::core::mem::swap(#field, slot)),
+                ),
+                GetterKind::Updating => context.accessor(
+                    quote!(&mut),
+                    quote!(fn #name(&mut self, f: impl FnOnce(&mut #field_type))),
+                    |field| quote!(f(#field)),
+                ),
+                GetterKind::Cloning | GetterKind::OwnedCloning => context.value_accessor(
+                    quote!(&),
+                    quote!(fn #name(&self) -> #field_type),
+                    |field| quote!(::core::clone::Clone::clone(#field)),
+                ),
+                GetterKind::Copying => context.value_accessor(
+                    quote!(&),
+                    quote!(fn #name(&self) -> #field_type),
+                    |field| quote!(*#field),
+                ),
+                GetterKind::Cow => {
+                    let cow = config.alloc_path(quote!(borrow::Cow));
+                    context.accessor(
+                        quote!(&),
+                        quote!(fn #name(&self) -> #cow<'_, #field_type>),
+                        |field| quote!(#cow::Borrowed(#field)),
+                    )
+                }
+                GetterKind::Arc => {
+                    let arc = config.alloc_path(quote!(sync::Arc));
+                    context.accessor(
+                        quote!(&),
+                        quote!(fn #name(&self) -> #arc<#field_type>),
+                        |field| quote!(#arc::clone(#field)),
+                    )
+                }
+                GetterKind::Pinning => {
+                    pinned_fields.push((field_name.clone(), field_type.clone()));
+                    context.pin_projection(name)
+                }
+                GetterKind::OptionDeref => context.accessor(
+                    quote!(&),
+                    quote!(fn #name(&self) -> ::core::option::Option<&#field_type>),
+                    // Matching instead of calling as_deref() to support types that don't implement Deref
+                    |field| {
+                        quote! {
+                            match #field {
+                                ::core::option::Option::Some(value) => ::core::option::Option::Some(value),
+                                ::core::option::Option::None => ::core::option::Option::None,
+                            }
+                        }
+                    },
+                ),
+                GetterKind::AsRef => context.accessor(
+                    quote!(&),
+                    quote!(fn #name(&self) -> &#field_type),
+                    |field| quote!(::core::convert::AsRef::<#field_type>::as_ref(#field)),
+                ),
+                GetterKind::Expecting(message) => context.accessor(
+                    quote!(&),
+                    quote!(fn #name(&self) -> &#field_type),
+                    |field| {
+                        // Message is used as a format string, so braces in it need escaping
+                        let message = message.replace('{', "{{").replace('}', "}}");
+                        quote! {
+                            match #field {
+                                ::core::option::Option::Some(value) => value,
+                                ::core::option::Option::None => ::core::panic!(#message),
+                            }
+                        }
+                    },
+                ),
+                GetterKind::CellGetting => context.value_accessor(
+                    quote!(&),
+                    quote!(fn #name(&self) -> #field_type),
+                    |field| quote!(::core::cell::Cell::get(#field)),
+                ),
+                GetterKind::AtomicLoading => context.accessor(
+                    quote!(&),
+                    quote!(fn #name(&self, order: ::core::sync::atomic::Ordering) -> #field_type),
+                    |field| quote!((#field).load(order)),
+                ),
+                GetterKind::AtomicStoring => context.accessor(
+                    quote!(&),
+                    quote!(fn #name(&self, value: #field_type, order: ::core::sync::atomic::Ordering)),
+                    |field| quote!((#field).store(value, order)),
+                ),
+                GetterKind::TryOwning => context.fallible_accessor(
+                    quote!(),
+                    quote!(fn #name(self) -> ::core::result::Result<#field_type, Self>),
+                    |field| quote!(::core::result::Result::Ok(#field)),
+                    |variant| {
+                        let pattern = variant.pattern();
+                        quote!(this @ #pattern => ::core::result::Result::Err(this))
+                    },
+                ),
+                GetterKind::Resulting => {
+                    let error_name = format_ident!("{enum_name}MissingFieldError");
+                    let field = field_name.to_string();
+                    missing_field_error = Some(error_name.clone());
+                    context.fallible_accessor(
+                        quote!(&),
+                        quote!(fn #name(&self) -> ::core::result::Result<&#field_type, #error_name>),
+                        |field| quote!(::core::result::Result::Ok(#field)),
+                        |variant| {
+                            let pattern = variant.pattern();
+                            let variant = variant.name.to_string();
+                            quote!(#pattern => ::core::result::Result::Err(#error_name { field: #field, variant: #variant }))
+                        },
+                    )
+                }
+                GetterKind::Indexed => context.indexed_accessor(name),
+                GetterKind::TryConverting(source) => {
+                    let source = syn::parse_str::<syn::Type>(&source).unwrap();
+                    context.accessor(
+                        quote!(&),
+                        quote!(fn #name(&self) -> ::core::result::Result<#field_type, <#source as ::core::convert::TryInto<#field_type>>::Error>),
+                        |field| quote!(<#source as ::core::convert::TryInto<#field_type>>::try_into(::core::clone::Clone::clone(#field))),
+                    )
+                }
+                GetterKind::Mapping => context.mapper(name),
+                GetterKind::StrOwning => {
+                    let string = config.alloc_path(quote!(string::String));
+                    context.accessor(
+                        quote!(),
+                        quote!(fn #name(self) -> #string),
+                        |field| quote!(::core::convert::Into::into((#field).as_str())),
+                    )
+                }
+                GetterKind::Bytes => context.accessor(
+                    quote!(&),
+                    quote!(fn #name(&self) -> &[u8]),
+                    |field| quote!(::bytemuck::bytes_of::<#field_type>(#field)),
+                ),
+                GetterKind::Dereferencing => unreachable!("Trait implementations are generated above"),
+            };
+            match &implemented {
+                Some(path) => {
+                    let mut trait_path = path.clone();
+                    trait_path.segments.pop();
+                    trait_path.segments.pop_punct();
+                    let method = trait_method(accessor);
+                    let key = quote!(#trait_path).to_string();
+                    match trait_impls
+                        .iter_mut()
+                        .find(|(other, _)| quote!(#other).to_string() == key)
+                    {
+                        Some((_, methods)) => methods.push(method),
+                        None => trait_impls.push((trait_path, vec![method])),
+                    }
+                }
+                None => stream.extend(accessor),
+            }
+        }
+        if as_mut {
+            context.must_use = false;
+            context.delegate_call = Some(quote!(::core::convert::AsMut::<#field_type>::as_mut(v)));
+            stream.extend(context.trait_impl(
+                quote!(::core::convert::AsMut<#field_type>),
+                quote!(),
+                quote!(&mut),
+                quote!(fn as_mut(&mut self) -> &mut #field_type),
+            ));
+        }
+        if display {
+            if let Some(other) = display_field.replace(field_name.clone()) {
+                panic!("\"display\" option can be set only for one field, but it's set for {other} and {field_name}")
+            }
+            context.must_use = false;
+            context.delegate_call = Some(quote!(::core::fmt::Display::fmt(v, f)));
+            let method = trait_method(context.accessor(
+                quote!(&),
+                quote!(fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result),
+                |field| quote!(::core::fmt::Display::fmt(#field, f)),
+            ));
+            stream.extend(quote! {
+                impl ::core::fmt::Display for #enum_name {
+                    #method
+                }
+            });
+        }
+        if eq {
+            context.must_use = false;
+            context.delegate_call =
+                Some(quote!(::core::cmp::PartialEq::<#field_type>::eq(v, other)));
+            let method = trait_method(context.accessor(
+                quote!(&),
+                quote!(fn eq(&self, other: &#field_type) -> bool),
+                |field| quote!(<#field_type as ::core::cmp::PartialEq>::eq(#field, other)),
+            ));
+            // Impls for references make comparisons like `my_enum == "key"` work, like they do for String
+            stream.extend(quote! {
+                impl ::core::cmp::PartialEq<#field_type> for #enum_name {
+                    #method
+                }
+                impl<'a> ::core::cmp::PartialEq<&'a #field_type> for #enum_name {
+                    fn eq(&self, other: &&'a #field_type) -> bool {
+                        ::core::cmp::PartialEq::<#field_type>::eq(self, *other)
+                    }
+                }
+                impl ::core::cmp::PartialEq<#enum_name> for #field_type {
+                    fn eq(&self, other: &#enum_name) -> bool {
+                        ::core::cmp::PartialEq::<#field_type>::eq(other, self)
+                    }
+                }
+                impl<'a> ::core::cmp::PartialEq<#enum_name> for &'a #field_type {
+                    fn eq(&self, other: &#enum_name) -> bool {
+                        ::core::cmp::PartialEq::<#field_type>::eq(other, *self)
+                    }
+                }
+            });
+        }
+        if from {
+            let Some(owning_call) = owning_call else {
+                panic!("\"from\" option requires own or own_only modifier, since From is implemented with the owning accessor")
+            };
+            if skipped && !defaults_cover_skipped {
+                panic!("\"from\" option requires every variant to have the field {field_name} or a default value for it")
+            }
+            stream.extend(quote! {
+                impl ::core::convert::From<#enum_name> for #field_type {
+                    fn from(value: #enum_name) -> Self {
+                        #owning_call(value)
+                    }
+                }
+            });
+        }
+        // BorrowMut requires Borrow, so borrow_mut implements both
+        if borrow || borrow_mut {
+            context.must_use = false;
+            context.delegate_call = Some(quote!(::core::borrow::Borrow::<#field_type>::borrow(v)));
+            stream.extend(context.trait_impl(
+                quote!(::core::borrow::Borrow<#field_type>),
+                quote!(),
+                quote!(&),
+                quote!(fn borrow(&self) -> &#field_type),
+            ));
+        }
+        if borrow_mut {
+            context.delegate_call =
+                Some(quote!(::core::borrow::BorrowMut::<#field_type>::borrow_mut(v)));
+            stream.extend(context.trait_impl(
+                quote!(::core::borrow::BorrowMut<#field_type>),
+                quote!(),
+                quote!(&mut),
+                quote!(fn borrow_mut(&mut self) -> &mut #field_type),
+            ));
+        }
+        if let Some(collector_name) = collect {
+            let name = collector_name.unwrap_or_else(|| format_ident!("collect_{base_name}s"));
+            if cfg.is_none() {
+                method_names.push(name.to_string());
+                if let Some(version) = &since {
+                    let name = name.to_string();
+                    since_table.push(quote!((#name, #version)));
+                }
+            }
+            stream.extend(context.collector(name, config.alloc_path(quote!(vec::Vec))));
+        }
+        if let Some(cfg) = &cfg {
+            stream = add_cfg(cfg, stream);
+        }
+        stream = quote!(#preceding #stream);
+    }
+
+    for CommonFieldsGroup {
+        name,
+        fields,
+        mutable,
+    } in &groups
+    {
+        method_names.push(name.to_string());
+        stream.extend(generate_group_accessor(
+            &enum_name,
+            &variants,
+            fields,
+            name,
+            quote!(&),
+            &methods_vis,
+        ));
+        if *mutable {
+            let name = format_ident!("{name}_mut");
+            method_names.push(name.to_string());
+            stream.extend(generate_group_accessor(
+                &enum_name,
+                &variants,
+                fields,
+                &name,
+                quote!(&mut),
+                &methods_vis,
+            ));
+        }
+    }
+
+    for delegate in &delegates {
+        method_names.push(delegate.name.to_string());
+        stream.extend(generate_delegate(
+            &enum_name,
+            &variants,
+            delegate,
+            config.audit_call(&delegate.field_name),
+            &methods_vis,
+        ));
+    }
+
+    for tuple in &tuples {
+        let name = &tuple.name;
+        method_names.extend([
+            name.to_string(),
+            format!("{name}_mut"),
+            format!("into_{name}"),
+        ]);
+        stream.extend(generate_tuple_accessors(
+            &enum_name,
+            &variants,
+            tuple,
+            config.audit_call(name),
+            &methods_vis,
+        ));
+    }
+
+    if !pinned_fields.is_empty() {
+        stream.extend(generate_pin_guards(&enum_name, &pinned_fields));
+    }
+
+    if let Some(error_name) = missing_field_error {
+        stream.extend(generate_missing_field_error(
+            &enum_vis,
+            &enum_name,
+            &error_name,
+        ));
+    }
+
+    if config.common_iter {
+        stream.extend(generate_common_iter(&enum_name, &distinct_fields, &config));
+    }
+    if config.common_serde {
+        stream.extend(generate_common_serialize(
+            &enum_name,
+            &distinct_fields,
+            &config,
+        ));
+    }
+
+    for (enabled, kind, method_name) in [
+        (config.common, CommonStruct::Ref, "common"),
+        (config.common_mut, CommonStruct::Mut, "common_mut"),
+        (config.into_common, CommonStruct::Owned, "into_common"),
+        (config.common_owned, CommonStruct::Cloned, "common_owned"),
+    ] {
+        if enabled
+            && distinct_fields.is_empty()
+            && matches!(kind, CommonStruct::Ref | CommonStruct::Mut)
+        {
+            panic!("\"{method_name}\" option needs at least one common field without cfg(...) prefix, since the struct of references can't be empty")
+        }
+        if enabled {
+            method_names.push(method_name.to_string());
+            stream.extend(generate_common_struct(
+                &enum_vis,
+                &enum_name,
+                &variants,
+                &distinct_fields,
+                &config,
+                kind,
+            ));
+        }
+    }
+
+    if config.split {
+        method_names.push("split".to_string());
+        stream.extend(generate_split(
+            &enum_vis,
+            &enum_name,
+            &variants,
+            &distinct_fields,
+            &config,
+        ));
+    }
+
+    if config.columns {
+        method_names.push("columns".to_string());
+        stream.extend(generate_columns(
+            &enum_vis,
+            &enum_name,
+            &variants,
+            &distinct_fields,
+            &config,
+        ));
+    }
+
+    if config.constructors {
+        stream.extend(generate_constructors(
+            &enum_name,
+            &variants,
+            &distinct_fields,
+            &mut method_names,
+            &methods_vis,
+        ));
+    }
+
+    if config.arbitrary {
+        if !config.into_common && !config.common_owned {
+            panic!("\"arbitrary\" option needs \"into_common\" or \"common_owned\" option, that generate the struct of common fields")
+        }
+        if distinct_fields.is_empty() {
+            panic!("\"arbitrary\" option needs at least one common field without cfg(...) prefix")
+        }
+        method_names.push("with_arbitrary_common".to_string());
+        stream.extend(generate_arbitrary(
+            &enum_name,
+            &variants,
+            &distinct_fields,
+            &config,
+        ));
+    }
+
+    if config.proptest {
+        method_names.push("common_strategy".to_string());
+        stream.extend(generate_proptest_strategy(
+            &enum_name,
+            &variants,
+            &distinct_fields,
+            &methods_vis,
+        ));
+    }
+
+    if let Some(hashed_fields) = &hashed_fields {
+        stream.extend(generate_hash(
+            &enum_name,
+            &variants,
+            &distinct_fields,
+            hashed_fields,
+        ));
+    }
+
+    if let Some(ordered_fields) = &ordered_fields {
+        if compared_fields
+            .as_ref()
+            .is_some_and(|compared_fields| compared_fields != ordered_fields)
+        {
+            panic!("Ord has to agree with PartialEq, so #[common_fields_ord(...)] and #[common_fields_eq(...)] have to list the same fields")
+        }
+        stream.extend(generate_ord(
+            &enum_name,
+            &variants,
+            &distinct_fields,
+            ordered_fields,
+        ));
+    }
+
+    if let Some(compared_fields) = &compared_fields {
+        stream.extend(generate_eq(
+            &enum_name,
+            &variants,
+            &distinct_fields,
+            compared_fields,
+        ));
+    }
+
+    if let Some(template) = &config.display {
+        if let Some(field_name) = &display_field {
+            panic!(
+                "\"display\" enum option conflicts with \"display\" option of field {field_name}"
+            )
+        }
+        stream.extend(generate_display(
+            &enum_name,
+            &variants,
+            &distinct_fields,
+            template,
+        ));
+    }
+
+    if config.debug {
+        stream.extend(generate_debug(
+            &enum_name,
+            &variants,
+            &distinct_fields,
+            &redacted_fields,
+        ));
+    }
+
+    if !since_table.is_empty() {
+        stream.extend(quote! {
+            impl #enum_name {
+                /// Versions in which the generated accessors became available, as `(accessor_name, version)` pairs
+                #methods_vis const COMMON_FIELDS_SINCE: &'static [(&'static str, &'static str)] = &[#(#since_table),*];
+            }
+        });
+    }
+
+    for (trait_path, methods) in trait_impls {
+        stream.extend(quote! {
+            impl #trait_path for #enum_name {
+                #(#methods)*
+            }
+        });
+    }
+
+    if let Some(inline) = &config.inline {
+        stream = add_inline(inline, stream);
+    }
+
+    let object_safe_trait = common_trait
+        .as_ref()
+        .filter(|common_trait| common_trait.object_safe)
+        .map(|common_trait| common_trait.name.clone());
+    if let Some(remote) = &config.remote {
+        if common_trait
+            .as_ref()
+            .is_some_and(|common_trait| common_trait.payloads)
+        {
+            panic!("\"payloads\" option of #[common_fields_trait(...)] can't be used with \"remote\" option")
+        }
+        let trait_name = common_trait.map_or_else(
+            || format_ident!("{}CommonFields", remote.segments.last().unwrap().ident),
+            |common_trait| common_trait.name,
+        );
+        stream = generate_remote_trait(&enum_vis, remote, &trait_name, stream);
+    } else if let Some(common_trait) = &common_trait {
+        stream.extend(generate_trait(
+            &enum_vis,
+            &enum_name,
+            &variants,
+            common_trait,
+            &stream,
+        ));
+    }
+    // Using the trait object type fails compilation, if some accessor makes the trait not object safe
+    if let Some(trait_name) = object_safe_trait {
+        stream.extend(quote! {
+            const _: ::core::option::Option<&dyn #trait_name> = ::core::option::Option::None;
+        });
+    }
+    stream.extend(borrow_checks);
+
+    if config.metadata {
+        let metadata = generate_metadata(&enum_name, &variants, &distinct_fields, &method_names);
+        stream.extend(quote! {
+            impl #enum_name {
+                /// JSON description of the common fields and the methods generated for them
+                #methods_vis const COMMON_FIELDS_METADATA: &'static str = #metadata;
+            }
+        });
+    }
+
+    if let Some(frozen) = &config.frozen {
+        check_frozen_methods(frozen, method_names);
+    }
+    TokenStream::from(stream)
+}
+
+/// Generates accessors for common fields of enums defined in other crates, which can't be annotated with the derive.
+/// Since inherent methods can't be added to foreign types, accessors are methods of an extension trait,
+/// named `<EnumName>CommonFields` by default (or given with `as TraitName`), that has the visibility given before `for`.
+///
+/// Every field lists how to get it from every variant: `Variant { field }` takes a field of a struct variant,
+/// and `Variant(binding => expression)` binds the first element of a tuple variant, and evaluates the expression to the field.
+/// Fields are separated with `;`, and `mut` before a field generates the mutable accessor too:
+/// ```rust
+/// # use enum_common_fields::enum_common_fields;
+/// mod other_crate {
+///     pub struct Deletion { pub key: String }
+///     pub enum Event {
+///         Created { key: String, size: u64 },
+///         Deleted(Deletion),
+///     }
+/// }
+///
+/// enum_common_fields! {
+///     pub for other_crate::Event {
+///         mut key: String in Created { key }, Deleted(d => d.key);
+///     }
+/// }
+///
+/// let mut event = other_crate::Event::Deleted(other_crate::Deletion { key: "a".to_string() });
+/// event.key_mut().push('b');
+/// assert_eq!(event.key(), "ab");
+/// ```
+/// Several enums can be listed in one macro call, each starting with `for`.
+#[proc_macro]
+pub fn enum_common_fields(input: TokenStream) -> TokenStream {
+    let foreign_enums = parse_macro_input!(input with syn::punctuated::Punctuated::<ForeignEnum, syn::parse::Nothing>::parse_terminated);
+    foreign_enums
+        .iter()
+        .map(generate_foreign_accessors)
+        .collect::<proc_macro2::TokenStream>()
+        .into()
+}
+
+/// Defines an enum with fields of the `common { ... }` block added to every variant, and derives accessors for them.
+/// Entries of the block are written like `#[common_field]` annotations without options, so `id: Uuid` generates `id()` accessor,
+/// and `mut + own id: Uuid` generates `id()`, `id_mut()` and `into_id()`. Variants can be struct or unit ones
+/// (that get only the common fields), and attributes of the enum (including other annotations of the derive) are kept:
+/// ```rust
+/// # use enum_common_fields::common_fields_enum;
+/// common_fields_enum! {
+///     #[derive(Debug)]
+///     pub enum Job {
+///         common { id: u64, mut name: String }
+///         Pending,
+///         Running { pid: u32 },
+///     }
+/// }
+///
+/// let mut job = Job::Running { id: 1, name: "build".to_string(), pid: 42 };
+/// job.name_mut().push_str("-all");
+/// assert_eq!(job.name(), "build-all");
+/// assert_eq!(Job::Pending { id: 2, name: String::new() }.id(), &2);
+/// ```
+#[proc_macro]
+pub fn common_fields_enum(input: TokenStream) -> TokenStream {
+    generate_hoisted_enum(parse_macro_input!(input as HoistedEnum)).into()
+}
+
+/// Attribute alternative to the derive, that adds `#[derive(EnumCommonFields)]` to the enum, together with its annotations.
+/// `fields(...)` argument is written like the `common { ... }` block of [`common_fields_enum!`], and adds the fields to every
+/// struct and unit variant. Other arguments are options of `#[common_fields(...)]` annotation.
+/// Annotations and markers of the derive can still be used with the enum:
+/// ```rust
+/// # use enum_common_fields::with_common_fields;
+/// #[with_common_fields(fields(id: u64, mut name: String), constructors)]
+/// #[common_field(own_only name: String)]
+/// pub enum Job {
+///     Pending,
+///     Running { pid: u32 },
+/// }
+///
+/// let mut job = Job::new_running(1, "build".to_string(), 42);
+/// job.name_mut().push_str("-all");
+/// assert_eq!(job.into_name(), "build-all");
+/// ```
+/// If arguments can't be parsed, the enum is kept as it is, so the only error is the one about arguments.
+#[proc_macro_attribute]
+pub fn with_common_fields(arguments: TokenStream, item: TokenStream) -> TokenStream {
+    let item = proc_macro2::TokenStream::from(item);
+    let arguments = match syn::parse::<HoistedArguments>(arguments) {
+        Ok(arguments) => arguments,
+        Err(error) => {
+            let error = error.to_compile_error();
+            return quote!(#error #item).into();
+        }
+    };
+    let syn::ItemEnum {
+        mut attrs,
+        vis,
+        ident,
+        generics,
+        variants,
+        ..
+    } = match syn::parse2::<syn::ItemEnum>(item.clone()) {
+        Ok(item_enum) => item_enum,
+        Err(error) => {
+            let error = error.to_compile_error();
+            return quote!(#error #item).into();
+        }
+    };
+    if !generics.params.is_empty() {
+        let error =
+            syn::Error::new_spanned(generics, "with_common_fields doesn't support generic enums")
+                .to_compile_error();
+        return quote!(#error #item).into();
+    }
+    let config = &arguments.config;
+    if !config.is_empty() {
+        attrs.insert(0, syn::parse_quote!(#[common_fields(#(#config),*)]));
+    }
+    generate_hoisted_enum(HoistedEnum {
+        attrs,
+        vis,
+        name: ident,
+        common_fields: arguments.common_fields,
+        variants: variants.into_iter().collect(),
+    })
+    .into()
+}
+
+/// Generates the enum with common fields added to every variant, and the derive with annotations for them
+fn generate_hoisted_enum(
+    HoistedEnum {
+        attrs,
+        vis,
+        name,
+        common_fields,
+        variants,
+    }: HoistedEnum,
+) -> proc_macro2::TokenStream {
+    let annotations = common_fields.iter().map(
+        |HoistedField {
+             modifiers,
+             field_name,
+             field_type,
+         }| quote!(#[common_field(#modifiers #field_name: #field_type)]),
+    );
+    let common_names: Vec<_> = common_fields
+        .iter()
+        .map(|common_field| &common_field.field_name)
+        .collect();
+    let common_types: Vec<_> = common_fields
+        .iter()
+        .map(|common_field| &common_field.field_type)
+        .collect();
+    let variants = variants.iter().map(|variant| {
+        let syn::Variant {
+            attrs,
+            ident,
+            fields,
+            discriminant,
+        } = variant;
+        if discriminant.is_some() {
+            return syn::Error::new_spanned(
+                variant,
+                "Variants with common fields can't have discriminants",
+            )
+            .to_compile_error();
+        }
+        let fields = match fields {
+            Fields::Named(fields) => fields.named.iter().collect(),
+            Fields::Unit => Vec::new(),
+            Fields::Unnamed(_) => {
+                return syn::Error::new_spanned(
+                    variant,
+                    "Common fields can be added only to struct and unit variants",
+                )
+                .to_compile_error()
+            }
+        };
+        quote! {
+            #(#attrs)*
+            #ident { #(#common_names: #common_types,)* #(#fields,)* }
+        }
+    });
+    quote! {
+        #[derive(::enum_common_fields::EnumCommonFields)]
+        #(#annotations)*
+        #(#attrs)*
+        #vis enum #name {
+            #(#variants,)*
+        }
+    }
+}
+
+/// Generates extension trait with accessors of a foreign enum, and implements it for the enum
+fn generate_foreign_accessors(
+    ForeignEnum {
+        vis,
+        path,
+        trait_name,
+        fields,
+    }: &ForeignEnum,
+) -> proc_macro2::TokenStream {
+    let mut signatures = Vec::new();
+    let mut methods = Vec::new();
+    for ForeignField {
+        mutable,
+        field_name,
+        field_type,
+        branches,
+    } in fields
+    {
+        let mut accessors = vec![(field_name.clone(), quote!(&self), quote!(&))];
+        if *mutable {
+            accessors.push((
+                format_ident!("{field_name}_mut"),
+                quote!(&mut self),
+                quote!(&mut),
+            ));
+        }
+        for (name, receiver, ref_token) in accessors {
+            let signature = quote!(fn #name(#receiver) -> #ref_token #field_type);
+            let match_branches = branches.iter().map(
+                |ForeignBranch {
+                     variant,
+                     binding,
+                     expression,
+                 }| match expression {
+                    None => quote!(#path::#variant { #binding, .. } => #binding),
+                    Some(expression) => {
+                        quote!(#path::#variant(#binding, ..) => #ref_token (#expression))
+                    }
+                },
+            );
+            methods.push(quote! {
+                #signature {
+                    match self {
+                        #(#match_branches,)*
+                    }
+                }
+            });
+            signatures.push(signature);
+        }
+    }
+    let doc = format!(
+        " Common fields of [`{}`]",
+        quote!(#path).to_string().replace(' ', "")
+    );
+    quote! {
+        #[doc = #doc]
+        #vis trait #trait_name {
+            #(#signatures;)*
+        }
+
+        impl #trait_name for #path {
+            #(#methods)*
+        }
+    }
+}
+
+/// Things shared by all accessors generated from one #[common_field] annotation
+struct AccessorContext<'a> {
+    enum_name: &'a Ident,
+    variants: &'a [EnumVariantInfo],
+    field_name: &'a Ident,
+    field_type: &'a syn::Type,
+    default: Option<&'a syn::Expr>,
+    attributes: proc_macro2::TokenStream,
+    audit: proc_macro2::TokenStream, // Call of the audit hook, if there is one
+    vis: syn::Visibility,            // Visibility of inherent accessors
+    constant: bool,                  // Whether inherent accessors are const fn
+    must_use: bool,                  // Whether the accessor gets #[must_use], set for every kind
+    delegate_call: Option<proc_macro2::TokenStream>, // Call of the same accessor of the inner type `v`, set for every kind
+    via: Option<&'a proc_macro2::TokenStream>, // Method call that returns reference to the field of tuple variant payload
+}
+
+impl AccessorContext<'_> {
+    /// Implements standard trait for the enum with the only method, that returns the field like `accessor` does
+    fn trait_impl(
+        &self,
+        trait_path: proc_macro2::TokenStream,
+        associated_types: proc_macro2::TokenStream,
+        ref_token: proc_macro2::TokenStream,
+        signature: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let enum_name = self.enum_name;
+        let method = trait_method(self.accessor(ref_token, signature, |field| field));
+        quote! {
+            impl #trait_path for #enum_name {
+                #associated_types
+                #method
+            }
+        }
+    }
+
+    /// Generates accessor with given signature, that matches on `self` and passes the field to `wrap_field` in every branch.
+    /// `ref_token` is applied to the field of tuple variant payload, so it must match the receiver in the signature
+    fn accessor(
+        &self,
+        ref_token: proc_macro2::TokenStream,
+        signature: proc_macro2::TokenStream,
+        wrap_field: impl Fn(proc_macro2::TokenStream) -> proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        self.generate(signature, |variant| {
+            variant.assert_has_fields(&[self.field_name]);
+            self.field_branch(variant, &ref_token, &wrap_field)
+        })
+    }
+
+    /// Same as `accessor`, but variants that don't have the field return the default value, if there is one
+    fn value_accessor(
+        &self,
+        ref_token: proc_macro2::TokenStream,
+        signature: proc_macro2::TokenStream,
+        wrap_field: impl Fn(proc_macro2::TokenStream) -> proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let has_defaults = self.default.is_some()
+            || self
+                .variants
+                .iter()
+                .any(|variant| variant.field_default(self.field_name).is_some());
+        if !has_defaults {
+            return self.accessor(ref_token, signature, wrap_field);
+        }
+        self.fallible_accessor(ref_token.clone(), signature, &wrap_field, |variant| {
+            // Default of the variant takes precedence over the one from the default option
+            match variant.field_default(self.field_name).or(self.default) {
+                Some(default) => {
+                    let pattern = variant.pattern();
+                    quote!(#pattern => #default)
+                }
+                None => {
+                    variant.assert_has_fields(&[self.field_name]);
+                    self.field_branch(variant, &ref_token, &wrap_field)
+                }
+            }
+        })
+    }
+
+    /// Accessor of given kind that returns `None` for variants that don't have the field
+    fn optional_accessor(&self, kind: &GetterKind, name: Ident) -> proc_macro2::TokenStream {
+        let field_type = self.field_type;
+        let (ref_token, receiver, return_type) = match kind {
+            GetterKind::ReadOnly => (quote!(&), quote!(&self), quote!(&#field_type)),
+            GetterKind::Mutable => (quote!(&mut), quote!(&mut self), quote!(&mut #field_type)),
+            GetterKind::Owning => (quote!(), quote!(self), quote!(#field_type)),
+            _ => (quote!(&), quote!(&self), quote!(#field_type)),
+        };
+        self.fallible_accessor(
+            ref_token,
+            quote!(fn #name(#receiver) -> ::core::option::Option<#return_type>),
+            |field| {
+                let value = match kind {
+                    GetterKind::Cloning | GetterKind::OwnedCloning => {
+                        quote!(::core::clone::Clone::clone(#field))
+                    }
+                    GetterKind::Copying => quote!(*#field),
+                    GetterKind::CellGetting => quote!(::core::cell::Cell::get(#field)),
+                    _ => field,
+                };
+                quote!(::core::option::Option::Some(#value))
+            },
+            |variant| {
+                let pattern = variant.pattern();
+                quote!(#pattern => ::core::option::Option::None)
+            },
+        )
+    }
+
+    /// Same as `accessor`, but supports variants that don't have the field.
+    /// For such variants `on_missing` returns the whole match branch
+    fn fallible_accessor(
+        &self,
+        ref_token: proc_macro2::TokenStream,
+        signature: proc_macro2::TokenStream,
+        wrap_field: impl Fn(proc_macro2::TokenStream) -> proc_macro2::TokenStream,
+        on_missing: impl Fn(&EnumVariantInfo) -> proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        self.generate(signature, |variant| {
+            if variant.has_field(self.field_name) {
+                self.field_branch(variant, &ref_token, &wrap_field)
+            } else {
+                on_missing(variant)
+            }
+        })
+    }
+
+    fn field_branch(
+        &self,
+        variant: &EnumVariantInfo,
+        ref_token: &proc_macro2::TokenStream,
+        wrap_field: impl Fn(proc_macro2::TokenStream) -> proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        if let Some(via) = self.via {
+            if !variant.is_struct && variant.field_override(self.field_name).is_none() {
+                // Dereferencing the result makes it a place expression, just like the field access it replaces
+                let pattern = variant.tuple_pattern(quote!(v));
+                let field = wrap_field(quote!(#ref_token (*v.#via)));
+                return quote!(#pattern => #field);
+            }
+        }
+        let (pattern, fields) = variant.bind_fields(&[self.field_name], ref_token);
+        let field = wrap_field(fields.into_iter().next().unwrap());
+        quote!(#pattern => #field)
+    }
+
+    fn generate(
+        &self,
+        signature: proc_macro2::TokenStream,
+        branch: impl Fn(&EnumVariantInfo) -> proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let enum_name = self.enum_name;
+        let attributes = &self.attributes;
+        let audit = &self.audit;
+        let vis = &self.vis;
+        let constness = self.constant.then(|| quote!(const));
+        let must_use = self.must_use.then(|| quote!(#[must_use]));
+        let match_branches = variant_branches(self.variants, |variant| {
+            if !variant.delegated {
+                return branch(variant);
+            }
+            let Some(call) = &self.delegate_call else {
+                panic!(
+                    "Variant {} delegates to the inner type with #[common_field(delegate)], which is not supported by try_own, result, indexed and map accessors",
+                    variant.name
+                )
+            };
+            let pattern = variant.tuple_pattern(quote!(v));
+            quote!(#pattern => #call)
+        });
+        let matched = match_variants(quote!(self), &match_branches);
+        quote! {
+            impl #enum_name {
+                #attributes
+                #must_use
+                #vis #constness #signature {
+                    #audit
+                    #matched
+                }
+            }
+        }
+    }
+
+    fn indexed_accessor(&self, resulting_name: Ident) -> proc_macro2::TokenStream {
+        let field_type = self.field_type;
+        self.generate(
+            quote!(fn #resulting_name(&self) -> (usize, &#field_type)),
+            |variant| {
+                variant.assert_has_fields(&[self.field_name]);
+                let index = self
+                    .variants
+                    .iter()
+                    .position(|other| other.name == variant.name)
+                    .unwrap();
+                self.field_branch(variant, &quote!(&), |field| quote!((#index, #field)))
+            },
+        )
+    }
+
+    fn collector(
+        &self,
+        resulting_name: Ident,
+        vec: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let enum_name = self.enum_name;
+        let audit = &self.audit;
+        let field_type = self.field_type;
+        let attributes = &self.attributes;
+        let vis = &self.vis;
+        let match_branches = variant_branches(self.variants, |variant| {
+            variant.assert_has_fields(&[self.field_name]);
+            self.field_branch(variant, &quote!(&), |field| field)
+        });
+        let matched = match_variants(quote!(item), &match_branches);
+        quote! {
+            impl #enum_name {
+                #attributes
+                #[must_use]
+                #vis fn #resulting_name(items: &[Self]) -> #vec<&#field_type> {
+                    // Explicit return type lets match branches coerce to Deref target
+                    items.iter().map(|item| -> &#field_type {
+                        #audit
+                        #matched
+                    }).collect()
+                }
+            }
+        }
+    }
+
+    fn mapper(&self, resulting_name: Ident) -> proc_macro2::TokenStream {
+        let field_name = self.field_name;
+        let field_type = self.field_type;
+        self.generate(
+            quote!(fn #resulting_name(self, f: impl FnOnce(#field_type) -> #field_type) -> Self),
+            |variant| {
+                variant.assert_has_fields(&[self.field_name]);
+                variant.assert_not_overridden(field_name);
+                let EnumVariantInfo {
+                    name,
+                    is_struct,
+                    fields,
+                    ..
+                } = variant;
+                if *is_struct {
+                    // Rebuilding the variant from all of it's fields, replacing only the mapped one
+                    let rebuilt_fields = fields.iter().map(|field| {
+                        if field == variant.field_ident(field_name) {
+                            quote!(#field: f(#field))
+                        } else {
+                            quote!(#field)
+                        }
+                    });
+                    quote!(Self::#name{#(#fields),*} => Self::#name{#(#rebuilt_fields),*})
+                } else {
+                    let field_name = variant.field_ident(field_name);
+                    // Rebuilding the variant from all elements of the tuple, with the updated payload
+                    let elements: Vec<_> = (0..variant.tuple_len)
+                        .map(|i| {
+                            if i == variant.tuple_index {
+                                format_ident!("v")
+                            } else {
+                                format_ident!("__{i}")
+                            }
+                        })
+                        .collect();
+                    let bindings = elements.iter().map(|element| {
+                        if element == "v" {
+                            quote!(mut v)
+                        } else {
+                            quote!(#element)
+                        }
+                    });
+                    quote!(Self::#name(#(#bindings),*) => {
+                        v.#field_name = f(v.#field_name);
+                        Self::#name(#(#elements),*)
+                    })
+                }
+            },
+        )
+    }
+
+    fn pin_projection(&self, resulting_name: Ident) -> proc_macro2::TokenStream {
+        let enum_name = self.enum_name;
+        let audit = &self.audit;
+        let field_name = self.field_name;
+        let field_type = self.field_type;
+        let attributes = &self.attributes;
+        let vis = &self.vis;
+        let match_branches = variant_branches(self.variants, |variant| {
+            variant.assert_has_fields(&[self.field_name]);
+            variant.assert_not_overridden(field_name);
+            let EnumVariantInfo {
+                name, is_struct, ..
+            } = variant;
+            if !*is_struct {
+                // Payload struct could move the field in it's Drop impl, and we can't check it from here
+                panic!(
+                    "Variant {name} is a tuple variant, pin modifier supports only struct variants"
+                )
+            }
+            let binding = variant.field_binding(field_name);
+            quote!(Self::#name{#binding, ..} => #field_name)
+        });
+        let matched = match_variants(quote!(this), &match_branches);
+        quote! {
+            impl #enum_name {
+                #attributes
+                #vis fn #resulting_name(self: ::core::pin::Pin<&mut Self>) -> ::core::pin::Pin<&mut #field_type> {
+                    // SAFETY: the field is pinned structurally. Generated guards make sure that the enum
+                    // doesn't implement Drop and is Unpin only if all pinned fields are, and the field is
+                    // never moved out of a pinned enum by generated code
+                    #audit
+                    unsafe {
+                        self.map_unchecked_mut(|this| #matched)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Panics if generated methods are not exactly the ones listed in the frozen option
+fn check_frozen_methods(frozen: &[String], mut method_names: Vec<String>) {
+    method_names.sort();
+    method_names.dedup();
+    let missing: Vec<_> = frozen
+        .iter()
+        .filter(|name| !method_names.contains(name))
+        .map(String::as_str)
+        .collect();
+    let unexpected: Vec<_> = method_names
+        .iter()
+        .filter(|name| !frozen.contains(name))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() || !unexpected.is_empty() {
+        panic!(
+            "Generated methods differ from the frozen list. Missing: [{}], not in the list: [{}]",
+            missing.join(", "),
+            unexpected.join(", ")
+        )
+    }
+}
+
+/// Generates error type returned by `result` accessors
+fn generate_missing_field_error(
+    enum_vis: &syn::Visibility,
+    enum_name: &Ident,
+    error_name: &Ident,
+) -> proc_macro2::TokenStream {
+    let doc = format!(" Error returned by accessors of [`{enum_name}`] when current variant doesn't have the field");
+    let message = format!("variant {{}} of {enum_name} doesn't have field {{}}");
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #enum_vis struct #error_name {
+            /// Name of the missing field
+            pub field: &'static str,
+            /// Name of the variant that doesn't have the field
+            pub variant: &'static str,
+        }
+
+        impl ::core::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::write!(f, #message, self.variant, self.field)
+            }
+        }
+
+        impl ::core::error::Error for #error_name {}
+    }
+}
+
+/// Generates checks that make pin projections sound, the same way `pin-project` crate does.
+/// Enum can't implement `Drop`, because `drop` takes `&mut self` and could move pinned fields,
+/// and can't implement `Unpin` by hand, because it would allow moving out of pinned fields in safe code.
+fn generate_pin_guards(
+    enum_name: &Ident,
+    pinned_fields: &[(Ident, syn::Type)],
+) -> proc_macro2::TokenStream {
+    let pinned_struct = format_ident!("__{enum_name}PinnedFields");
+    let fields = pinned_fields
+        .iter()
+        .enumerate()
+        .map(|(i, (field_name, field_type))| {
+            let field = format_ident!("__{field_name}_{i}");
+            quote!(#field: #field_type)
+        });
+    quote! {
+        const _: () = {
+            trait MustNotImplDrop {}
+            #[allow(drop_bounds)]
+            impl<T: ::core::ops::Drop> MustNotImplDrop for T {}
+            impl MustNotImplDrop for #enum_name {}
+
+            #[allow(dead_code, non_snake_case)]
+            struct #pinned_struct<'pin> {
+                __lifetime: ::core::marker::PhantomData<&'pin ()>,
+                #(#fields,)*
+            }
+            impl<'pin> ::core::marker::Unpin for #enum_name where #pinned_struct<'pin>: ::core::marker::Unpin {}
+        };
+    }
+}
+
+/// Generates match that sets the field of `target` enum (a mutable reference) to `value`
+fn field_assignment(
+    variants: &[EnumVariantInfo],
+    field_name: &Ident,
+    target: proc_macro2::TokenStream,
+    value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let match_branches = variant_branches(variants, |variant| {
+        variant.assert_has_fields(&[field_name]);
+        variant.assert_not_overridden(field_name);
+        let EnumVariantInfo {
+            name, is_struct, ..
+        } = variant;
+        let variant_name = variant.field_ident(field_name);
+        if *is_struct {
+            quote!(Self::#name{#variant_name: __field, ..} => *__field = #value)
+        } else {
+            let pattern = variant.tuple_pattern(quote!(v));
+            quote!(#pattern => v.#variant_name = #value)
+        }
+    });
+    match_variants(target, &match_branches)
+}
+
+fn generate_proptest_strategy(
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    fields: &[(Ident, syn::Type)],
+    vis: &syn::Visibility,
+) -> proc_macro2::TokenStream {
+    let field_names: Vec<_> = fields.iter().map(|(field_name, _)| field_name).collect();
+    let field_types = fields.iter().map(|(_, field_type)| field_type);
+    let assignments = field_names.iter().map(|field_name| {
+        field_assignment(
+            variants,
+            field_name,
+            quote!(&mut __value),
+            quote!(#field_name),
+        )
+    });
+    quote! {
+        impl #enum_name {
+            /// Strategy that generates values using `variants` strategy,
+            /// and then sets every common field to a value generated by the strategy for that field
+            #vis fn common_strategy(
+                #(#field_names: impl ::proptest::strategy::Strategy<Value = #field_types>,)*
+                variants: impl ::proptest::strategy::Strategy<Value = Self>,
+            ) -> impl ::proptest::strategy::Strategy<Value = Self> {
+                ::proptest::strategy::Strategy::prop_map(
+                    (variants, #(#field_names,)*),
+                    |(mut __value, #(#field_names,)*)| {
+                        #(#assignments)*
+                        __value
+                    },
+                )
+            }
+        }
+    }
+}
+
+/// Implements `Arbitrary` for the owned common struct, and generates the method replacing common fields with arbitrary values
+fn generate_arbitrary(
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    fields: &[(Ident, syn::Type)],
+    config: &CommonFieldsConfig,
+) -> proc_macro2::TokenStream {
+    let common_name = format_ident!("{enum_name}Common");
+    let vis = config.vis();
+    let base_names: Vec<_> = fields
+        .iter()
+        .map(|(field_name, _)| config.base_name(field_name))
+        .collect();
+    let assignments = fields
+        .iter()
+        .zip(&base_names)
+        .map(|((field_name, _), base_name)| {
+            field_assignment(
+                variants,
+                field_name,
+                quote!(&mut self),
+                quote!(__common.#base_name),
+            )
+        });
+    quote! {
+        impl<'a> ::arbitrary::Arbitrary<'a> for #common_name {
+            fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+                ::core::result::Result::Ok(Self {
+                    #(#base_names: ::arbitrary::Arbitrary::arbitrary(u)?,)*
+                })
+            }
+        }
+
+        impl #enum_name {
+            /// Replaces every common field with an arbitrary value, keeping the variant and the rest of its fields
+            #vis fn with_arbitrary_common(mut self, u: &mut ::arbitrary::Unstructured<'_>) -> ::arbitrary::Result<Self> {
+                let __common = <#common_name as ::arbitrary::Arbitrary>::arbitrary(u)?;
+                #(#assignments;)*
+                ::core::result::Result::Ok(self)
+            }
+        }
+    }
+}
+
+fn generate_group_accessor(
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    fields: &[(Ident, syn::Type)],
+    resulting_name: &Ident,
+    ref_token: proc_macro2::TokenStream,
+    vis: &syn::Visibility,
+) -> proc_macro2::TokenStream {
+    let field_names: Vec<_> = fields.iter().map(|(field_name, _)| field_name).collect();
+    let field_types = fields.iter().map(|(_, field_type)| field_type);
+    let match_branches = variant_branches(variants, |variant| {
+        variant.assert_has_fields(&field_names);
+        let (pattern, fields) = variant.bind_fields(&field_names, &ref_token);
+        quote!(#pattern => (#(#fields,)*))
+    });
+    let matched = match_variants(quote!(self), &match_branches);
+    quote! {
+        impl #enum_name {
+            #[must_use]
+            #vis fn #resulting_name(#ref_token self) -> (#(#ref_token #field_types,)*) {
+                #matched
+            }
+        }
+    }
+}
+
+/// Generates method that calls the method with the same name on the common field
+fn generate_delegate(
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    CommonDelegate {
+        name,
+        receiver,
+        arguments,
+        output,
+        field_name,
+    }: &CommonDelegate,
+    audit: proc_macro2::TokenStream,
+    vis: &syn::Visibility,
+) -> proc_macro2::TokenStream {
+    let argument_names: Vec<_> = arguments
+        .iter()
+        .map(|(argument_name, _)| argument_name)
+        .collect();
+    let argument_types = arguments.iter().map(|(_, argument_type)| argument_type);
+    let match_branches = variant_branches(variants, |variant| {
+        variant.assert_has_fields(&[field_name]);
+        let (pattern, fields) = variant.bind_fields(&[field_name], &quote!());
+        let field = &fields[0];
+        quote!(#pattern => #field.#name(#(#argument_names),*))
+    });
+    let matched = match_variants(quote!(self), &match_branches);
+    quote! {
+        impl #enum_name {
+            #vis fn #name(#receiver, #(#argument_names: #argument_types),*) #output {
+                #audit
+                #matched
+            }
+        }
+    }
+}
+
+/// Turns generated inherent accessor into a method of trait implementation, that has no visibility, constness,
+/// #[must_use] and #[doc(alias = ...)]
+fn trait_method(accessor: proc_macro2::TokenStream) -> syn::ImplItem {
+    let accessor: syn::ItemImpl = syn::parse2(accessor).unwrap();
+    let mut method = accessor.items.into_iter().next().unwrap();
+    if let syn::ImplItem::Fn(method) = &mut method {
+        method.vis = syn::Visibility::Inherited;
+        method.sig.constness = None;
+        method.attrs.retain(allowed_in_trait_impl);
+    }
+    method
+}
+
+/// Returns false for attributes of accessors, that methods of trait implementations can't have
+fn allowed_in_trait_impl(attr: &syn::Attribute) -> bool {
+    let doc_alias = attr.path().is_ident("doc") && matches!(attr.meta, Meta::List(_));
+    !attr.path().is_ident("must_use") && !doc_alias
+}
+
+/// Generates trait with all generated methods that take `self`, and implements it for the enum with these methods
+fn generate_trait(
+    enum_vis: &syn::Visibility,
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    CommonFieldsTrait {
+        name: trait_name,
+        payloads,
+        ..
+    }: &CommonFieldsTrait,
+    generated: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let generated: syn::File = syn::parse2(generated.clone()).unwrap();
+    let accessors: Vec<_> = generated
+        .items
+        .into_iter()
+        .filter_map(|item| match item {
+            syn::Item::Impl(item) if item.trait_.is_none() => Some(gated_methods(item)),
+            _ => None,
+        })
+        .flatten()
+        .filter(|method| method.sig.receiver().is_some())
+        .map(|mut method| {
+            // Trait methods can't be const
+            method.sig.constness = None;
+            method
+        })
+        .collect();
+    let signatures = accessors.iter().map(|accessor| {
+        let cfgs = cfg_attributes(&accessor.attrs);
+        let signature = &accessor.sig;
+        quote!(#(#cfgs)* #signature;)
+    });
+    let methods = accessors.iter().map(|accessor| {
+        let cfgs = cfg_attributes(&accessor.attrs);
+        let signature = &accessor.sig;
+        let name = &signature.ident;
+        let arguments = signature.inputs.iter().map(|argument| match argument {
+            syn::FnArg::Receiver(_) => quote!(self),
+            syn::FnArg::Typed(argument) => {
+                let pattern = &argument.pat;
+                quote!(#pattern)
+            }
+        });
+        // Inherent methods take precedence over trait methods, so this calls the generated method
+        quote!(#(#cfgs)* #signature { Self::#name(#(#arguments),*) })
+    });
+    let mut payload_types = Vec::new();
+    let payload_impls: Vec<_> = if *payloads {
+        variants
+            .iter()
+            .filter(|variant| !variant.is_struct && !variant.is_unit && !variant.delegated)
+            .filter(|variant| {
+                // Several variants can hold the same payload type, but the trait can be implemented only once
+                let payload_type = &variant.field_types[variant.tuple_index];
+                let payload_type = quote!(#payload_type).to_string();
+                if payload_types.contains(&payload_type) {
+                    return false;
+                }
+                payload_types.push(payload_type);
+                true
+            })
+            .map(|variant| generate_payload_impl(trait_name, variant, &accessors))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let doc = format!(" Common fields of [`{enum_name}`]");
+    quote! {
+        #[doc = #doc]
+        #enum_vis trait #trait_name {
+            #(#signatures)*
+        }
+
+        impl #trait_name for #enum_name {
+            #(#methods)*
+        }
+
+        #(#payload_impls)*
+    }
+}
+
+/// Returns methods of the impl, with #[cfg(...)] attributes of the impl added to them
+fn gated_methods(item: syn::ItemImpl) -> Vec<syn::ImplItemFn> {
+    let cfgs: Vec<_> = cfg_attributes(&item.attrs).cloned().collect();
+    item.items
+        .into_iter()
+        .filter_map(|item| match item {
+            syn::ImplItem::Fn(mut method) => {
+                method.attrs.splice(0..0, cfgs.iter().cloned());
+                Some(method)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns #[cfg(...)] attributes among the attributes
+fn cfg_attributes(attrs: &[syn::Attribute]) -> impl Iterator<Item = &syn::Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("cfg"))
+}
+
+/// Adds #[cfg(...)] attribute to all generated impls
+fn add_cfg(cfg: &syn::Attribute, generated: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let mut generated: syn::File = syn::parse2(generated).unwrap();
+    for item in &mut generated.items {
+        if let syn::Item::Impl(item) = item {
+            item.attrs.push(cfg.clone());
+        }
+    }
+    quote!(#generated)
+}
+
+/// Adds #[inline] attribute to all generated functions that don't have one
+fn add_inline(
+    inline: &syn::Attribute,
+    generated: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let mut generated: syn::File = syn::parse2(generated).unwrap();
+    for item in &mut generated.items {
+        if let syn::Item::Impl(item) = item {
+            for item in &mut item.items {
+                if let syn::ImplItem::Fn(method) = item {
+                    if !method
+                        .attrs
+                        .iter()
+                        .any(|attr| attr.path().is_ident("inline"))
+                    {
+                        method.attrs.push(inline.clone());
+                    }
+                }
+            }
+        }
+    }
+    quote!(#generated)
+}
+
+/// Moves methods of inherent impls of the local copy of remote enum to an extension trait, and implements it for the remote enum.
+/// Branches of the methods match on `Self::Variant`, so they work for the remote enum as they are
+fn generate_remote_trait(
+    enum_vis: &syn::Visibility,
+    remote: &syn::Path,
+    trait_name: &Ident,
+    generated: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let generated: syn::File = syn::parse2(generated).unwrap();
+    let mut items = Vec::new();
+    let mut methods = Vec::new();
+    for item in generated.items {
+        match item {
+            syn::Item::Impl(item) if item.trait_.is_none() => methods.extend(
+                gated_methods(item)
+                    .into_iter()
+                    .filter(|method| method.sig.receiver().is_some())
+                    .map(|mut method| {
+                        method.vis = syn::Visibility::Inherited;
+                        method.sig.constness = None;
+                        method.attrs.retain(allowed_in_trait_impl);
+                        method
+                    }),
+            ),
+            item => items.push(item),
+        }
+    }
+    let signatures = methods.iter().map(|method| {
+        let cfgs = cfg_attributes(&method.attrs);
+        let signature = &method.sig;
+        quote!(#(#cfgs)* #signature;)
+    });
+    let doc = format!(
+        " Common fields of [`{}`]",
+        quote!(#remote).to_string().replace(' ', "")
+    );
+    quote! {
+        #(#items)*
+
+        #[doc = #doc]
+        #enum_vis trait #trait_name {
+            #(#signatures)*
+        }
+
+        impl #trait_name for #remote {
+            #(#methods)*
+        }
+    }
+}
+
+/// Generates implementation of the accessor trait for the payload of a tuple variant, with bodies of the match branches for the variant
+fn generate_payload_impl(
+    trait_name: &Ident,
+    variant: &EnumVariantInfo,
+    accessors: &[syn::ImplItemFn],
+) -> proc_macro2::TokenStream {
+    let payload_type = &variant.field_types[variant.tuple_index];
+    let methods = accessors.iter().map(|accessor| {
+        let signature = &accessor.sig;
+        let fail = |reason: &str| -> ! {
+            panic!(
+                "Method {} can't be implemented for payload of variant {}, since {reason}",
+                signature.ident, variant.name
+            )
+        };
+        let Some((syn::Stmt::Expr(syn::Expr::Match(matched), None), statements)) =
+            accessor.block.stmts.split_last()
+        else {
+            fail("it doesn't match on the variant")
+        };
+        let Some(arm) = matched
+            .arms
+            .iter()
+            .find(|arm| pattern_variant(&arm.pat) == Some(&variant.name))
+        else {
+            fail("it doesn't match on the variant")
+        };
+        let body = &arm.body;
+        if mentions_self(quote!(#body)) {
+            fail("it constructs the enum")
+        }
+        // The branch uses the payload through the binding of the pattern, so the binding is made for `self`
+        let binding = pattern_binding(&arm.pat).map(|binding| quote!(let #binding = self;));
+        let cfgs = cfg_attributes(&accessor.attrs);
+        quote! {
+            #(#cfgs)*
+            #signature {
+                #(#statements)*
+                #binding
+                #body
+            }
+        }
+    });
+    let cfgs = &variant.cfgs;
+    quote! {
+        #(#cfgs)*
+        impl #trait_name for #payload_type {
+            #(#methods)*
+        }
+    }
+}
+
+/// Returns name of the enum variant that the pattern matches, like `Variant` for `Self::Variant(v)` or `this @ Self::Variant(..)`
+fn pattern_variant(pattern: &syn::Pat) -> Option<&Ident> {
+    match pattern {
+        syn::Pat::TupleStruct(pattern) => Some(&pattern.path.segments.last()?.ident),
+        syn::Pat::Struct(pattern) => Some(&pattern.path.segments.last()?.ident),
+        syn::Pat::Path(pattern) => Some(&pattern.path.segments.last()?.ident),
+        syn::Pat::Ident(syn::PatIdent {
+            subpat: Some((_, pattern)),
+            ..
+        }) => pattern_variant(pattern),
+        _ => None,
+    }
+}
+
+/// Returns binding of the tuple variant element in the pattern, if there is one
+fn pattern_binding(pattern: &syn::Pat) -> Option<&Ident> {
+    match pattern {
+        syn::Pat::TupleStruct(pattern) => pattern.elems.iter().find_map(|element| match element {
+            syn::Pat::Ident(binding) => Some(&binding.ident),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// Whether tokens use `Self`, that means a different type in the implementation for the payload
+fn mentions_self(tokens: proc_macro2::TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(ident) => ident == "Self",
+        proc_macro2::TokenTree::Group(group) => mentions_self(group.stream()),
+        _ => false,
+    })
+}
+
+/// Generates immutable, mutable and owning accessors of the tuple element shared by all variants
+fn generate_tuple_accessors(
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    CommonTuple {
+        index,
+        name,
+        element_type,
+    }: &CommonTuple,
+    audit: proc_macro2::TokenStream,
+    vis: &syn::Visibility,
+) -> proc_macro2::TokenStream {
+    let match_branches = variant_branches(variants, |variant| {
+        let pattern = variant.element_pattern(*index, name);
+        quote!(#pattern => #name)
+    });
+    let matched = match_variants(quote!(self), &match_branches);
+    let mutable_name = format_ident!("{name}_mut");
+    let owning_name = format_ident!("into_{name}");
+    quote! {
+        impl #enum_name {
+            #[must_use]
+            #vis fn #name(&self) -> &#element_type {
+                #audit
+                #matched
+            }
+
+            #vis fn #mutable_name(&mut self) -> &mut #element_type {
+                #audit
+                #matched
+            }
+
+            #[must_use]
+            #vis fn #owning_name(self) -> #element_type {
+                #audit
+                #matched
+            }
+        }
+    }
+}
+
+/// Returns JSON object with names of the enum and its variants, names and types of common fields, and names of generated methods
+fn generate_metadata(
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    fields: &[(Ident, syn::Type)],
+    method_names: &[String],
+) -> String {
+    let variants: Vec<_> = variants
+        .iter()
+        .map(|variant| json_string(&variant.name.to_string()))
+        .collect();
+    let fields: Vec<_> = fields
+        .iter()
+        .map(|(field_name, field_type)| {
+            format!(
+                r#"{{"name":{},"type":{}}}"#,
+                json_string(&field_name.to_string()),
+                json_string(&type_string(field_type)),
+            )
+        })
+        .collect();
+    let mut methods: Vec<_> = method_names.iter().map(|name| json_string(name)).collect();
+    methods.sort();
+    methods.dedup();
+    format!(
+        r#"{{"enum":{},"variants":[{}],"fields":[{}],"methods":[{}]}}"#,
+        json_string(&enum_name.to_string()),
+        variants.join(","),
+        fields.join(","),
+        methods.join(","),
+    )
+}
+
+/// Returns the type as it's usually written, instead of spacing of token streams (`Vec<u8>` instead of `Vec < u8 >`)
+fn type_string(field_type: &syn::Type) -> String {
+    quote!(#field_type)
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(":: ", "::")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace("& ", "&")
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .replace("[ ", "[")
+        .replace(" ]", "]")
+        .replace(" ;", ";")
+}
+
+/// Returns the string as JSON string literal
+fn json_string(value: &str) -> String {
+    let mut result = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// Generates `new_<variant_name>()` constructor for every struct and unit variant, that takes common fields and then other fields of the variant
+fn generate_constructors(
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    fields: &[(Ident, syn::Type)],
+    method_names: &mut Vec<String>,
+    vis: &syn::Visibility,
+) -> proc_macro2::TokenStream {
+    let constructors = variants
+        .iter()
+        .filter(|variant| variant.is_struct || variant.is_unit)
+        .map(|variant| {
+            let variant_name = &variant.name;
+            let name = format_ident!("new_{}", snake_case(&variant_name.to_string()));
+            method_names.push(name.to_string());
+            let variant_fields: Vec<_> = variant.fields.iter().zip(&variant.field_types).collect();
+            // Common fields come first, named as in annotations, then other fields of the variant in order of declaration
+            let common_fields: Vec<_> = fields
+                .iter()
+                .map(|(field_name, _)| field_name)
+                .filter(|field_name| {
+                    variant.field_override(field_name).is_none()
+                        && variant.fields.contains(variant.field_ident(field_name))
+                })
+                .collect();
+            let common_idents: Vec<_> = common_fields
+                .iter()
+                .map(|field_name| variant.field_ident(field_name))
+                .collect();
+            let parameters = common_fields
+                .iter()
+                .map(|field_name| {
+                    let (_, field_type) = variant_fields
+                        .iter()
+                        .find(|(ident, _)| *ident == variant.field_ident(field_name))
+                        .unwrap();
+                    quote!(#field_name: #field_type)
+                })
+                .chain(
+                    variant_fields
+                        .iter()
+                        .filter(|(ident, _)| !common_idents.contains(ident))
+                        .map(|(ident, field_type)| quote!(#ident: #field_type)),
+                );
+            let initializers = common_fields
+                .iter()
+                .zip(&common_idents)
+                .map(|(field_name, ident)| quote!(#ident: #field_name))
+                .chain(
+                    variant
+                        .fields
+                        .iter()
+                        .filter(|ident| !common_idents.contains(ident))
+                        .map(|ident| quote!(#ident)),
+                );
+            let construction = if variant.is_unit {
+                quote!(Self::#variant_name)
+            } else {
+                quote!(Self::#variant_name { #(#initializers),* })
+            };
+            let cfgs = &variant.cfgs;
+            let doc = format!(" Creates [`{enum_name}::{variant_name}`]");
+            quote! {
+                #(#cfgs)*
+                #[doc = #doc]
+                #vis fn #name(#(#parameters),*) -> Self {
+                    #construction
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    quote! {
+        impl #enum_name {
+            #(#constructors)*
+        }
+    }
+}
+
+/// Converts CamelCase variant name to snake_case, keeping acronyms together (`HTTPRequest` becomes `http_request`)
+fn snake_case(name: &str) -> String {
+    let characters: Vec<char> = name.chars().collect();
+    let mut result = String::new();
+    for (index, character) in characters.iter().enumerate() {
+        if character.is_uppercase() && index > 0 {
+            let previous = characters[index - 1];
+            let next_is_lowercase = characters
+                .get(index + 1)
+                .is_some_and(|next| next.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lowercase)
+            {
+                result.push('_');
+            }
+        }
+        result.extend(character.to_lowercase());
+    }
+    result
+}
+
+/// Returns names used in `{name}` and `{name:spec}` placeholders of a format template, without duplicates
+fn template_placeholders(template: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '{' {
+            continue;
+        }
+        if chars.peek() == Some(&'{') {
+            chars.next(); // Escaped brace
+            continue;
+        }
+        let name: String = chars
+            .by_ref()
+            .take_while(|c| *c != '}')
+            .collect::<String>()
+            .split(':')
+            .next()
+            .unwrap()
+            .trim()
+            .to_string();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Generates Display implementation, that formats the template with the variant name and common fields
+fn generate_display(
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    fields: &[(Ident, syn::Type)],
+    template: &syn::LitStr,
+) -> proc_macro2::TokenStream {
+    let placeholders = template_placeholders(&template.value());
+    // Unused named arguments are an error in format strings, so variant name is passed only if it's used
+    let variant_argument = placeholders.iter().any(|name| name == "variant");
+    let field_names: Vec<_> = placeholders
+        .into_iter()
+        .filter(|name| name != "variant")
+        .map(|name| {
+            fields
+                .iter()
+                .map(|(field_name, _)| field_name)
+                .find(|field_name| *field_name == &name)
+                .unwrap_or_else(|| {
+                    panic!("display template references {name}, which is not a common field")
+                })
+        })
+        .collect();
+    let match_branches = variant_branches(variants, |variant| {
+        let variant_name = variant.name.to_string();
+        let variant_name = variant_argument.then(|| quote!(variant = #variant_name,));
+        if field_names.is_empty() {
+            let pattern = variant.pattern();
+            return quote!(#pattern => ::core::write!(f, #template, #variant_name));
+        }
+        variant.assert_has_fields(&field_names);
+        let (pattern, fields) = variant.bind_fields(&field_names, &quote!(&));
+        quote!(#pattern => ::core::write!(f, #template, #variant_name #(#field_names = #fields),*))
+    });
+    let matched = match_variants(quote!(self), &match_branches);
+    quote! {
+        impl ::core::fmt::Display for #enum_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #matched
+            }
+        }
+    }
+}
+
+/// Generates Hash implementation, that hashes only the listed common fields, in the order they are listed
+fn generate_hash(
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    fields: &[(Ident, syn::Type)],
+    hashed_fields: &[Ident],
+) -> proc_macro2::TokenStream {
+    assert_common_fields("common_fields_hash", fields, hashed_fields);
+    let field_names: Vec<_> = hashed_fields.iter().collect();
+    let match_branches = variant_branches(variants, |variant| {
+        variant.assert_has_fields(&field_names);
+        let (pattern, fields) = variant.bind_fields(&field_names, &quote!(&));
+        quote!(#pattern => { #(::core::hash::Hash::hash(#fields, state);)* })
+    });
+    let matched = match_variants(quote!(self), &match_branches);
+    quote! {
+        impl ::core::hash::Hash for #enum_name {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                #matched
+            }
+        }
+    }
+}
+
+/// Generates PartialEq and Eq implementations, that compare only the listed common fields.
+/// If `variant` is listed too, values of different variants are never equal
+fn generate_eq(
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    fields: &[(Ident, syn::Type)],
+    compared_fields: &[Ident],
+) -> proc_macro2::TokenStream {
+    let compares_variant = compared_fields
+        .iter()
+        .any(|field_name| field_name == "variant");
+    let compared_fields: Vec<_> = compared_fields
+        .iter()
+        .filter(|field_name| *field_name != "variant")
+        .cloned()
+        .collect();
+    assert_common_fields("common_fields_eq", fields, &compared_fields);
+    let mut comparisons = Vec::new();
+    if compares_variant {
+        comparisons.push(quote!(
+            ::core::mem::discriminant(self) == ::core::mem::discriminant(other)
+        ));
+    }
+    if !compared_fields.is_empty() {
+        let ours = generate_fields_tuple(quote!(self), variants, fields, &compared_fields);
+        let theirs = generate_fields_tuple(quote!(other), variants, fields, &compared_fields);
+        comparisons.push(quote!((#ours == #theirs)));
+    }
+    // Enum without variants has no values, and comparing fields of both sides would make the second one unreachable code
+    let comparison = if variants.is_empty() {
+        match_variants(quote!(self), &[])
+    } else {
+        quote!(#(#comparisons)&&*)
+    };
+    quote! {
+        impl ::core::cmp::PartialEq for #enum_name {
+            fn eq(&self, other: &Self) -> bool {
+                #comparison
+            }
+        }
+        impl ::core::cmp::Eq for #enum_name {}
+    }
+}
+
+/// Generates PartialOrd and Ord implementations, that compare the listed common fields in the order they are listed
+fn generate_ord(
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    fields: &[(Ident, syn::Type)],
+    ordered_fields: &[Ident],
+) -> proc_macro2::TokenStream {
+    assert_common_fields("common_fields_ord", fields, ordered_fields);
+    let ours = generate_fields_tuple(quote!(self), variants, fields, ordered_fields);
+    let theirs = generate_fields_tuple(quote!(other), variants, fields, ordered_fields);
+    // Same as in PartialEq, enum without variants has nothing to compare
+    let comparison = if variants.is_empty() {
+        match_variants(quote!(self), &[])
+    } else {
+        quote!(::core::cmp::Ord::cmp(&#ours, &#theirs))
+    };
+    quote! {
+        impl ::core::cmp::PartialOrd for #enum_name {
+            fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::core::cmp::Ordering> {
+                ::core::option::Option::Some(::core::cmp::Ord::cmp(self, other))
+            }
+        }
+        impl ::core::cmp::Ord for #enum_name {
+            fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                #comparison
+            }
+        }
+    }
+}
+
+/// Generates expression, that evaluates to a tuple of references to the given common fields of `scrutinee`
+fn generate_fields_tuple(
+    scrutinee: proc_macro2::TokenStream,
+    variants: &[EnumVariantInfo],
+    fields: &[(Ident, syn::Type)],
+    field_names: &[Ident],
+) -> proc_macro2::TokenStream {
+    let field_types = field_names.iter().map(|field_name| {
+        let (_, field_type) = fields
+            .iter()
+            .find(|(common_field, _)| common_field == field_name)
+            .unwrap();
+        field_type
+    });
+    let field_names: Vec<_> = field_names.iter().collect();
+    let match_branches = variant_branches(variants, |variant| {
+        variant.assert_has_fields(&field_names);
+        let (pattern, fields) = variant.bind_fields(&field_names, &quote!(&));
+        quote!(#pattern => (#(#fields,)*))
+    });
+    let matched = match_variants(scrutinee, &match_branches);
+    // Type of the tuple is given explicitly, so fields are coerced to the annotated types, like in accessors
+    quote! {
+        {
+            let fields: (#(&#field_types,)*) = #matched;
+            fields
+        }
+    }
+}
+
+/// Panics if an enum-level attribute lists a field, that has no #[common_field] annotation
+fn assert_common_fields(attribute: &str, fields: &[(Ident, syn::Type)], listed_fields: &[Ident]) {
+    for field_name in listed_fields {
+        if !fields
+            .iter()
+            .any(|(common_field, _)| common_field == field_name)
+        {
+            panic!("#[{attribute}(...)] references {field_name}, which is not a common field")
+        }
+    }
+}
+
+/// Generates Debug implementation, that prints the variant name and common fields, hiding values of redacted ones
+fn generate_debug(
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    fields: &[(Ident, syn::Type)],
+    redacted_fields: &[Ident],
+) -> proc_macro2::TokenStream {
+    let match_branches = variant_branches(variants, |variant| {
+        if variant.delegated {
+            // Panics, since fields of the inner type are not accessible
+            variant.assert_has_fields(&[]);
+        }
+        let variant_name = variant.name.to_string();
+        // Variants that skip or don't have a field just don't print it
+        let (shown, redacted): (Vec<_>, Vec<_>) = fields
+            .iter()
+            .map(|(field_name, _)| field_name)
+            .filter(|field_name| variant.has_field(field_name))
+            .partition(|field_name| !redacted_fields.contains(field_name));
+        let shown_names = shown.iter().map(ToString::to_string);
+        let redacted_names = redacted.iter().map(ToString::to_string);
+        let (pattern, shown_fields) = if shown.is_empty() {
+            (variant.pattern(), Vec::new())
+        } else {
+            variant.bind_fields(&shown, &quote!(&))
+        };
+        // Other fields of the variant are not printed
+        let finish = if variant.is_unit {
+            quote!(finish)
+        } else {
+            quote!(finish_non_exhaustive)
+        };
+        quote! {
+            #pattern => f.debug_struct(#variant_name)
+                #(.field(#shown_names, #shown_fields))*
+                #(.field(#redacted_names, &::core::format_args!("<redacted>")))*
+                .#finish()
+        }
+    });
+    let matched = match_variants(quote!(self), &match_branches);
+    quote! {
+        impl ::core::fmt::Debug for #enum_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #matched
+            }
+        }
+    }
+}
+
+/// Kinds of structs with all common fields of an enum
+#[derive(Clone, Copy)]
+enum CommonStruct {
+    Ref,
+    Mut,
+    Owned,
+    Cloned,
+}
+
+/// Generates struct with all common fields, and the accessor returning it
+fn generate_common_struct(
+    enum_vis: &syn::Visibility,
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    fields: &[(Ident, syn::Type)],
+    config: &CommonFieldsConfig,
+    kind: CommonStruct,
+) -> proc_macro2::TokenStream {
+    let (common_name, signature, ref_token, field_ref, lifetime, derives, doc) = match kind {
+        CommonStruct::Ref => {
+            let common_name = format_ident!("{enum_name}CommonRef");
+            (
+                common_name.clone(),
+                quote!(fn common(&self) -> #common_name<'_>),
+                quote!(&),
+                quote!(&'a),
+                quote!(<'a>),
+                quote!(#[derive(Clone, Copy)]),
+                format!(" References to all common fields of a [`{enum_name}`]"),
+            )
+        }
+        CommonStruct::Mut => {
+            let common_name = format_ident!("{enum_name}CommonMut");
+            (
+                common_name.clone(),
+                quote!(fn common_mut(&mut self) -> #common_name<'_>),
+                quote!(&mut),
+                quote!(&'a mut),
+                quote!(<'a>),
+                quote!(),
+                format!(" Mutable references to all common fields of a [`{enum_name}`]"),
+            )
+        }
+        CommonStruct::Owned => {
+            let common_name = format_ident!("{enum_name}Common");
+            (
+                common_name.clone(),
+                quote!(fn into_common(self) -> #common_name),
+                quote!(),
+                quote!(),
+                quote!(),
+                quote!(),
+                format!(" All common fields of a [`{enum_name}`]"),
+            )
+        }
+        CommonStruct::Cloned => {
+            let common_name = format_ident!("{enum_name}Common");
+            (
+                common_name.clone(),
+                quote!(fn common_owned(&self) -> #common_name),
+                quote!(&),
+                quote!(),
+                quote!(),
+                quote!(),
+                format!(" All common fields of a [`{enum_name}`]"),
+            )
+        }
+    };
+    let field_names: Vec<_> = fields.iter().map(|(field_name, _)| field_name).collect();
+    let field_types = fields.iter().map(|(_, field_type)| field_type);
+    let base_names: Vec<_> = field_names
+        .iter()
+        .map(|field_name| config.base_name(field_name))
+        .collect();
+    let match_branches = variant_branches(variants, |variant| {
+        variant.assert_has_fields(&field_names);
+        let (pattern, mut fields) = variant.bind_fields(&field_names, &ref_token);
+        if let CommonStruct::Cloned = kind {
+            fields = fields
+                .into_iter()
+                .map(|field| quote!(::core::clone::Clone::clone(#field)))
+                .collect();
+        }
+        quote!(#pattern => #common_name { #(#base_names: #fields,)* })
+    });
+    let matched = match_variants(quote!(self), &match_branches);
+    // `into_common` and `common_owned` share the struct
+    let common_struct = if matches!(kind, CommonStruct::Cloned) && config.into_common {
+        quote!()
+    } else {
+        quote! {
+            #[doc = #doc]
+            #derives
+            #enum_vis struct #common_name #lifetime {
+                #(pub #base_names: #field_ref #field_types,)*
+            }
+        }
+    };
+    let vis = config.vis();
+    let method_doc = match kind {
+        CommonStruct::Cloned => " Returns clones of all common fields at once",
+        _ => " Returns all common fields at once",
+    };
+    quote! {
+        #common_struct
+
+        impl #enum_name {
+            #[doc = #method_doc]
+            #[must_use]
+            #vis #signature {
+                #matched
+            }
+        }
+    }
+}
+
+/// Generates `MyEnumRest` enum with fields that are not common, and `split()` method, that moves the enum into it and `MyEnumCommon`
+fn generate_split(
+    enum_vis: &syn::Visibility,
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    fields: &[(Ident, syn::Type)],
+    config: &CommonFieldsConfig,
+) -> proc_macro2::TokenStream {
+    let common_name = format_ident!("{enum_name}Common");
+    let rest_name = format_ident!("{enum_name}Rest");
+    let field_names: Vec<_> = fields.iter().map(|(field_name, _)| field_name).collect();
+    let base_names: Vec<_> = field_names
+        .iter()
+        .map(|field_name| config.base_name(field_name))
+        .collect();
+    let mut rest_variants = Vec::new();
+    let match_branches = variant_branches(variants, |variant| {
+        variant.assert_has_fields(&field_names);
+        if !variant.is_struct {
+            panic!(
+                "Variant {} is a tuple variant, which is not supported by split option",
+                variant.name
+            )
+        }
+        field_names
+            .iter()
+            .for_each(|field_name| variant.assert_not_overridden(field_name));
+        let variant_name = &variant.name;
+        let common_idents: Vec<_> = field_names
+            .iter()
+            .map(|field_name| variant.field_ident(field_name))
+            .collect();
+        let (rest_fields, rest_types): (Vec<_>, Vec<_>) = variant
+            .fields
+            .iter()
+            .zip(&variant.field_types)
+            .filter(|(ident, _)| !common_idents.contains(ident))
+            .unzip();
+        let bindings = field_names
+            .iter()
+            .map(|field_name| variant.field_binding(field_name));
+        let cfgs = &variant.cfgs;
+        let rest = if rest_fields.is_empty() {
+            rest_variants.push(quote!(#(#cfgs)* #variant_name));
+            quote!(#rest_name::#variant_name)
+        } else {
+            rest_variants.push(quote!(#(#cfgs)* #variant_name { #(#rest_fields: #rest_types),* }));
+            quote!(#rest_name::#variant_name { #(#rest_fields),* })
+        };
+        quote! {
+            Self::#variant_name { #(#bindings,)* #(#rest_fields),* } => (
+                #common_name { #(#base_names: #field_names,)* },
+                #rest,
+            )
+        }
+    });
+    let matched = match_variants(quote!(self), &match_branches);
+    // `into_common` and `common_owned` options generate the same struct
+    let common_struct = if config.into_common || config.common_owned {
+        quote!()
+    } else {
+        let field_types = fields.iter().map(|(_, field_type)| field_type);
+        let doc = format!(" All common fields of a [`{enum_name}`]");
+        quote! {
+            #[doc = #doc]
+            #enum_vis struct #common_name {
+                #(pub #base_names: #field_types,)*
+            }
+        }
+    };
+    let rest_doc = format!(" Fields of a [`{enum_name}`], that are not common");
+    let vis = config.vis();
+    quote! {
+        #common_struct
+
+        #[doc = #rest_doc]
+        #enum_vis enum #rest_name {
+            #(#rest_variants,)*
+        }
+
+        impl #enum_name {
+            /// Moves the enum into its common fields and the rest of the fields
+            #[must_use]
+            #vis fn split(self) -> (#common_name, #rest_name) {
+                #matched
+            }
+        }
+    }
+}
+
+fn generate_common_iter(
+    enum_name: &Ident,
+    fields: &[(Ident, syn::Type)],
+    config: &CommonFieldsConfig,
+) -> proc_macro2::TokenStream {
+    let common_name = format_ident!("{enum_name}CommonRef");
+    let field_count = fields.len();
+    let base_names: Vec<_> = fields
+        .iter()
+        .map(|(field_name, _)| config.base_name(field_name))
+        .collect();
+    let base_name_strings = base_names.iter().map(Ident::to_string);
+    quote! {
+        impl<'a> ::core::iter::IntoIterator for #common_name<'a> {
+            type Item = (&'static str, &'a dyn ::core::fmt::Display);
+            type IntoIter = ::core::array::IntoIter<Self::Item, #field_count>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                [#((#base_name_strings, self.#base_names as &'a dyn ::core::fmt::Display),)*].into_iter()
+            }
+        }
+    }
+}
+
+/// Generates Serialize implementation for the CommonRef struct, that serializes it as a struct with the name of the enum
+fn generate_common_serialize(
+    enum_name: &Ident,
+    fields: &[(Ident, syn::Type)],
+    config: &CommonFieldsConfig,
+) -> proc_macro2::TokenStream {
+    let common_name = format_ident!("{enum_name}CommonRef");
+    let enum_name_string = enum_name.to_string();
+    let field_count = fields.len();
+    let base_names: Vec<_> = fields
+        .iter()
+        .map(|(field_name, _)| config.base_name(field_name))
+        .collect();
+    let base_name_strings = base_names.iter().map(Ident::to_string);
+    quote! {
+        impl<'a> ::serde::Serialize for #common_name<'a> {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error> {
+                let mut state = ::serde::Serializer::serialize_struct(serializer, #enum_name_string, #field_count)?;
+                #(::serde::ser::SerializeStruct::serialize_field(&mut state, #base_name_strings, self.#base_names)?;)*
+                ::serde::ser::SerializeStruct::end(state)
+            }
+        }
+    }
+}
+
+fn generate_columns(
+    enum_vis: &syn::Visibility,
+    enum_name: &Ident,
+    variants: &[EnumVariantInfo],
+    fields: &[(Ident, syn::Type)],
+    config: &CommonFieldsConfig,
+) -> proc_macro2::TokenStream {
+    let columns_name = format_ident!("{enum_name}CommonColumns");
+    let vis = config.vis();
+    let doc = format!(" Values of common fields of [`{enum_name}`] items, one vector per field");
+    let field_names: Vec<_> = fields.iter().map(|(field_name, _)| field_name).collect();
+    let field_types = fields.iter().map(|(_, field_type)| field_type);
+    let column_names: Vec<_> = field_names
+        .iter()
+        .map(|field_name| format_ident!("{}s", config.base_name(field_name)))
+        .collect();
+    // Fields are pushed in the branches, so nothing follows the match of enums without variants
+    let match_branches = variant_branches(variants, |variant| {
+        variant.assert_has_fields(&field_names);
+        let (pattern, fields) = variant.bind_fields(&field_names, &quote!());
+        quote!(#pattern => {
+            #(columns.#column_names.push(#fields);)*
+        })
+    });
+    let matched = match_variants(quote!(item), &match_branches);
+    let vec = config.alloc_path(quote!(vec::Vec));
+    quote! {
+        #[doc = #doc]
+        #[derive(Default)]
+        #enum_vis struct #columns_name {
+            #(pub #column_names: #vec<#field_types>,)*
+        }
+
+        impl #enum_name {
+            /// Moves common fields of all items into one vector per field
+            #[must_use]
+            #vis fn columns(items: impl ::core::iter::IntoIterator<Item = Self>) -> #columns_name {
+                let mut columns = #columns_name::default();
+                for item in items {
+                    #matched
+                }
+                columns
+            }
+        }
+    }
+}
+
+/// Returns every common field once, with the type from the first annotation that moves the field
+/// (so its type has to be the actual type of the field), or just from the first annotation of the field.
+/// Annotations with cfg(...) prefix are left out, since the field may not be common when the cfg is off
+fn distinct_common_fields(common_fields: &[CommonField]) -> Vec<(Ident, syn::Type)> {
+    let common_fields: Vec<_> = common_fields
+        .iter()
+        .filter(|common_field| common_field.cfg.is_none())
+        .collect();
+    let mut fields: Vec<(Ident, syn::Type)> = Vec::new();
+    for common_field in &common_fields {
+        if fields
+            .iter()
+            .any(|(field_name, _)| *field_name == common_field.field_name)
+        {
+            continue;
+        }
+        let field_type = &common_fields
+            .iter()
+            .find(|other| {
+                other.field_name == common_field.field_name
+                    && other.kinds.iter().any(GetterKind::moves_field)
+            })
+            .unwrap_or(common_field)
+            .field_type;
+        fields.push((common_field.field_name.clone(), field_type.clone()));
+    }
+    fields
+}
+
+fn parse_config_attributes(ast: &DeriveInput) -> CommonFieldsConfig {
+    let mut config = CommonFieldsConfig::default();
+    for attr in &ast.attrs {
+        // #[common_fields(...)] is an alias of #[common_fields_config(...)]
+        if attr.path().is_ident("common_fields_config") || attr.path().is_ident("common_fields") {
+            attr.parse_args_with(|input: ParseStream| config.parse_options(input))
+                .unwrap();
+        }
+    }
+    config
+}
+
+fn parse_group_attributes(ast: &DeriveInput) -> Vec<CommonFieldsGroup> {
+    let mut groups = Vec::new();
+    for attr in &ast.attrs {
+        if attr.path().is_ident("common_fields_group") {
+            groups.extend(
+                attr.parse_args_with(
+                    syn::punctuated::Punctuated::<CommonFieldsGroup, Token![,]>::parse_terminated,
+                )
+                .unwrap(),
+            );
+        }
+    }
+    groups
+}
+
+/// Returns names of traits from #[derive(...)] attributes of the enum, that follow the one with EnumCommonFields
+fn parse_derived_traits(ast: &DeriveInput) -> Vec<Ident> {
+    let mut derived = Vec::new();
+    for attr in &ast.attrs {
+        if attr.path().is_ident("derive") {
+            let paths = attr
+                .parse_args_with(
+                    syn::punctuated::Punctuated::<syn::Path, Token![,]>::parse_terminated,
+                )
+                .unwrap();
+            derived.extend(
+                paths
+                    .into_iter()
+                    .map(|path| path.segments.last().unwrap().ident.clone()),
+            );
+        }
+    }
+    derived
+}
+
+fn parse_trait_attribute(ast: &DeriveInput) -> Option<CommonFieldsTrait> {
+    let mut trait_names = ast
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("common_fields_trait"))
+        .map(|attr| attr.parse_args::<CommonFieldsTrait>().unwrap());
+    let trait_name = trait_names.next();
+    if trait_names.next().is_some() {
+        panic!("Only one #[common_fields_trait(...)] annotation is allowed")
+    }
+    trait_name
+}
+
+/// Parses the only enum-level attribute with given name, that lists common fields, like #[common_fields_hash(...)]
+fn parse_field_list_attribute(ast: &DeriveInput, attribute: &str) -> Option<Vec<Ident>> {
+    let mut lists = ast
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident(attribute))
+        .map(|attr| {
+            attr.parse_args_with(syn::punctuated::Punctuated::<Ident, Token![,]>::parse_terminated)
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>()
+        });
+    let listed_fields = lists.next();
+    if lists.next().is_some() {
+        panic!("Only one #[{attribute}(...)] annotation is allowed")
+    }
+    if listed_fields.as_ref().is_some_and(Vec::is_empty) {
+        panic!("#[{attribute}(...)] requires at least one field")
+    }
+    listed_fields
+}
+
+fn parse_tuple_attributes(ast: &DeriveInput) -> Vec<CommonTuple> {
+    let mut tuples = Vec::new();
+    for attr in &ast.attrs {
+        if attr.path().is_ident("common_tuple") {
+            tuples.extend(
+                attr.parse_args_with(
+                    syn::punctuated::Punctuated::<CommonTuple, Token![,]>::parse_terminated,
+                )
+                .unwrap(),
+            );
+        }
+    }
+    tuples
+}
+
+fn parse_delegate_attributes(ast: &DeriveInput) -> Vec<CommonDelegate> {
+    let mut delegates = Vec::new();
+    for attr in &ast.attrs {
+        if attr.path().is_ident("common_delegate") {
+            delegates.extend(
+                attr.parse_args_with(
+                    syn::punctuated::Punctuated::<CommonDelegate, Token![,]>::parse_terminated,
+                )
+                .unwrap(),
+            );
+        }
+    }
+    delegates
+}
+
+fn parse_common_fields_attributes(ast: &DeriveInput) -> Vec<CommonField> {
+    ast
+        .attrs
+        .iter()
+        .filter_map(|attr| {
+            // Checking that we have only #[common_field ...] attributes
+            if attr.path().is_ident("common_field") {
+                // Checking that the attribute has parenthesis like this #[common_field(...)]
+                if let Meta::List(list) = &attr.meta {
+                    // Parsing data of the attribute
+                    Some(syn::parse2::<CommonField>(list.tokens.clone()).unwrap().flatten())
+                } else {
+                    panic!("Expected format: #[common_field([cfg(...) ][flatten ][ref|all|own|own_only|mut|mut_only|replace|take_into|map|update|clone|get_or_clone|copy|cow|arc|pin|as_deref|as_ref|deref|expect(\"message\")|cell|atomic|try_own|result|indexed|try_into(FieldType)|bytes|own_str][ + modifier...] field_name[, field_name...] [as getter_name|as (getter_name, ...)]: Type[ {{ field_name: Type, ... }}][, options...])]")
+                }
+            } else {
+                None
+            }
+        })
+        .flatten()
+        .collect()
+}
+
+/// Parses #[common] and #[common(...)] markers of variant fields into annotations, that use name and type of the field.
+/// Only the first marker of a field declares it, other markers must be the same or bare #[common]
+fn parse_common_markers(ast: &DeriveInput, declared: &[CommonField]) -> Vec<CommonField> {
+    let syn::Data::Enum(data) = &ast.data else {
+        return Vec::new();
+    };
+    let mut markers: Vec<(Ident, String)> = Vec::new(); // Names of marked fields and arguments of their first markers
+    let mut common_fields = Vec::new();
+    for variant in &data.variants {
+        for field in &variant.fields {
+            for attr in field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("common"))
+            {
+                let Some(field_name) = &field.ident else {
+                    panic!(
+                        "#[common] marker of variant {} needs a named field, since accessors are named after it",
+                        variant.ident
+                    )
+                };
+                let arguments = match &attr.meta {
+                    Meta::Path(_) => proc_macro2::TokenStream::new(),
+                    Meta::List(list) => list.tokens.clone(),
+                    Meta::NameValue(_) => {
+                        panic!("Expected format: #[common] or #[common([modifiers...] [as getter_name][, options...])]")
+                    }
+                };
+                if let Some((_, first)) = markers.iter().find(|(marked, _)| marked == field_name) {
+                    if !arguments.is_empty() && arguments.to_string() != *first {
+                        panic!(
+                            "Field {field_name} of variant {} has #[common(...)] marker different from the first one. Use bare #[common] or the same marker",
+                            variant.ident
+                        )
+                    }
+                    continue;
+                }
+                markers.push((field_name.clone(), arguments.to_string()));
+                if declared
+                    .iter()
+                    .any(|common_field| &common_field.field_name == field_name)
+                {
+                    panic!("Field {field_name} is declared with both #[common_field] annotation and #[common] marker")
+                }
+                // Modifiers go before the field name, and the rest of the marker after it
+                let mut tokens = arguments.into_iter().peekable();
+                let mut modifiers = proc_macro2::TokenStream::new();
+                while let Some(token) = tokens.next_if(|token| {
+                    !matches!(token, proc_macro2::TokenTree::Punct(punct) if punct.as_char() == ',')
+                        && !matches!(token, proc_macro2::TokenTree::Ident(ident) if ident == "as")
+                }) {
+                    modifiers.extend([token]);
+                }
+                let mut names = proc_macro2::TokenStream::new();
+                while let Some(token) = tokens.next_if(|token| {
+                    !matches!(token, proc_macro2::TokenTree::Punct(punct) if punct.as_char() == ',')
+                }) {
+                    names.extend([token]);
+                }
+                let options: proc_macro2::TokenStream = tokens.collect();
+                let field_type = &field.ty;
+                common_fields.extend(
+                    syn::parse2::<CommonField>(
+                        quote!(#modifiers #field_name #names: #field_type #options),
+                    )
+                    .unwrap()
+                    .flatten(),
+                );
+            }
+        }
+    }
+    common_fields
+}
+
+/// Returns annotations for fields with the same name and type in every variant, except the excluded
+/// and already declared ones. Variants have to be struct variants, since fields of tuple variant payloads are not visible
+fn detect_common_fields(
+    ast: &DeriveInput,
+    exclude: &[Ident],
+    declared: &[CommonField],
+) -> Vec<CommonField> {
+    let syn::Data::Enum(data) = &ast.data else {
+        return Vec::new();
+    };
+    if let Some(variant) = data
+        .variants
+        .iter()
+        .find(|variant| !matches!(variant.fields, Fields::Named(_)))
+    {
+        panic!(
+            "Variant {} is not a struct variant, which is not supported by auto option",
+            variant.ident
+        )
+    }
+    let Some(first) = data.variants.first() else {
+        return Vec::new();
+    };
+    first
+        .fields
+        .iter()
+        .filter_map(|field| {
+            let field_name = field.ident.as_ref().unwrap();
+            let field_type = &field.ty;
+            let type_string = quote!(#field_type).to_string();
+            let is_common = data.variants.iter().all(|variant| {
+                variant.fields.iter().any(|other| {
+                    let other_type = &other.ty;
+                    other.ident.as_ref() == Some(field_name)
+                        && quote!(#other_type).to_string() == type_string
+                })
+            });
+            let skipped = exclude.contains(field_name)
+                || declared
+                    .iter()
+                    .any(|common_field| &common_field.field_name == field_name);
+            (is_common && !skipped)
+                .then(|| syn::parse2::<CommonField>(quote!(#field_name: #field_type)).unwrap())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod common_field_parsing_tests {
+    use super::*;
+    use syn::parse_quote;
+    #[test]
+    fn test_basic_field() {
+        let tokens = parse_quote! { field1: i32 };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(i32));
+        assert_eq!(parsed.kinds, vec![GetterKind::ReadOnly]);
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_field_with_custom_name() {
+        let tokens = parse_quote! { field1 as custom_name: i32 };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.resulting_name.unwrap(), "custom_name");
+        assert_eq!(parsed.field_type, parse_quote!(i32));
+        assert_eq!(parsed.kinds, vec![GetterKind::ReadOnly]);
+    }
+
+    #[test]
+    fn test_mutable_field() {
+        let tokens = parse_quote! { mut field1: i32 };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(i32));
+        assert_eq!(
+            parsed.kinds,
+            vec![GetterKind::ReadOnly, GetterKind::Mutable]
+        );
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_owning_field() {
+        let tokens = parse_quote! { own_only field1: i32 };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(i32));
+        assert_eq!(parsed.kinds, vec![GetterKind::Owning]);
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_replace_field() {
+        let tokens = parse_quote! { replace field1: i32 };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(i32));
+        assert_eq!(parsed.kinds, vec![GetterKind::Replacing]);
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_take_into_field() {
+        let tokens = parse_quote! { take_into field1: i32 };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(i32));
+        assert_eq!(parsed.kinds, vec![GetterKind::Swapping]);
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_map_field() {
+        let tokens = parse_quote! { map field1: i32 };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(i32));
+        assert_eq!(parsed.kinds, vec![GetterKind::Mapping]);
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_update_field() {
+        let tokens = parse_quote! { update field1: i32 };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(i32));
+        assert_eq!(parsed.kinds, vec![GetterKind::Updating]);
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_clone_field() {
+        let tokens = parse_quote! { clone field1: i32 };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(i32));
+        assert_eq!(parsed.kinds, vec![GetterKind::Cloning]);
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_copy_field() {
+        let tokens = parse_quote! { copy field1: i32 };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(i32));
+        assert_eq!(parsed.kinds, vec![GetterKind::Copying]);
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_cow_field() {
+        let tokens = parse_quote! { cow field1: str };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(str));
+        assert_eq!(parsed.kinds, vec![GetterKind::Cow]);
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_arc_field() {
+        let tokens = parse_quote! { arc field1: dyn Send };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(dyn Send));
+        assert_eq!(parsed.kinds, vec![GetterKind::Arc]);
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_generic_field_type() {
+        let tokens = parse_quote! { mut field1: Vec<Option<i32>>, since = "1.0" };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(Vec<Option<i32>>));
+        assert_eq!(parsed.since.as_deref(), Some("1.0"));
+    }
+
+    #[test]
+    fn test_as_deref_field() {
+        let tokens = parse_quote! { as_deref field1: str };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(str));
+        assert_eq!(parsed.kinds, vec![GetterKind::OptionDeref]);
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_expect_field() {
+        let tokens = parse_quote! { expect("must be set") field1: i32 };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(i32));
+        assert_eq!(
+            parsed.kinds,
+            vec![GetterKind::Expecting("must be set".to_string())]
+        );
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_field_named_like_modifier() {
+        let tokens = parse_quote! { expect as get_expect: i32 };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "expect");
+        assert_eq!(parsed.resulting_name.unwrap(), "get_expect");
+        assert_eq!(parsed.kinds, vec![GetterKind::ReadOnly]);
+    }
+
+    #[test]
+    fn test_cell_field() {
+        let tokens = parse_quote! { cell field1: i32 };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(i32));
+        assert_eq!(parsed.kinds, vec![GetterKind::CellGetting]);
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_atomic_field() {
+        let tokens = parse_quote! { atomic field1: u64 };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(u64));
+        assert_eq!(
+            parsed.kinds,
+            vec![GetterKind::AtomicLoading, GetterKind::AtomicStoring]
+        );
+    }
+
+    #[test]
+    fn test_result_field() {
+        let tokens = parse_quote! { result field1 as get_field1: String };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.resulting_name.unwrap(), "get_field1");
+        assert_eq!(parsed.kinds, vec![GetterKind::Resulting]);
+    }
+
+    #[test]
+    fn test_joined_modifiers() {
+        let tokens = parse_quote! { ref + own_only + mut field1: String };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(
+            parsed.kinds,
+            vec![
+                GetterKind::ReadOnly,
+                GetterKind::Owning,
+                GetterKind::Mutable
+            ]
+        );
+    }
+
+    #[test]
+    fn test_joined_modifier_missing() {
+        let tokens = parse_quote! { ref + field1: String };
+        let result: Result<CommonField, _> = syn::parse2(tokens);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_indexed_field() {
+        let tokens = parse_quote! { indexed field1: String };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.kinds, vec![GetterKind::Indexed]);
+    }
+
+    #[test]
+    fn test_names_for_every_kind() {
+        let tokens =
+            parse_quote! { own_only + replace + ref field1 as (f, into_f, replace_f): String };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(
+            parsed.kinds,
+            vec![
+                GetterKind::Owning,
+                GetterKind::Replacing,
+                GetterKind::ReadOnly
+            ]
+        );
+        assert_eq!(parsed.kind_names, ["into_f", "replace_f", "f"]);
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_wrong_number_of_names() {
+        let tokens = parse_quote! { mut field1 as (f): String };
+        let result: Result<CommonField, _> = syn::parse2(tokens);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_multiple_kinds_with_name() {
+        let tokens = parse_quote! { own field1 as f: String };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.kinds.len(), 3);
+        assert_eq!(parsed.resulting_name.unwrap(), "f");
+    }
+
+    #[test]
+    fn test_try_into_field() {
+        let tokens = parse_quote! { try_into(Vec<u64>) field1: Vec<u32> };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(Vec<u32>));
+        assert_eq!(
+            parsed.kinds,
+            vec![GetterKind::TryConverting(quote!(Vec<u64>).to_string())]
+        );
+    }
+
+    #[test]
+    fn test_get_or_clone_field() {
+        let tokens = parse_quote! { get_or_clone field1: String };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_type, parse_quote!(String));
+        assert_eq!(
+            parsed.kinds,
+            vec![GetterKind::ReadOnly, GetterKind::OwnedCloning]
+        );
+    }
+
+    #[test]
+    fn test_flattened_field() {
+        let tokens = parse_quote! { flatten mut common: CommonData { id: Uuid, created_at: Instant }, since = "1.0" };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(
+            parsed.kinds,
+            vec![GetterKind::ReadOnly, GetterKind::Mutable]
+        );
+        assert_eq!(parsed.flattened.len(), 2);
+        let flattened = parsed.flatten();
+        assert_eq!(flattened.len(), 3);
+        assert_eq!(flattened[2].field_name, "created_at");
+        assert_eq!(flattened[2].through.as_ref().unwrap(), "common");
+        assert_eq!(flattened[2].since.as_ref().unwrap(), "1.0");
+
+        let tokens = parse_quote! { flatten: bool };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+        assert_eq!(parsed.field_name, "flatten");
+        assert!(parsed.flattened.is_empty());
+    }
+
+    #[test]
+    fn test_field_with_from() {
+        let tokens = parse_quote! { own field1: String, from };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert!(parsed.from);
+        assert!(!parsed.as_mut);
+    }
+
+    #[test]
+    fn test_field_with_as_mut() {
+        let tokens = parse_quote! { mut field1: String, as_mut };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert!(parsed.as_mut);
+        assert_eq!(parsed.options[0], "as_mut");
+    }
+
+    #[test]
+    fn test_field_with_impl() {
+        let tokens =
+            parse_quote! { copy field1: u64, impl = crate::Identifiable::id, since = "1.0" };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        let path = parsed.implemented.unwrap();
+        assert_eq!(path.segments.len(), 3);
+        assert_eq!(path.segments.last().unwrap().ident, "id");
+        assert_eq!(parsed.since.unwrap(), "1.0");
+    }
+
+    #[test]
+    fn test_field_with_impl_without_trait() {
+        let tokens = parse_quote! { field1: u64, impl = id };
+        assert!(syn::parse2::<CommonField>(tokens).is_err());
+    }
+
+    #[test]
+    fn test_field_via_method() {
+        let tokens = parse_quote! { mut field1 via get(0) as f: String };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.resulting_name.unwrap(), "f");
+        assert_eq!(parsed.via.unwrap().to_string(), quote!(get(0)).to_string());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_own_str_field() {
+        let tokens = parse_quote! { own_str field1: str };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_type, parse_quote!(str));
+        assert_eq!(parsed.kinds, vec![GetterKind::StrOwning]);
+    }
+
+    #[test]
+    #[cfg(not(feature = "bytemuck"))]
+    fn test_bytes_field_without_feature() {
+        let error = syn::parse2::<CommonField>(quote!(bytes header: Header))
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "\"bytes\" modifier requires \"bytemuck\" feature of enum_common_fields"
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "alloc"))]
+    fn test_own_str_field_without_feature() {
+        let error = syn::parse2::<CommonField>(quote!(own_str field1: str))
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "\"own_str\" modifier requires \"alloc\" feature of enum_common_fields"
+        );
+    }
+
+    #[test]
+    fn test_deref_field() {
+        let tokens = parse_quote! { deref + ref field1: String };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(
+            parsed.kinds,
+            vec![GetterKind::Dereferencing, GetterKind::ReadOnly]
+        );
+    }
+
+    #[test]
+    fn test_as_ref_field() {
+        let tokens = parse_quote! { as_ref field1: std::path::Path };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_type, parse_quote!(std::path::Path));
+        assert_eq!(parsed.kinds, vec![GetterKind::AsRef]);
+    }
+
+    #[test]
+    fn test_try_own_field() {
+        let tokens = parse_quote! { try_own field1: String };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.kinds, vec![GetterKind::TryOwning]);
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_all_field() {
+        let tokens = parse_quote! { all field1: i32 };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(i32));
+        assert_eq!(
+            parsed.kinds,
+            vec![
+                GetterKind::Owning,
+                GetterKind::Mutable,
+                GetterKind::ReadOnly
+            ]
+        );
+        assert!(parsed.resulting_name.is_none());
+    }
+
+    #[test]
+    fn test_field_with_since() {
+        let tokens = parse_quote! { mut field1: i32, since = "1.2" };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.field_name, "field1");
+        assert_eq!(parsed.field_type, parse_quote!(i32));
+        assert_eq!(
+            parsed.kinds,
+            vec![GetterKind::ReadOnly, GetterKind::Mutable]
+        );
+        assert_eq!(parsed.since.as_deref(), Some("1.2"));
+    }
+
+    #[test]
+    fn test_field_with_multiple_options() {
+        let tokens = parse_quote! { field1: i32, no_coverage, since = "1.2", };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.since.as_deref(), Some("1.2"));
+        assert!(parsed.no_coverage);
+    }
+
+    #[test]
+    fn test_field_with_default() {
+        let tokens = parse_quote! { copy field1: i32, default = 1 + 2, since = "1.2" };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.default, Some(parse_quote!(1 + 2)));
+        assert_eq!(parsed.since.as_deref(), Some("1.2"));
+    }
+
+    #[test]
+    fn test_field_with_get() {
+        let tokens =
+            parse_quote! { key as key_len: usize, get = |key: &String| key.len(), no_coverage };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.get, Some(parse_quote!(|key: &String| key.len())));
+        assert!(parsed.no_coverage);
+    }
+
+    #[test]
+    fn test_field_with_collect() {
+        let tokens = parse_quote! { field1: i32, collect };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+        assert_eq!(parsed.collect, Some(None));
+
+        let tokens = parse_quote! { field1: i32, collect = all_fields, no_coverage };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+        assert_eq!(parsed.collect.unwrap().unwrap(), "all_fields");
+        assert!(parsed.no_coverage);
+    }
+
+    #[test]
+    fn test_field_with_redact() {
+        let tokens = parse_quote! { token: String, redact };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+        assert!(parsed.redact);
+
+        let tokens = parse_quote! { token: String };
+        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+        assert!(!parsed.redact);
+    }
+
+    #[test]
+    fn test_unknown_option() {
+        let tokens = parse_quote! { field1: i32, unknown = "value" };
+        let result: Result<CommonField, _> = syn::parse2(tokens);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_invalid_format() {
+        let tokens = parse_quote! { field1 i32 };
+        let result: Result<CommonField, _> = syn::parse2(tokens);
+
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(test)]
+mod attributes_parse_tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_no_common_field() {
+        let input: DeriveInput = parse_quote! {
+            #[derive(Debug)]
+            enum TestEnum {
+                Variant1 { field1: i32 },
+                Variant2 { field1: i32 },
+            }
+        };
+
+        let result = parse_common_fields_attributes(&input);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_single_common_field() {
+        let input: DeriveInput = parse_quote! {
+            #[derive(Debug)]
+            #[common_field(field1: i32)]
+            enum TestEnum {
+                Variant1 { field1: i32 },
+                Variant2 { field1: i32 },
+            }
+        };
+
+        let result = parse_common_fields_attributes(&input);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].field_name, "field1");
+        assert_eq!(result[0].field_type, parse_quote!(i32));
+        assert_eq!(result[0].kinds, vec![GetterKind::ReadOnly]);
+    }
+
+    #[test]
+    fn test_multiple_common_fields() {
+        let input: DeriveInput = parse_quote! {
+            #[derive(Debug)]
+            #[common_field(field1: i32)]
+            #[common_field(mut field2: String)]
+            enum TestEnum {
+                Variant1 { field1: i32, field2: String },
+                Variant2 { field1: i32, field2: String },
+            }
+        };
+
+        let result = parse_common_fields_attributes(&input);
+        assert_eq!(result.len(), 2);
+
+        assert_eq!(result[0].field_name, "field1");
+        assert_eq!(result[0].field_type, parse_quote!(i32));
+        assert_eq!(result[0].kinds, vec![GetterKind::ReadOnly]);
+
+        assert_eq!(result[1].field_name, "field2");
+        assert_eq!(result[1].field_type, parse_quote!(String));
+        assert_eq!(
+            result[1].kinds,
+            vec![GetterKind::ReadOnly, GetterKind::Mutable]
+        );
+    }
+
+    #[test]
+    fn test_common_field_with_custom_name() {
+        let input: DeriveInput = parse_quote! {
+            #[derive(Debug)]
+            #[common_field(field1 as custom_name: i32)]
+            enum TestEnum {
+                Variant1 { field1: i32 },
+                Variant2 { field1: i32 },
+            }
+        };
+
+        let result = parse_common_fields_attributes(&input);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].field_name, "field1");
+        assert_eq!(result[0].clone().resulting_name.unwrap(), "custom_name");
+        assert_eq!(result[0].field_type, parse_quote!(i32));
+        assert_eq!(result[0].kinds, vec![GetterKind::ReadOnly]);
+    }
+
+    #[test]
+    fn test_distinct_common_fields() {
+        let input: DeriveInput = parse_quote! {
+            #[common_field(mut field1: str)]
+            #[common_field(field2: i32)]
+            #[common_field(own_only field1: String)]
+            #[common_field(field2 as other_name: i32)]
+            enum TestEnum {
+                Variant1 { field1: String, field2: i32 },
+            }
+        };
+
+        let fields = distinct_common_fields(&parse_common_fields_attributes(&input));
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].0, "field1");
+        assert_eq!(fields[0].1, parse_quote!(String));
+        assert_eq!(fields[1].0, "field2");
+        assert_eq!(fields[1].1, parse_quote!(i32));
+    }
+
+    #[test]
+    fn test_group_attributes() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields_group(coords(x: f32, y: f32), name(name: str))]
+            #[common_fields_group(mut empty())]
+            enum TestEnum {
+                Variant1 { x: f32, y: f32, name: String },
+            }
+        };
+
+        let groups = parse_group_attributes(&input);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].name, "coords");
+        assert_eq!(
+            groups[0].fields,
+            vec![
+                (format_ident!("x"), parse_quote!(f32)),
+                (format_ident!("y"), parse_quote!(f32)),
+            ]
+        );
+        assert_eq!(
+            groups[1].fields,
+            vec![(format_ident!("name"), parse_quote!(str))]
+        );
+        assert!(groups[2].fields.is_empty());
+        assert!(groups[2].mutable && !groups[0].mutable);
+    }
+
+    #[test]
+    fn test_skipped_fields() {
+        let input: DeriveInput = parse_quote! {
+            enum TestEnum {
+                Variant1 { key: i32, seq: u64 },
+                #[common_field_skip(key, seq)]
+                Variant2,
+                #[common_field_skip(seq)]
+                Variant3(Payload),
+            }
+        };
+        let syn::Data::Enum(data) = input.data else {
+            unreachable!()
+        };
+
+        let variants = parse_enum_variants(data).unwrap();
+        let key = format_ident!("key");
+        let seq = format_ident!("seq");
+        assert!(variants[0].skipped_fields.is_empty());
+        assert_eq!(variants[1].skipped_fields, vec![key.clone(), seq.clone()]);
+        assert!(variants[2].has_field(&key));
+        assert!(!variants[2].has_field(&seq));
+    }
+
+    #[test]
+    fn test_renamed_fields() {
+        let input: DeriveInput = parse_quote! {
+            enum TestEnum {
+                #[common_field_rename(id = user_id, name = user_name)]
+                Variant1 { user_id: i32, user_name: String },
+                Variant2 { id: i32, name: String },
+            }
+        };
+        let syn::Data::Enum(data) = input.data else {
+            unreachable!()
+        };
+
+        let variants = parse_enum_variants(data).unwrap();
+        let id = format_ident!("id");
+        assert_eq!(variants[0].field_ident(&id), "user_id");
+        assert!(variants[0].has_field(&id));
+        assert_eq!(variants[1].field_ident(&id), "id");
+        assert!(variants[1].renamed_fields.is_empty());
+    }
+
+    #[test]
+    fn test_tuple_index() {
+        let input: DeriveInput = parse_quote! {
+            enum TestEnum {
+                Variant1(Payload),
+                Variant2(Payload, u32),
+                #[common_field_at(2)]
+                Variant3(u32, u64, Payload),
+            }
+        };
+        let syn::Data::Enum(data) = input.data else {
+            unreachable!()
+        };
+
+        let variants = parse_enum_variants(data).unwrap();
+        assert_eq!((variants[0].tuple_len, variants[0].tuple_index), (1, 0));
+        assert_eq!((variants[1].tuple_len, variants[1].tuple_index), (2, 0));
+        assert_eq!((variants[2].tuple_len, variants[2].tuple_index), (3, 2));
+        assert_eq!(
+            variants[2].tuple_pattern(quote!(v)).to_string(),
+            quote!(Self::Variant3(_, _, v, ..)).to_string()
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "#[common_field_at(1)] of variant Variant1 doesn't point to an element of the tuple variant"
+    )]
+    fn test_tuple_index_out_of_bounds() {
+        let input: DeriveInput = parse_quote! {
+            enum TestEnum {
+                #[common_field_at(1)]
+                Variant1(Payload),
+            }
+        };
+        let syn::Data::Enum(data) = input.data else {
+            unreachable!()
+        };
+
+        parse_enum_variants(data).unwrap();
+    }
+
+    #[test]
+    fn test_overridden_fields() {
+        let input: DeriveInput = parse_quote! {
+            enum TestEnum {
+                #[common_field_via(key = v.meta.key, seq = v.seq())]
+                Variant1(Payload),
+                Variant2 { key: String },
+            }
+        };
+        let syn::Data::Enum(data) = input.data else {
+            unreachable!()
+        };
+
+        let variants = parse_enum_variants(data).unwrap();
+        let key = format_ident!("key");
+        assert_eq!(
+            variants[0].field_override(&key),
+            Some(&parse_quote!(v.meta.key))
+        );
+        assert_eq!(variants[0].overridden_fields.len(), 2);
+        assert!(variants[1].field_override(&key).is_none());
+    }
+
+    #[test]
+    fn test_variant_defaults() {
+        let input: DeriveInput = parse_quote! {
+            enum TestEnum {
+                Variant1 { priority: u8 },
+                #[common_field_default(priority = 255, name = String::new())]
+                Variant2,
+            }
+        };
+        let syn::Data::Enum(data) = input.data else {
+            unreachable!()
+        };
+
+        let variants = parse_enum_variants(data).unwrap();
+        let priority = format_ident!("priority");
+        assert!(variants[0].field_default(&priority).is_none());
+        assert_eq!(
+            variants[1].field_default(&priority),
+            Some(&parse_quote!(255))
+        );
+        assert_eq!(variants[1].default_fields.len(), 2);
+    }
+
+    #[test]
+    fn test_derived_traits() {
+        let input: DeriveInput = parse_quote! {
+            #[derive(Clone, std::hash::Hash)]
+            #[common_field(key: String)]
+            #[derive(PartialEq)]
+            enum TestEnum {
+                Variant1 { key: String },
+            }
+        };
+
+        let derived = parse_derived_traits(&input);
+        assert_eq!(derived, ["Clone", "Hash", "PartialEq"]);
+    }
+
+    #[test]
+    fn test_trait_attribute() {
+        let input: DeriveInput = parse_quote! {
+            #[common_field(key: String)]
+            #[common_fields_trait(HasKey)]
+            enum TestEnum {
+                Variant1 { key: String },
+            }
+        };
+
+        let common_trait = parse_trait_attribute(&input).unwrap();
+        assert_eq!(common_trait.name, "HasKey");
+        assert!(!common_trait.payloads);
+        assert!(!common_trait.object_safe);
+
+        let common_trait: CommonFieldsTrait = parse_quote!(HasKey, object_safe);
+        assert!(common_trait.object_safe);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only one #[common_fields_trait(...)] annotation is allowed")]
+    fn test_multiple_trait_attributes() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields_trait(HasKey)]
+            #[common_fields_trait(HasId)]
+            enum TestEnum {
+                Variant1 { key: String },
+            }
+        };
+
+        parse_trait_attribute(&input);
+    }
+
+    #[test]
+    fn test_hash_attribute() {
+        let input: DeriveInput = parse_quote! {
+            #[common_field(key: String)]
+            #[common_fields_hash(key, seq)]
+            enum TestEnum {
+                Variant1 { key: String, seq: u64 },
+            }
+        };
+
+        assert_eq!(
+            parse_field_list_attribute(&input, "common_fields_hash").unwrap(),
+            ["key", "seq"]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "#[common_fields_hash(...)] requires at least one field")]
+    fn test_empty_hash_attribute() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields_hash()]
+            enum TestEnum {
+                Variant1 { key: String },
+            }
+        };
+
+        parse_field_list_attribute(&input, "common_fields_hash");
+    }
+
+    #[test]
+    fn test_hoisted_enum() {
+        let tokens = quote! {
+            #[derive(Debug)]
+            pub enum Job {
+                common { id: Uuid, mut + own name: String, cache: HashMap<String, u32> }
+                Pending,
+                Running { pid: u32 },
+            }
+        };
+        let parsed: HoistedEnum = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.attrs.len(), 1);
+        assert_eq!(parsed.common_fields.len(), 3);
+        assert!(parsed.common_fields[0].modifiers.is_empty());
+        assert_eq!(parsed.common_fields[1].modifiers.to_string(), "mut + own");
+        assert_eq!(parsed.common_fields[1].field_name, "name");
+        assert_eq!(parsed.common_fields[2].field_name, "cache");
+        assert_eq!(parsed.variants.len(), 2);
+    }
+
+    #[test]
+    fn test_foreign_enum() {
+        let tokens = quote! {
+            pub for other::Event {
+                mut key: String in Created { key }, Deleted(d => d.key);
+                seq: u64 in Created { seq }, Deleted(d => d.seq)
+            }
+        };
+        let parsed: ForeignEnum = syn::parse2(tokens).expect("Failed to parse");
+
+        assert_eq!(parsed.trait_name, "EventCommonFields");
+        assert_eq!(parsed.fields.len(), 2);
+        assert!(parsed.fields[0].mutable && !parsed.fields[1].mutable);
+        let branches = &parsed.fields[0].branches;
+        assert_eq!(branches[0].binding, "key");
+        assert!(branches[0].expression.is_none());
+        assert_eq!(branches[1].variant, "Deleted");
+        assert!(branches[1].expression.is_some());
+    }
+
+    #[test]
+    fn test_tuple_attributes() {
+        let input: DeriveInput = parse_quote! {
+            #[common_tuple(0 as id: Uuid, 1 as name: String)]
+            enum TestEnum {
+                Variant1(Uuid, String),
+            }
+        };
+
+        let tuples = parse_tuple_attributes(&input);
+        assert_eq!(tuples.len(), 2);
+        assert_eq!(tuples[0].index, 0);
+        assert_eq!(tuples[0].name, "id");
+        assert_eq!(tuples[1].index, 1);
+        assert_eq!(tuples[1].name, "name");
+    }
+
+    #[test]
+    fn test_delegated_variant() {
+        let input: DeriveInput = parse_quote! {
+            enum TestEnum {
+                Variant1 { key: String },
+                #[common_field(delegate)]
+                Variant2(Inner),
+            }
+        };
+        let syn::Data::Enum(data) = input.data else {
+            unreachable!()
+        };
+
+        let variants = parse_enum_variants(data).unwrap();
+        assert!(!variants[0].delegated);
+        assert!(variants[1].delegated);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Variant Variant1 is not a tuple variant, so it can't delegate to the inner type"
+    )]
+    fn test_delegated_struct_variant() {
+        let input: DeriveInput = parse_quote! {
+            enum TestEnum {
+                #[common_field(delegate)]
+                Variant1 { key: String },
+            }
+        };
+        let syn::Data::Enum(data) = input.data else {
+            unreachable!()
+        };
+
+        parse_enum_variants(data).unwrap();
+    }
+
+    #[test]
+    fn test_malformed_variant_annotation() {
+        let input: DeriveInput = parse_quote! {
+            enum TestEnum {
+                #[common_field(delegat)]
+                Variant1(Inner),
+            }
+        };
+        let syn::Data::Enum(data) = input.data else {
+            unreachable!()
+        };
+        let error = parse_enum_variants(data).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Expected format of variant annotation: #[common_field(delegate)]"
+        );
+
+        let input: DeriveInput = parse_quote! {
+            enum TestEnum {
+                #[common_field]
+                Variant1(Inner),
+            }
+        };
+        let syn::Data::Enum(data) = input.data else {
+            unreachable!()
+        };
+        assert!(parse_enum_variants(data).is_err());
+    }
+
+    #[test]
+    fn test_delegate_attributes() {
+        let input: DeriveInput = parse_quote! {
+            #[common_delegate(len(&self) -> usize via items, push(&mut self, item: u32,) via items)]
+            #[common_delegate(into_iter(self) -> std::vec::IntoIter<u32> via items)]
+            enum TestEnum {
+                Variant1 { items: Vec<u32> },
+            }
+        };
+
+        let delegates = parse_delegate_attributes(&input);
+        assert_eq!(delegates.len(), 3);
+        assert_eq!(delegates[0].name, "len");
+        assert!(delegates[0].arguments.is_empty());
+        assert_eq!(delegates[0].output, parse_quote!(-> usize));
+        assert_eq!(delegates[0].field_name, "items");
+        assert!(delegates[1].receiver.mutability.is_some());
+        assert_eq!(
+            delegates[1].arguments,
+            vec![(format_ident!("item"), parse_quote!(u32))]
+        );
+        assert_eq!(delegates[1].output, syn::ReturnType::Default);
+        assert!(delegates[2].receiver.reference.is_none());
+    }
+
+    #[test]
+    fn test_delegate_without_via() {
+        let result = syn::parse2::<CommonDelegate>(parse_quote! { len(&self) -> usize items });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_snake_case() {
+        assert_eq!(snake_case("Running"), "running");
+        assert_eq!(snake_case("VariantOne"), "variant_one");
+        assert_eq!(snake_case("HTTPRequest"), "http_request");
+        assert_eq!(snake_case("V2Update"), "v2_update");
+    }
+
+    #[test]
+    fn test_vis_option() {
+        let common_field: CommonField = parse_quote!(mut key: String, vis = pub(crate));
+        let vis = common_field.vis.unwrap();
+        assert_eq!(quote!(#vis).to_string(), "pub (crate)");
+        let common_field: CommonField = parse_quote!(key: String, vis =, since = "1.0");
+        assert!(matches!(common_field.vis, Some(syn::Visibility::Inherited)));
+        assert_eq!(common_field.since.as_deref(), Some("1.0"));
+    }
+
+    #[test]
+    fn test_config_vis() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields(vis = pub(crate), common)]
+            enum TestEnum {
+                Variant1 { key: i32 },
+            }
+        };
+
+        let config = parse_config_attributes(&input);
+        let vis = config.vis();
+        assert_eq!(quote!(#vis).to_string(), "pub (crate)");
+        assert!(config.common);
+        let vis = CommonFieldsConfig::default().vis();
+        assert_eq!(quote!(#vis).to_string(), "pub");
+    }
+
+    #[test]
+    fn test_config_name_templates() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields(getter = "get_{field}", owner = "take_{field}")]
+            enum TestEnum {
+                Variant1 { key: i32 },
+            }
+        };
+
+        let config = parse_config_attributes(&input);
+        let key = format_ident!("key");
+        assert_eq!(config.accessor_name(&GetterKind::ReadOnly, &key), "get_key");
+        assert_eq!(config.accessor_name(&GetterKind::Mutable, &key), "key_mut");
+        assert_eq!(config.accessor_name(&GetterKind::Owning, &key), "take_key");
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected accessor name template with {field} placeholder")]
+    fn test_config_name_template_without_placeholder() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields(getter = "get")]
+            enum TestEnum {
+                Variant1 { key: i32 },
+            }
+        };
+
+        parse_config_attributes(&input);
+    }
+
+    #[test]
+    fn test_several_field_names() {
+        let common_field: CommonField = parse_quote!(mut width, height: u32, since = "1.0");
+        let fields = common_field.flatten();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].field_name, "width");
+        assert_eq!(fields[1].field_name, "height");
+        assert!(fields.iter().all(|field| field.kinds
+            == [GetterKind::ReadOnly, GetterKind::Mutable]
+            && field.since.as_deref() == Some("1.0")
+            && field.more_names.is_empty()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Annotation with several fields can't name accessors")]
+    fn test_several_field_names_with_name() {
+        let _: CommonField = parse_quote!(width, height as size: u32);
+    }
+
+    #[test]
+    fn test_several_field_names_with_traits() {
+        for tokens in [
+            quote!(mut width, height: u32, eq),
+            quote!(own width, height: u32, since = "1.0", from),
+            quote!(deref width, height: u32),
+            quote!(width, height: u32, impl = Size::width),
+        ] {
+            let error = syn::parse2::<CommonField>(tokens).err().unwrap();
+            assert!(error
+                .to_string()
+                .starts_with("Annotation with several fields can't implement traits"));
+        }
+        let common_field: CommonField = parse_quote!(width, height: u32, alias = "size");
+        assert_eq!(common_field.more_names.len(), 1);
+    }
+
+    #[test]
+    fn test_common_markers() {
+        let input: DeriveInput = parse_quote! {
+            enum TestEnum {
+                Variant1 {
+                    #[common(mut as k, since = "1.0")]
+                    key: String,
+                    #[common]
+                    seq: u64,
+                },
+                Variant2 {
+                    #[common]
+                    key: String,
+                    seq: u64,
+                },
+            }
+        };
+
+        let common_fields = parse_common_markers(&input, &[]);
+        assert_eq!(common_fields.len(), 2);
+        assert_eq!(common_fields[0].field_name, "key");
+        assert_eq!(
+            common_fields[0].kinds,
+            [GetterKind::ReadOnly, GetterKind::Mutable]
+        );
+        assert_eq!(common_fields[0].resulting_name.as_ref().unwrap(), "k");
+        assert_eq!(common_fields[0].since.as_deref(), Some("1.0"));
+        assert_eq!(common_fields[1].field_name, "seq");
+        assert_eq!(common_fields[1].kinds, [GetterKind::ReadOnly]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Field key of variant Variant2 has #[common(...)] marker different from the first one"
+    )]
+    fn test_different_common_markers() {
+        let input: DeriveInput = parse_quote! {
+            enum TestEnum {
+                Variant1 {
+                    #[common(mut)]
+                    key: String,
+                },
+                Variant2 {
+                    #[common(own)]
+                    key: String,
+                },
+            }
+        };
+
+        parse_common_markers(&input, &[]);
+    }
+
+    #[test]
+    fn test_hoisted_arguments() {
+        let arguments: HoistedArguments = parse_quote!(constructors, fields(id: u64, mut + own name: String), rename_map = "id => key");
+        assert_eq!(arguments.common_fields.len(), 2);
+        assert_eq!(arguments.common_fields[1].field_name, "name");
+        assert_eq!(
+            arguments.common_fields[1].modifiers.to_string(),
+            "mut + own"
+        );
+        let config = &arguments.config;
+        assert_eq!(
+            quote!(#(#config),*).to_string(),
+            "constructors , rename_map = \"id => key\""
+        );
+    }
+
+    #[test]
+    fn test_detect_common_fields() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields_config(auto, exclude(cache))]
+            enum TestEnum {
+                Variant1 { key: String, seq: u64, cache: Vec<u8>, id: u32 },
+                Variant2 { id: u64, cache: Vec<u8>, seq: u64, key: String },
+                Variant3 { seq: u64, key: String, cache: Vec<u8> },
+            }
+        };
+
+        let config = parse_config_attributes(&input);
+        assert!(config.auto);
+        let declared: Vec<CommonField> = vec![parse_quote!(mut key: str)];
+        let common_fields = detect_common_fields(&input, &config.exclude, &declared);
+        assert_eq!(common_fields.len(), 1);
+        assert_eq!(common_fields[0].field_name, "seq");
+        assert_eq!(common_fields[0].kinds, [GetterKind::ReadOnly]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Variant Variant2 is not a struct variant, which is not supported by auto option"
+    )]
+    fn test_detect_common_fields_in_tuple_variant() {
+        let input: DeriveInput = parse_quote! {
+            enum TestEnum {
+                Variant1 { key: String },
+                Variant2(String),
+            }
+        };
+
+        detect_common_fields(&input, &[], &[]);
+    }
+
+    #[test]
+    fn test_inline_option() {
+        let common_field: CommonField = parse_quote!(key: String, inline(always));
+        let inline = common_field.inline.unwrap();
+        assert_eq!(quote!(#inline).to_string(), "# [inline (always)]");
+        let common_field: CommonField = parse_quote!(key: String, inline, since = "1.0");
+        let inline = common_field.inline.unwrap();
+        assert_eq!(quote!(#inline).to_string(), "# [inline]");
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected inline, inline(always) or inline(never)")]
+    fn test_wrong_inline_option() {
+        let _: CommonField = parse_quote!(key: String, inline(sometimes));
+    }
+
+    #[test]
+    fn test_add_inline() {
+        let generated = add_inline(
+            &parse_quote!(#[inline]),
+            quote! {
+                impl TestEnum {
+                    fn key(&self) -> &String { todo!() }
+                    #[inline(never)]
+                    fn seq(&self) -> &u64 { todo!() }
+                }
+            },
+        );
+        let generated: syn::ItemImpl = syn::parse2(generated).unwrap();
+        let attributes: Vec<_> = generated
+            .items
+            .iter()
+            .map(|item| {
+                let syn::ImplItem::Fn(method) = item else {
+                    panic!("Expected method")
+                };
+                let attrs = &method.attrs;
+                quote!(#(#attrs)*).to_string()
+            })
+            .collect();
+        assert_eq!(attributes, ["# [inline]", "# [inline (never)]"]);
+    }
+
+    #[test]
+    fn test_const_option() {
+        let common_field: CommonField = parse_quote!(copy id: u64, const, since = "1.0");
+        assert!(common_field.constant);
+        assert_eq!(common_field.since.as_deref(), Some("1.0"));
+        assert_eq!(common_field.options, ["const", "since"]);
+        let common_field: CommonField = parse_quote!(id: u64);
+        assert!(!common_field.constant);
+    }
+
+    #[test]
+    fn test_const_option_conflicts() {
+        for tokens in [
+            quote!(id as id_len: usize, const, get = |id: &u64| *id as usize),
+            quote!(id via id(): u64, const),
+        ] {
+            let error = syn::parse2::<CommonField>(tokens).err().unwrap();
+            assert_eq!(
+                error.to_string(),
+                "\"const\" option can't be used with get option and via method, since const fn can't call them"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Option impl of #[common_field] annotation for key is denied")]
+    fn test_config_deny_keyword_options() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields_config(deny(const, impl))]
+            enum TestEnum {
+                Variant1 { key: i32 },
+            }
+        };
 
-    if common_fields.is_empty() {
-        panic!("EnumCommonFields requires at least one #[common_field] annotation")
+        let config = parse_config_attributes(&input);
+        assert_eq!(config.deny, ["const", "impl"]);
+        let mut key: CommonField = parse_quote!(key: i32, impl = Keyed::key);
+        config.apply_defaults(&mut key);
     }
 
-    let enum_name = ast.ident;
-    let variants: Vec<_> = match ast.data {
-        syn::Data::Enum(e) => parse_enum_variants(e),
-        _ => panic!("EnumCommonFields can only be applied to enums"),
-    };
+    #[test]
+    fn test_field_docs() {
+        let input: DeriveInput = parse_quote! {
+            #[common_field(key: String)]
+            enum TestEnum {
+                Variant1(Payload),
+                #[common_field_rename(key = id)]
+                Variant2 {
+                    seq: u64,
+                    /// Stable identifier
+                    #[allow(unused)]
+                    id: String,
+                },
+            }
+        };
+        let syn::Data::Enum(data) = input.data else {
+            panic!("Expected enum")
+        };
 
-    if variants.is_empty() {
-        return TokenStream::new();
+        let variants = parse_enum_variants(data).unwrap();
+        let key = format_ident!("key");
+        assert!(variants[0].field_doc(&key).is_empty());
+        let doc = variants[1].field_doc(&key);
+        assert_eq!(doc.len(), 1);
+        assert_eq!(
+            quote!(#(#doc)*).to_string(),
+            "# [doc = r\" Stable identifier\"]"
+        );
+        assert!(variants[1].field_doc(&format_ident!("seq")).is_empty());
     }
 
-    let mut stream = quote!();
+    #[test]
+    fn test_doc_option() {
+        let common_field: CommonField =
+            parse_quote!(flatten meta: Meta { id: u64 }, doc = "Metadata of the record");
+        let fields = common_field.flatten();
+        assert_eq!(fields[0].doc.as_deref(), Some("Metadata of the record"));
+        assert_eq!(fields[1].doc, None);
+    }
 
-    for CommonField {
-        kinds,
-        field_name,
-        field_type,
-        resulting_name,
-    } in common_fields
-    {
-        if resulting_name.is_some() && kinds.len() != 1 {
-            panic!("\"as getter_name\" syntax is supported only for single getter annotations (own_only, mut_only or immutable [no annotations])")
-        }
-        for kind in kinds {
-            match kind {
-                GetterKind::ReadOnly => {
-                    stream.extend(generate_accessor(
-                        &enum_name,
-                        &variants,
-                        &field_name,
-                        &field_type,
-                        quote!(&),
-                        resulting_name.clone().unwrap_or_else(|| field_name.clone()),
-                    ));
-                }
-                GetterKind::Mutable => {
-                    stream.extend(generate_accessor(
-                        &enum_name,
-                        &variants,
-                        &field_name,
-                        &field_type,
-                        quote!(&mut),
-                        resulting_name
-                            .clone()
-                            .unwrap_or_else(|| format_ident!("{field_name}_mut")),
-                    ));
-                }
-                GetterKind::Owning => {
-                    stream.extend(generate_accessor(
-                        &enum_name,
-                        &variants,
-                        &field_name,
-                        &field_type,
-                        quote!(),
-                        resulting_name
-                            .clone()
-                            .unwrap_or_else(|| format_ident!("into_{field_name}")),
-                    ));
-                }
-            }
-        }
+    #[test]
+    fn test_alias_option() {
+        let common_field: CommonField =
+            parse_quote!(key: String, alias = "identifier", since = "1.0", alias = "id");
+        assert_eq!(common_field.aliases, ["identifier", "id"]);
     }
-    TokenStream::from(stream)
-}
 
-fn generate_accessor(
-    enum_name: &Ident,
-    variants: &Vec<EnumVariantInfo>,
-    field_name: &Ident,
-    field_type: &Ident,
-    ref_token: proc_macro2::TokenStream,
-    resulting_name: Ident,
-) -> proc_macro2::TokenStream {
-    let match_branches: Vec<_> = variants
-        .clone()
-        .iter()
-        .map(|EnumVariantInfo { name, is_struct }| {
-            if *is_struct {
-                quote!(Self::#name{#field_name, ..} => #field_name)
-            } else {
-                quote!(Self::#name(v) => #ref_token v.#field_name)
+    #[test]
+    fn test_trait_method_attributes() {
+        let method = trait_method(quote! {
+            impl TestEnum {
+                /// Key of the record
+                #[doc(alias = "identifier")]
+                #[must_use]
+                #[inline]
+                pub const fn key(&self) -> &String { todo!() }
             }
-        })
-        .collect();
-    quote! {
-        impl #enum_name {
-            pub fn #resulting_name(#ref_token self) -> #ref_token #field_type {
-                match self {
-                    #(#match_branches,)*
-                }
+        });
+        assert_eq!(
+            quote!(#method).to_string(),
+            quote! {
+                /// Key of the record
+                #[inline]
+                fn key(&self) -> &String { todo!() }
             }
-        }
+            .to_string()
+        );
     }
-}
 
-fn parse_common_fields_attributes(ast: &DeriveInput) -> Vec<CommonField> {
-    ast
-        .attrs
-        .iter()
-        .filter_map(|attr| {
-            // Checking that we have only #[common_field ...] attributes
-            if attr.path().is_ident("common_field") {
-                // Checking that the attribute has parenthesis like this #[common_field(...)]
-                if let Meta::List(list) = &attr.meta {
-                    // Parsing data of the attribute
-                    Some(syn::parse2::<CommonField>(list.tokens.clone()).unwrap())
-                } else {
-                    panic!("Expected format: #[common_field([all|own|own_only|mut|mut_only] field_name [as getter_name]: Type)]")
-                }
-            } else {
-                None
+    #[test]
+    fn test_cfg_prefix() {
+        let common_field: CommonField =
+            parse_quote!(cfg(feature = "admin") flatten mut meta: Meta { id: u64 });
+        let cfg = common_field.cfg.as_ref().unwrap();
+        assert_eq!(
+            quote!(#cfg).to_string(),
+            quote!(#[cfg(feature = "admin")]).to_string()
+        );
+        assert_eq!(
+            common_field.kinds,
+            [GetterKind::ReadOnly, GetterKind::Mutable]
+        );
+        assert!(common_field
+            .flatten()
+            .iter()
+            .all(|field| field.cfg.is_some()));
+        // Field named cfg isn't mistaken for the prefix
+        let common_field: CommonField = parse_quote!(cfg: Config);
+        assert_eq!(common_field.field_name, "cfg");
+        assert!(common_field.cfg.is_none());
+    }
+
+    #[test]
+    fn test_gated_methods() {
+        let generated: syn::ItemImpl = parse_quote! {
+            #[cfg(feature = "admin")]
+            impl TestEnum {
+                #[must_use]
+                pub fn secret(&self) -> &String { todo!() }
             }
-        })
-        .collect()
-}
+        };
+        let methods = gated_methods(generated);
+        assert_eq!(methods.len(), 1);
+        let attrs = &methods[0].attrs;
+        assert_eq!(
+            quote!(#(#attrs)*).to_string(),
+            quote!(#[cfg(feature = "admin")] #[must_use]).to_string()
+        );
+    }
 
-#[cfg(test)]
-mod common_field_parsing_tests {
-    use super::*;
-    use syn::parse_quote;
     #[test]
-    fn test_basic_field() {
-        let tokens = parse_quote! { field1: i32 };
-        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+    fn test_type_string() {
+        let field_type: syn::Type =
+            parse_quote!(::std::collections::HashMap<&'static str, Vec<(u8, [u8; 4])>>);
+        assert_eq!(
+            type_string(&field_type),
+            "::std::collections::HashMap<&'static str, Vec<(u8, [u8; 4])>>"
+        );
+        assert_eq!(json_string("a\"b\\c\n"), r#""a\"b\\c\u000a""#);
+    }
 
-        assert_eq!(parsed.field_name, "field1");
-        assert_eq!(parsed.field_type, "i32");
-        assert_eq!(parsed.kinds, vec![GetterKind::ReadOnly]);
-        assert!(parsed.resulting_name.is_none());
+    #[test]
+    fn test_template_placeholders() {
+        assert_eq!(
+            template_placeholders("{variant}({key}, seq={seq:>4}) {{literal}} {key}"),
+            vec!["variant", "key", "seq"]
+        );
+        assert!(template_placeholders("no placeholders {{}}").is_empty());
     }
 
     #[test]
-    fn test_field_with_custom_name() {
-        let tokens = parse_quote! { field1 as custom_name: i32 };
-        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+    fn test_config_columns() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields_config(columns)]
+            enum TestEnum {
+                Variant1 { key: i32 },
+            }
+        };
 
-        assert_eq!(parsed.field_name, "field1");
-        assert_eq!(parsed.resulting_name.unwrap(), "custom_name");
-        assert_eq!(parsed.field_type, "i32");
-        assert_eq!(parsed.kinds, vec![GetterKind::ReadOnly]);
+        let config = parse_config_attributes(&input);
+        assert!(config.columns);
+        assert!(!config.proptest);
     }
 
     #[test]
-    fn test_mutable_field() {
-        let tokens = parse_quote! { mut field1: i32 };
-        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+    #[cfg(feature = "arbitrary")]
+    fn test_config_arbitrary() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields_config(into_common, arbitrary)]
+            enum TestEnum {
+                Variant1 { key: i32 },
+            }
+        };
 
-        assert_eq!(parsed.field_name, "field1");
-        assert_eq!(parsed.field_type, "i32");
+        let config = parse_config_attributes(&input);
+        assert!(config.arbitrary && config.into_common);
+    }
+
+    #[test]
+    #[cfg(feature = "audit")]
+    fn test_config_audit_hook() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields_config(audit_hook = crate::audit::on_access)]
+            enum TestEnum {
+                Variant1 { key: i32 },
+            }
+        };
+
+        let config = parse_config_attributes(&input);
         assert_eq!(
-            parsed.kinds,
-            vec![GetterKind::ReadOnly, GetterKind::Mutable]
+            config.audit_hook,
+            Some(parse_quote!(crate::audit::on_access))
+        );
+        assert_eq!(
+            config.audit_call(&format_ident!("key")).to_string(),
+            quote!(crate::audit::on_access("key");).to_string()
         );
-        assert!(parsed.resulting_name.is_none());
     }
 
     #[test]
-    fn test_owning_field() {
-        let tokens = parse_quote! { own_only field1: i32 };
-        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+    fn test_config_remote() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields_config(remote = "other_crate::events::Event")]
+            enum Event {
+                Variant1 { key: i32 },
+            }
+        };
 
-        assert_eq!(parsed.field_name, "field1");
-        assert_eq!(parsed.field_type, "i32");
-        assert_eq!(parsed.kinds, vec![GetterKind::Owning]);
-        assert!(parsed.resulting_name.is_none());
+        let config = parse_config_attributes(&input);
+        let remote = config.remote.unwrap();
+        assert_eq!(remote.segments.len(), 3);
+        assert_eq!(remote.segments.last().unwrap().ident, "Event");
     }
 
     #[test]
-    fn test_all_field() {
-        let tokens = parse_quote! { all field1: i32 };
-        let parsed: CommonField = syn::parse2(tokens).expect("Failed to parse");
+    fn test_config_common_iter() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields_config(common(iter), columns)]
+            enum TestEnum {
+                Variant1 { key: i32 },
+            }
+        };
 
-        assert_eq!(parsed.field_name, "field1");
-        assert_eq!(parsed.field_type, "i32");
-        assert_eq!(
-            parsed.kinds,
-            vec![
-                GetterKind::Owning,
-                GetterKind::Mutable,
-                GetterKind::ReadOnly
-            ]
-        );
-        assert!(parsed.resulting_name.is_none());
+        let config = parse_config_attributes(&input);
+        assert!(config.common && config.common_iter && config.columns);
+        assert!(!config.common_serde);
     }
 
     #[test]
-    fn test_invalid_format() {
-        let tokens = parse_quote! { field1 i32 };
-        let result: Result<CommonField, _> = syn::parse2(tokens);
+    #[should_panic(expected = "Unknown common option")]
+    fn test_config_unknown_common_option() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields_config(common(list))]
+            enum TestEnum {
+                Variant1 { key: i32 },
+            }
+        };
 
-        assert!(result.is_err());
+        parse_config_attributes(&input);
     }
-}
 
-#[cfg(test)]
-mod attributes_parse_tests {
-    use super::*;
-    use syn::parse_quote;
+    #[test]
+    fn test_config_frozen() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields(frozen = "key, key_mut,")]
+            enum TestEnum {
+                Variant1 { key: i32 },
+            }
+        };
+
+        let config = parse_config_attributes(&input);
+        assert_eq!(config.frozen.unwrap(), ["key", "key_mut"]);
+    }
 
     #[test]
-    fn test_no_common_field() {
+    fn test_frozen_methods_match() {
+        let frozen = ["key".to_string(), "key_mut".to_string()];
+        check_frozen_methods(
+            &frozen,
+            vec!["key_mut".to_string(), "key".to_string(), "key".to_string()],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Missing: [key_mut], not in the list: [into_key]")]
+    fn test_frozen_methods_differ() {
+        let frozen = ["key".to_string(), "key_mut".to_string()];
+        check_frozen_methods(&frozen, vec!["key".to_string(), "into_key".to_string()]);
+    }
+
+    #[test]
+    fn test_config_defaults() {
         let input: DeriveInput = parse_quote! {
-            #[derive(Debug)]
+            #[common_fields_config(defaults(since = "1.0", collect), deny(get))]
             enum TestEnum {
-                Variant1 { field1: i32 },
-                Variant2 { field1: i32 },
+                Variant1 { key: i32, seq: i32 },
             }
         };
 
-        let result = parse_common_fields_attributes(&input);
-        assert!(result.is_empty());
+        let config = parse_config_attributes(&input);
+        let mut key: CommonField = parse_quote!(key: i32, since = "1.1");
+        config.apply_defaults(&mut key);
+        assert_eq!(key.since.as_deref(), Some("1.1"));
+        assert_eq!(key.collect, Some(None));
+        assert!(!key.no_coverage);
+
+        let mut seq: CommonField = parse_quote!(seq: i32, collect = all_seqs);
+        config.apply_defaults(&mut seq);
+        assert_eq!(seq.since.as_deref(), Some("1.0"));
+        assert_eq!(seq.collect.unwrap().unwrap(), "all_seqs");
     }
 
     #[test]
-    fn test_single_common_field() {
+    fn test_config_default_doc_and_cfg() {
         let input: DeriveInput = parse_quote! {
-            #[derive(Debug)]
-            #[common_field(field1: i32)]
+            #[common_fields_config(defaults(doc = "Returns {field} of the record", cfg(feature = "accessors")))]
             enum TestEnum {
-                Variant1 { field1: i32 },
-                Variant2 { field1: i32 },
+                Variant1 { key: i32, seq: i32 },
             }
         };
 
-        let result = parse_common_fields_attributes(&input);
-        assert_eq!(result.len(), 1);
-        assert_eq!(result[0].field_name, "field1");
-        assert_eq!(result[0].field_type, "i32");
-        assert_eq!(result[0].kinds, vec![GetterKind::ReadOnly]);
+        let config = parse_config_attributes(&input);
+        assert_eq!(
+            config.default_doc(&format_ident!("key")).as_deref(),
+            Some("Returns key of the record")
+        );
+        let cfg = config.default_cfg.as_ref().unwrap();
+        assert_eq!(
+            quote!(#cfg).to_string(),
+            quote!(#[cfg(feature = "accessors")]).to_string()
+        );
+
+        // Default cfg is kept apart from the cfg(...) prefix, which takes precedence when the accessors are generated
+        let mut key: CommonField = parse_quote!(key: i32);
+        config.apply_defaults(&mut key);
+        assert!(key.cfg.is_none());
+        let mut seq: CommonField = parse_quote!(cfg(test) seq: i32);
+        config.apply_defaults(&mut seq);
+        let cfg = seq.cfg.unwrap();
+        assert_eq!(quote!(#cfg).to_string(), quote!(#[cfg(test)]).to_string());
     }
 
     #[test]
-    fn test_multiple_common_fields() {
+    #[should_panic(expected = "Option cfg of #[common_field] annotation for key is denied")]
+    fn test_config_deny_cfg() {
         let input: DeriveInput = parse_quote! {
-            #[derive(Debug)]
-            #[common_field(field1: i32)]
-            #[common_field(mut field2: String)]
+            #[common_fields_config(deny(cfg))]
             enum TestEnum {
-                Variant1 { field1: i32, field2: String },
-                Variant2 { field1: i32, field2: String },
+                Variant1 { key: i32 },
             }
         };
 
-        let result = parse_common_fields_attributes(&input);
-        assert_eq!(result.len(), 2);
+        let config = parse_config_attributes(&input);
+        let mut key: CommonField = parse_quote!(cfg(test) key: i32);
+        config.apply_defaults(&mut key);
+    }
 
-        assert_eq!(result[0].field_name, "field1");
-        assert_eq!(result[0].field_type, "i32");
-        assert_eq!(result[0].kinds, vec![GetterKind::ReadOnly]);
+    #[test]
+    fn test_config_default_kinds() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields(defaults(kinds = mut + own, no_coverage))]
+            enum TestEnum {
+                Variant1 { key: i32, seq: i32 },
+            }
+        };
 
-        assert_eq!(result[1].field_name, "field2");
-        assert_eq!(result[1].field_type, "String");
+        let config = parse_config_attributes(&input);
+        let mut key: CommonField = parse_quote!(key: i32);
+        config.apply_defaults(&mut key);
         assert_eq!(
-            result[1].kinds,
-            vec![GetterKind::ReadOnly, GetterKind::Mutable]
+            key.kinds,
+            [
+                GetterKind::ReadOnly,
+                GetterKind::Mutable,
+                GetterKind::Owning
+            ]
         );
+        assert!(key.no_coverage);
+
+        let mut seq: CommonField = parse_quote!(ref seq: i32);
+        config.apply_defaults(&mut seq);
+        assert_eq!(seq.kinds, [GetterKind::ReadOnly]);
     }
 
     #[test]
-    fn test_common_field_with_custom_name() {
+    #[should_panic(expected = "Option get of #[common_field] annotation for key is denied")]
+    fn test_config_deny() {
         let input: DeriveInput = parse_quote! {
-            #[derive(Debug)]
-            #[common_field(field1 as custom_name: i32)]
+            #[common_fields_config(deny(default, get))]
+            enum TestEnum {
+                Variant1 { key: i32 },
+            }
+        };
+
+        let config = parse_config_attributes(&input);
+        let mut key: CommonField =
+            parse_quote!(key as key_len: usize, get = |key: &i32| *key as usize);
+        config.apply_defaults(&mut key);
+    }
+
+    #[test]
+    fn test_config_rename_map() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields_config(rename_map = "old_key => key,old_seq=>seq")]
+            enum TestEnum {
+                Variant1 { old_key: i32, old_seq: i32 },
+            }
+        };
+
+        let config = parse_config_attributes(&input);
+        assert_eq!(config.base_name(&format_ident!("old_key")), "key");
+        assert_eq!(config.base_name(&format_ident!("old_seq")), "seq");
+        assert_eq!(config.base_name(&format_ident!("other")), "other");
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected rename_map format")]
+    fn test_config_invalid_rename_map() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields_config(rename_map = "old_key -> key")]
+            enum TestEnum {
+                Variant1 { old_key: i32 },
+            }
+        };
+
+        parse_config_attributes(&input);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown common_fields_config option")]
+    fn test_unknown_config_option() {
+        let input: DeriveInput = parse_quote! {
+            #[common_fields_config(unknown)]
             enum TestEnum {
                 Variant1 { field1: i32 },
-                Variant2 { field1: i32 },
             }
         };
 
-        let result = parse_common_fields_attributes(&input);
-        assert_eq!(result.len(), 1);
-        assert_eq!(result[0].field_name, "field1");
-        assert_eq!(result[0].clone().resulting_name.unwrap(), "custom_name");
-        assert_eq!(result[0].field_type, "i32");
-        assert_eq!(result[0].kinds, vec![GetterKind::ReadOnly]);
+        parse_config_attributes(&input);
     }
 
     #[test]
     #[should_panic(
-        expected = "Expected format: #[common_field([all|own|own_only|mut|mut_only] field_name [as getter_name]: Type)]"
+        expected = "Expected format: #[common_field([cfg(...) ][flatten ][ref|all|own|own_only|mut|mut_only|replace|take_into|map|update|clone|get_or_clone|copy|cow|arc|pin|as_deref|as_ref|deref|expect(\"message\")|cell|atomic|try_own|result|indexed|try_into(FieldType)|bytes|own_str][ + modifier...] field_name[, field_name...] [as getter_name|as (getter_name, ...)]: Type[ { field_name: Type, ... }][, options...])]"
     )]
     fn test_invalid_common_field_format() {
         let input: DeriveInput = parse_quote! {
@@ -0,0 +1,76 @@
+//! Checks that generated code doesn't trigger pedantic clippy lints
+#![deny(clippy::pedantic)]
+
+use enum_common_fields::EnumCommonFields;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+pub struct Payload {
+    pub key: String,
+    pub seq: u64,
+    pub hits: Cell<u32>,
+    pub counter: AtomicU64,
+    pub comment: Option<String>,
+    pub config: Arc<str>,
+}
+
+#[derive(EnumCommonFields)]
+#[common_field(own key: String, collect)]
+#[common_field(replace + take_into + map + update key: String)]
+#[common_field(cow key as key_cow: str)]
+#[common_field(result + indexed key as (key_result, key_with_index): str)]
+#[common_field(copy seq: u64, default = 0)]
+#[common_field(try_into(u64) seq as narrow_seq: u32)]
+#[common_field(try_own seq as try_seq: u64)]
+#[common_field(seq as has_seq: bool, get = |seq: &u64| *seq > 0, default = false)]
+#[common_field(cell hits: u32)]
+#[common_field(atomic counter: u64)]
+#[common_field(as_deref comment: str)]
+#[common_field(expect("comment must be set") comment as comment_expected: String)]
+#[common_field(arc config: str)]
+#[common_field(clone config as config_clone: Arc<str>)]
+#[common_fields_group(mut header(key: String, seq: u64))]
+pub enum Message {
+    Payload(Payload),
+    Inline {
+        key: String,
+        seq: u64,
+        hits: Cell<u32>,
+        counter: AtomicU64,
+        comment: Option<String>,
+        config: Arc<str>,
+    },
+}
+
+#[derive(EnumCommonFields)]
+#[common_field(mut key: String)]
+#[common_field(seq: u64)]
+#[common_fields_config(common(iter), common_mut, into_common, columns)]
+pub enum Envelope {
+    Ping {
+        key: String,
+        seq: u64,
+    },
+    Data {
+        key: String,
+        seq: u64,
+        data: Vec<u8>,
+    },
+}
+
+#[test]
+fn test_pedantic_expansion() {
+    let message = Message::Inline {
+        key: "a".into(),
+        seq: 2,
+        hits: Cell::new(0),
+        counter: AtomicU64::new(0),
+        comment: None,
+        config: "config".into(),
+    };
+    message.set_counter(1, Ordering::Relaxed);
+    assert_eq!(message.counter(Ordering::Relaxed), 1);
+    assert!(message.has_seq());
+    assert_eq!(message.key_with_index(), (1, "a"));
+}
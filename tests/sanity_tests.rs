@@ -1,3 +1,5 @@
+extern crate alloc;
+
 use enum_common_fields::EnumCommonFields;
 
 #[test]
@@ -315,3 +317,3807 @@ fn test_mixed_variant_mutable_accessor() {
     test_enum_tuple.key_mut().push_str(" Accessor");
     assert_eq!(test_enum_tuple.key(), "Mutable Mixed Tuple Accessor");
 }
+
+#[test]
+fn test_replacing_accessor() {
+    struct StructVariant {
+        key: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String)]
+    #[common_field(replace key: String)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String },
+    }
+
+    let mut test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "Old Struct".into(),
+    });
+    assert_eq!(
+        test_enum_struct.replace_key("New Struct".into()),
+        "Old Struct"
+    );
+    assert_eq!(test_enum_struct.key(), "New Struct");
+
+    let mut test_enum_tuple = TestEnum::TupleVariant {
+        key: "Old Tuple".into(),
+    };
+    assert_eq!(test_enum_tuple.replace_key("New Tuple".into()), "Old Tuple");
+    assert_eq!(test_enum_tuple.key(), "New Tuple");
+}
+
+#[test]
+fn test_take_into_accessor() {
+    struct StructVariant {
+        key: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String)]
+    #[common_field(take_into key: String)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String },
+    }
+
+    let mut buffer = String::from("Buffer");
+
+    let mut test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "Struct".into(),
+    });
+    test_enum_struct.take_key_into(&mut buffer);
+    assert_eq!(buffer, "Struct");
+    assert_eq!(test_enum_struct.key(), "Buffer");
+
+    let mut test_enum_tuple = TestEnum::TupleVariant {
+        key: "Tuple".into(),
+    };
+    test_enum_tuple.take_key_into(&mut buffer);
+    assert_eq!(buffer, "Tuple");
+    assert_eq!(test_enum_tuple.key(), "Struct");
+}
+
+#[test]
+fn test_mapping_accessor() {
+    #[derive(Debug, PartialEq)]
+    struct StructVariant {
+        key: String,
+        other: u32,
+    }
+
+    #[derive(EnumCommonFields, Debug, PartialEq)]
+    #[common_field(map key: String)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { other: u32, key: String },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "Struct".into(),
+        other: 1,
+    });
+    assert_eq!(
+        test_enum_struct.map_key(|key| key + " Mapped"),
+        TestEnum::StructVariant(StructVariant {
+            key: "Struct Mapped".into(),
+            other: 1,
+        })
+    );
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        other: 2,
+        key: "Tuple".into(),
+    };
+    assert_eq!(
+        test_enum_tuple.map_key(|key| key + " Mapped"),
+        TestEnum::TupleVariant {
+            other: 2,
+            key: "Tuple Mapped".into(),
+        }
+    );
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn test_bytes_accessor() {
+    struct StructVariant {
+        key: u32,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(bytes key: u32)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: u32 },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant { key: 0x01020304 });
+    assert_eq!(test_enum_struct.key_bytes(), &0x01020304u32.to_ne_bytes());
+
+    let test_enum_tuple = TestEnum::TupleVariant { key: 7 };
+    assert_eq!(test_enum_tuple.key_bytes(), &7u32.to_ne_bytes());
+}
+
+#[test]
+fn test_updating_accessor() {
+    struct StructVariant {
+        key: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String)]
+    #[common_field(update key: String)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String },
+    }
+
+    let mut test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "Struct".into(),
+    });
+    test_enum_struct.update_key(|key| key.push_str(" Updated"));
+    assert_eq!(test_enum_struct.key(), "Struct Updated");
+
+    let mut test_enum_tuple = TestEnum::TupleVariant {
+        key: "Tuple".into(),
+    };
+    test_enum_tuple.update_key(|key| key.push_str(" Updated"));
+    assert_eq!(test_enum_tuple.key(), "Tuple Updated");
+}
+
+#[test]
+fn test_since_table() {
+    #[derive(EnumCommonFields)]
+    #[common_field(own key: String, since = "1.2")]
+    #[common_field(value: u32)]
+    #[common_field(replace value: u32, since = "2.0")]
+    enum TestEnum {
+        _Variant { key: String, value: u32 },
+    }
+
+    assert_eq!(
+        TestEnum::COMMON_FIELDS_SINCE,
+        &[
+            ("into_key", "1.2"),
+            ("key_mut", "1.2"),
+            ("key", "1.2"),
+            ("replace_value", "2.0")
+        ]
+    );
+}
+
+#[test]
+fn test_cloning_accessor() {
+    struct StructVariant {
+        key: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(clone key: String)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "Struct".into(),
+    });
+    let key: String = test_enum_struct.key();
+    assert_eq!(key, "Struct");
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        key: "Tuple".into(),
+    };
+    let key: String = test_enum_tuple.key();
+    assert_eq!(key, "Tuple");
+}
+
+#[test]
+fn test_copying_accessor() {
+    struct StructVariant {
+        id: u64,
+        flag: bool,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(copy id: u64)]
+    #[common_field(copy flag as is_flagged: bool)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { id: u64, flag: bool },
+    }
+
+    let test_enum_tuple = TestEnum::TupleVariant { id: 7, flag: true };
+    assert_eq!(test_enum_tuple.id(), 7);
+    assert!(test_enum_tuple.is_flagged());
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        id: 42,
+        flag: false,
+    });
+    assert_eq!(test_enum_struct.id(), 42);
+    assert!(!test_enum_struct.is_flagged());
+}
+
+#[test]
+fn test_cow_accessor() {
+    use std::borrow::Cow;
+
+    struct StructVariant {
+        name: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(cow name: str)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { name: String },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        name: "Struct".into(),
+    });
+    assert!(matches!(test_enum_struct.name(), Cow::Borrowed("Struct")));
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        name: "Tuple".into(),
+    };
+    assert!(matches!(test_enum_tuple.name(), Cow::Borrowed("Tuple")));
+}
+
+#[test]
+fn test_no_coverage_option() {
+    struct StructVariant {
+        key: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(mut key: String, no_coverage)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String },
+    }
+
+    let mut test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "Struct".into(),
+    });
+    test_enum_struct.key_mut().push_str(" Mutated");
+    assert_eq!(test_enum_struct.key(), "Struct Mutated");
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        key: "Tuple".into(),
+    };
+    assert_eq!(test_enum_tuple.key(), "Tuple");
+}
+
+#[test]
+fn test_arc_accessor() {
+    use std::sync::Arc;
+
+    trait Handler {
+        fn handle(&self) -> &'static str;
+    }
+    struct Noop;
+    impl Handler for Noop {
+        fn handle(&self) -> &'static str {
+            "noop"
+        }
+    }
+
+    struct StructVariant {
+        handler: Arc<dyn Handler>,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(arc handler: dyn Handler)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { handler: Arc<dyn Handler> },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        handler: Arc::new(Noop),
+    });
+    let handler: Arc<dyn Handler> = test_enum_struct.handler_arc();
+    assert_eq!(handler.handle(), "noop");
+    assert_eq!(Arc::strong_count(&handler), 2);
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        handler: Arc::new(Noop),
+    };
+    assert_eq!(test_enum_tuple.handler_arc().handle(), "noop");
+}
+
+#[test]
+fn test_pin_projection() {
+    use std::future::Future;
+    use std::marker::PhantomPinned;
+    use std::pin::{pin, Pin};
+    use std::task::{Context, Poll, Waker};
+
+    struct Countdown {
+        remaining: u32,
+        _pinned: PhantomPinned,
+    }
+    impl Future for Countdown {
+        type Output = &'static str;
+        fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+            // SAFETY: remaining is not structurally pinned
+            let this = unsafe { self.get_unchecked_mut() };
+            if this.remaining == 0 {
+                return Poll::Ready("done");
+            }
+            this.remaining -= 1;
+            Poll::Pending
+        }
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(pin future: Countdown)]
+    #[common_field(copy attempt: u8)]
+    enum TestEnum {
+        First {
+            future: Countdown,
+            attempt: u8,
+        },
+        _Second {
+            future: Countdown,
+            attempt: u8,
+            _other: String,
+        },
+    }
+
+    let mut test_enum = pin!(TestEnum::First {
+        future: Countdown {
+            remaining: 1,
+            _pinned: PhantomPinned,
+        },
+        attempt: 3,
+    });
+    let mut context = Context::from_waker(Waker::noop());
+    assert_eq!(
+        test_enum.as_mut().future_pin().poll(&mut context),
+        Poll::Pending
+    );
+    assert_eq!(
+        test_enum.as_mut().future_pin().poll(&mut context),
+        Poll::Ready("done")
+    );
+    assert_eq!(test_enum.attempt(), 3);
+}
+
+#[cfg(feature = "proptest")]
+mod proptest_strategy {
+    use enum_common_fields::EnumCommonFields;
+    use proptest::prelude::*;
+
+    #[derive(Clone, Debug)]
+    struct Payload {
+        key: String,
+        seq: u64,
+        data: Vec<u8>,
+    }
+
+    #[derive(Clone, Debug, EnumCommonFields)]
+    #[common_field(key: str)]
+    #[common_field(own_only key: String)]
+    #[common_field(copy seq: u64)]
+    #[common_fields_config(proptest)]
+    enum TestEnum {
+        Ping { key: String, seq: u64 },
+        Data(Payload),
+    }
+
+    fn variants() -> impl Strategy<Value = TestEnum> {
+        prop_oneof![
+            Just(TestEnum::Ping {
+                key: String::new(),
+                seq: 0
+            }),
+            any::<Vec<u8>>().prop_map(|data| TestEnum::Data(Payload {
+                key: String::new(),
+                seq: 0,
+                data
+            })),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn common_fields_come_from_field_strategies(
+            value in TestEnum::common_strategy("[a-z]{8}", 1..100u64, variants())
+        ) {
+            prop_assert_eq!(value.key().len(), 8);
+            prop_assert!((1..100).contains(&value.seq()));
+            if let TestEnum::Data(payload) = &value {
+                prop_assert!(payload.data.len() < 100);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_common {
+    use arbitrary::{Arbitrary, Unstructured};
+    use enum_common_fields::EnumCommonFields;
+
+    struct Payload {
+        key: String,
+        id: u32,
+        data: Vec<u8>,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String)]
+    #[common_field(copy id: u32)]
+    #[common_fields_config(common_owned, arbitrary, rename_map = "id => ident")]
+    enum TestEnum {
+        Ping { key: String, id: u32 },
+        Data(Payload),
+    }
+
+    #[test]
+    fn test_with_arbitrary_common() {
+        const BYTES: &[u8] = &[7, 0, 0, 1, b'a', b'b', b'c', 2];
+        let expected = TestEnumCommon::arbitrary(&mut Unstructured::new(BYTES)).unwrap();
+
+        let data = TestEnum::Data(Payload {
+            key: String::new(),
+            id: 0,
+            data: vec![1, 2],
+        })
+        .with_arbitrary_common(&mut Unstructured::new(BYTES))
+        .unwrap();
+        assert_eq!(data.key(), &expected.key);
+        assert_eq!(data.ident(), expected.ident);
+        // Only common fields are replaced
+        let TestEnum::Data(payload) = data else {
+            panic!("Variant has changed")
+        };
+        assert_eq!(payload.data, [1, 2]);
+
+        let ping = TestEnum::Ping {
+            key: String::new(),
+            id: 0,
+        }
+        .with_arbitrary_common(&mut Unstructured::new(BYTES))
+        .unwrap();
+        let common = ping.common_owned();
+        assert_eq!((common.key, common.ident), (expected.key, expected.ident));
+        assert!(matches!(ping, TestEnum::Ping { .. }));
+    }
+}
+
+#[test]
+fn test_as_deref_accessor() {
+    struct StructVariant {
+        key: Option<String>,
+        value: Option<u32>,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(as_deref key: str)]
+    #[common_field(as_deref value: u32)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant {
+            key: Option<String>,
+            value: Option<u32>,
+        },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: Some("Struct".into()),
+        value: None,
+    });
+    assert_eq!(test_enum_struct.key(), Some("Struct"));
+    assert_eq!(test_enum_struct.value(), None);
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        key: None,
+        value: Some(42),
+    };
+    assert_eq!(test_enum_tuple.key(), None);
+    assert_eq!(test_enum_tuple.value(), Some(&42));
+}
+
+#[test]
+fn test_rename_map() {
+    struct StructVariant {
+        old_key: String,
+        old_seq: u64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(own old_key: String)]
+    #[common_field(copy old_seq: u64)]
+    #[common_field(old_seq as raw_seq: u64)]
+    #[common_fields_config(rename_map = "old_key => key, old_seq => seq")]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { old_key: String, old_seq: u64 },
+    }
+
+    let mut test_enum_struct = TestEnum::StructVariant(StructVariant {
+        old_key: "Struct".into(),
+        old_seq: 1,
+    });
+    test_enum_struct.key_mut().push_str(" Renamed");
+    assert_eq!(test_enum_struct.seq(), 1);
+    assert_eq!(test_enum_struct.raw_seq(), &1);
+    assert_eq!(test_enum_struct.into_key(), "Struct Renamed");
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        old_key: "Tuple".into(),
+        old_seq: 2,
+    };
+    assert_eq!(test_enum_tuple.key(), "Tuple");
+    assert_eq!(test_enum_tuple.seq(), 2);
+}
+
+#[test]
+fn test_expect_accessor() {
+    struct StructVariant {
+        session: Option<String>,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(expect("session must be set") session: str)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { session: Option<String> },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        session: Some("Struct".into()),
+    });
+    assert_eq!(test_enum_struct.session(), "Struct");
+
+    let test_enum_tuple = TestEnum::TupleVariant { session: None };
+    let result = std::panic::catch_unwind(|| {
+        let _ = test_enum_tuple.session();
+    });
+    assert_eq!(
+        result.unwrap_err().downcast_ref::<&str>(),
+        Some(&"session must be set")
+    );
+}
+
+#[test]
+fn test_cell_accessor() {
+    use std::cell::Cell;
+
+    struct StructVariant {
+        flag: Cell<bool>,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(cell flag: bool)]
+    #[common_field(flag as flag_cell: Cell<bool>)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { flag: Cell<bool> },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        flag: Cell::new(false),
+    });
+    test_enum_struct.flag_cell().set(true);
+    assert!(test_enum_struct.flag());
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        flag: Cell::new(true),
+    };
+    assert!(test_enum_tuple.flag());
+}
+
+#[test]
+fn test_try_owning_accessor() {
+    #[derive(Debug, PartialEq)]
+    struct StructVariant {
+        key: String,
+    }
+
+    #[derive(Debug, PartialEq, EnumCommonFields)]
+    #[common_field(try_own key: String)]
+    #[common_field(try_own key as try_take_key: String)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String },
+        WithoutKey { value: u32 },
+        Unit,
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant { key: "a".into() });
+    assert_eq!(test_enum_struct.try_into_key(), Ok("a".to_string()));
+
+    let test_enum_tuple = TestEnum::TupleVariant { key: "b".into() };
+    assert_eq!(test_enum_tuple.try_take_key(), Ok("b".to_string()));
+
+    let without_key = TestEnum::WithoutKey { value: 1 };
+    assert_eq!(
+        without_key.try_into_key(),
+        Err(TestEnum::WithoutKey { value: 1 })
+    );
+    assert_eq!(TestEnum::Unit.try_into_key(), Err(TestEnum::Unit));
+}
+
+#[test]
+fn test_atomic_accessors() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    struct StructVariant {
+        flag: AtomicBool,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(atomic flag: bool)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { flag: AtomicBool },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        flag: AtomicBool::new(false),
+    });
+    test_enum_struct.set_flag(true, Ordering::SeqCst);
+    assert!(test_enum_struct.flag(Ordering::SeqCst));
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        flag: AtomicBool::new(true),
+    };
+    test_enum_tuple.set_flag(false, Ordering::SeqCst);
+    assert!(!test_enum_tuple.flag(Ordering::SeqCst));
+}
+
+#[test]
+fn test_default_option() {
+    struct StructVariant {
+        tags: Vec<String>,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(clone tags: Vec<String>, default = Vec::new())]
+    #[common_field(own_only tags as into_tags: Vec<String>, default = vec!["none".to_string()])]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { tags: Vec<String> },
+        WithoutTags { value: u32 },
+        Unit,
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        tags: vec!["a".into()],
+    });
+    assert_eq!(test_enum_struct.tags(), vec!["a".to_string()]);
+    assert_eq!(test_enum_struct.into_tags(), vec!["a".to_string()]);
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        tags: vec!["b".into()],
+    };
+    assert_eq!(test_enum_tuple.tags(), vec!["b".to_string()]);
+
+    let without_tags = TestEnum::WithoutTags { value: 1 };
+    assert!(matches!(without_tags, TestEnum::WithoutTags { value: 1 }));
+    assert!(without_tags.tags().is_empty());
+    assert_eq!(without_tags.into_tags(), vec!["none".to_string()]);
+    assert!(TestEnum::Unit.tags().is_empty());
+}
+
+#[test]
+fn test_collect_option() {
+    struct StructVariant {
+        key: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(mut key: String, collect)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String },
+    }
+
+    let items = vec![
+        TestEnum::StructVariant(StructVariant { key: "a".into() }),
+        TestEnum::TupleVariant { key: "b".into() },
+    ];
+    assert_eq!(TestEnum::collect_keys(&items), vec!["a", "b"]);
+    assert!(TestEnum::collect_keys(&[]).is_empty());
+}
+
+#[test]
+fn test_result_accessor() {
+    use std::error::Error;
+
+    struct StructVariant {
+        key: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(result key: String)]
+    #[common_field(result key as key_str: str)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String },
+        Unit,
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant { key: "a".into() });
+    assert_eq!(test_enum_struct.key(), Ok(&"a".to_string()));
+
+    let test_enum_tuple = TestEnum::TupleVariant { key: "b".into() };
+    assert_eq!(test_enum_tuple.key_str(), Ok("b"));
+
+    let error = TestEnum::Unit.key_str().unwrap_err();
+    assert_eq!(
+        error,
+        TestEnumMissingFieldError {
+            field: "key",
+            variant: "Unit"
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "variant Unit of TestEnum doesn't have field key"
+    );
+    assert!(error.source().is_none());
+}
+
+#[test]
+fn test_no_std_option() {
+    use alloc::borrow::Cow;
+    use alloc::sync::Arc;
+
+    // Allocating types come from alloc crate, that no_std crates can use too
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String, collect)]
+    #[common_field(cow name: str)]
+    #[common_field(arc meta: u64)]
+    #[common_fields_config(no_std)]
+    enum TestEnum {
+        VariantOne {
+            key: String,
+            name: String,
+            meta: Arc<u64>,
+        },
+        VariantTwo {
+            key: String,
+            name: String,
+            meta: Arc<u64>,
+        },
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String)]
+    #[common_fields_config(columns, no_std)]
+    enum Keyed {
+        VariantOne { key: String },
+        VariantTwo { key: String },
+    }
+
+    let items = [
+        TestEnum::VariantOne {
+            key: "a".into(),
+            name: "one".into(),
+            meta: Arc::new(1),
+        },
+        TestEnum::VariantTwo {
+            key: "b".into(),
+            name: "two".into(),
+            meta: Arc::new(2),
+        },
+    ];
+    assert!(matches!(items[0].name(), Cow::Borrowed("one")));
+    assert_eq!(*items[1].meta_arc(), 2);
+    assert_eq!(TestEnum::collect_keys(&items), ["a", "b"]);
+    let columns = Keyed::columns([
+        Keyed::VariantOne { key: "c".into() },
+        Keyed::VariantTwo { key: "d".into() },
+    ]);
+    assert_eq!(columns.keys, ["c", "d"]);
+}
+
+#[test]
+fn test_columns_option() {
+    struct StructVariant {
+        key: String,
+        seq: u64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String)]
+    #[common_field(seq: u64)]
+    #[common_fields_config(columns, rename_map = "seq => sequence")]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String, seq: u64 },
+    }
+
+    let items = vec![
+        TestEnum::StructVariant(StructVariant {
+            key: "a".into(),
+            seq: 1,
+        }),
+        TestEnum::TupleVariant {
+            key: "b".into(),
+            seq: 2,
+        },
+    ];
+    let columns: TestEnumCommonColumns = TestEnum::columns(items);
+    assert_eq!(columns.keys, vec!["a", "b"]);
+    assert_eq!(columns.sequences, vec![1, 2]);
+
+    let empty = TestEnum::columns([]);
+    assert!(empty.keys.is_empty() && empty.sequences.is_empty());
+}
+
+#[test]
+fn test_group_accessor() {
+    struct StructVariant {
+        key: String,
+        seq: u64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(mut seq: u64)]
+    #[common_fields_group(header(key: str, seq: u64))]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String, seq: u64 },
+    }
+
+    let mut test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "a".into(),
+        seq: 1,
+    });
+    *test_enum_struct.seq_mut() += 1;
+    assert_eq!(test_enum_struct.header(), ("a", &2));
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        key: "b".into(),
+        seq: 3,
+    };
+    let (key, seq) = test_enum_tuple.header();
+    assert_eq!((key, *seq), ("b", 3));
+}
+
+#[test]
+fn test_common_ref() {
+    struct StructVariant {
+        key: String,
+        seq: u64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String)]
+    #[common_field(seq: u64)]
+    #[common_fields_config(common, rename_map = "seq => sequence")]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String, seq: u64 },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "a".into(),
+        seq: 1,
+    });
+    let common: TestEnumCommonRef = test_enum_struct.common();
+    assert_eq!((common.key.as_str(), *common.sequence), ("a", 1));
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        key: "b".into(),
+        seq: 2,
+    };
+    let common = test_enum_tuple.common();
+    let copied = common;
+    assert_eq!((common.key.as_str(), *copied.sequence), ("b", 2));
+}
+
+#[test]
+fn test_common_mut() {
+    struct StructVariant {
+        key: String,
+        seq: u64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: str)]
+    #[common_field(seq: u64)]
+    #[common_fields_config(common, common_mut)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String, seq: u64 },
+    }
+
+    let mut test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "a".into(),
+        seq: 1,
+    });
+    let common: TestEnumCommonMut = test_enum_struct.common_mut();
+    common.key.make_ascii_uppercase();
+    *common.seq += 1;
+    assert_eq!(test_enum_struct.key(), "A");
+    assert_eq!(*test_enum_struct.common().seq, 2);
+
+    let mut test_enum_tuple = TestEnum::TupleVariant {
+        key: "b".into(),
+        seq: 3,
+    };
+    *test_enum_tuple.common_mut().seq = 4;
+    assert_eq!(*test_enum_tuple.seq(), 4);
+}
+
+#[cfg(feature = "audit")]
+mod audit {
+    use enum_common_fields::EnumCommonFields;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static ACCESSED: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn on_access(field_name: &'static str) {
+        ACCESSED.with(|accessed| accessed.borrow_mut().push(field_name));
+    }
+
+    #[test]
+    fn test_audit_hook() {
+        struct StructVariant {
+            key: String,
+            seq: u64,
+        }
+
+        #[derive(EnumCommonFields)]
+        #[common_field(mut key: String)]
+        #[common_field(copy seq: u64, collect)]
+        #[common_fields_config(audit_hook = on_access)]
+        enum TestEnum {
+            StructVariant(StructVariant),
+            TupleVariant { key: String, seq: u64 },
+        }
+
+        let mut test_enum_struct = TestEnum::StructVariant(StructVariant {
+            key: "a".into(),
+            seq: 1,
+        });
+        test_enum_struct.key_mut().push('b');
+        assert_eq!(test_enum_struct.key(), "ab");
+
+        let test_enum_tuple = TestEnum::TupleVariant {
+            key: "c".into(),
+            seq: 2,
+        };
+        assert_eq!(test_enum_tuple.seq(), 2);
+        assert_eq!(
+            TestEnum::collect_seqs(&[test_enum_struct, test_enum_tuple]),
+            vec![&1, &2]
+        );
+
+        ACCESSED.with(|accessed| {
+            assert_eq!(*accessed.borrow(), vec!["key", "key", "seq", "seq", "seq"]);
+        });
+    }
+}
+
+#[test]
+fn test_into_common() {
+    struct StructVariant {
+        key: String,
+        seq: u64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: str)]
+    #[common_field(own_only key: String)]
+    #[common_field(seq: u64)]
+    #[common_fields_config(into_common, rename_map = "seq => sequence")]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String, seq: u64 },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "a".into(),
+        seq: 1,
+    });
+    let common: TestEnumCommon = test_enum_struct.into_common();
+    assert_eq!((common.key, common.sequence), ("a".to_string(), 1));
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        key: "b".into(),
+        seq: 2,
+    };
+    let TestEnumCommon { key, sequence } = test_enum_tuple.into_common();
+    assert_eq!((key.as_str(), sequence), ("b", 2));
+}
+
+#[test]
+fn test_joined_modifiers() {
+    struct StructVariant {
+        key: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(mut_only + own_only + replace key: String)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String },
+    }
+
+    let mut test_enum_struct = TestEnum::StructVariant(StructVariant { key: "a".into() });
+    test_enum_struct.key_mut().push('b');
+    assert_eq!(test_enum_struct.replace_key("c".into()), "ab");
+    assert_eq!(test_enum_struct.into_key(), "c");
+
+    let test_enum_tuple = TestEnum::TupleVariant { key: "d".into() };
+    assert_eq!(test_enum_tuple.into_key(), "d");
+}
+
+#[test]
+fn test_common_iter() {
+    struct StructVariant {
+        key: String,
+        seq: u64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String)]
+    #[common_field(seq: u64)]
+    #[common_fields_config(common(iter), rename_map = "seq => sequence")]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String, seq: u64 },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "a".into(),
+        seq: 1,
+    });
+    let rendered: Vec<_> = test_enum_struct
+        .common()
+        .into_iter()
+        .map(|(name, value)| format!("{name}: {value}"))
+        .collect();
+    assert_eq!(rendered, ["key: a", "sequence: 1"]);
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        key: "b".into(),
+        seq: 2,
+    };
+    let names: Vec<_> = test_enum_tuple
+        .common()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(names, ["key", "sequence"]);
+}
+
+#[test]
+fn test_indexed_accessor() {
+    struct StructVariant {
+        key: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(indexed key: String)]
+    #[common_field(indexed key as key_str_with_index: str)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant { key: "a".into() });
+    assert_eq!(test_enum_struct.key_with_index(), (0, &"a".to_string()));
+
+    let test_enum_tuple = TestEnum::TupleVariant { key: "b".into() };
+    assert_eq!(test_enum_tuple.key_str_with_index(), (1, "b"));
+}
+
+#[test]
+fn test_names_for_every_kind() {
+    struct StructVariant {
+        key: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(own key as (get_key, get_key_mut, take_key): String)]
+    #[common_field(clone + replace key as (swap_key, cloned_key): String)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String },
+    }
+
+    let mut test_enum_struct = TestEnum::StructVariant(StructVariant { key: "a".into() });
+    test_enum_struct.get_key_mut().push('b');
+    assert_eq!(test_enum_struct.get_key(), "ab");
+    assert_eq!(test_enum_struct.swap_key("c".into()), "ab");
+    assert_eq!(test_enum_struct.cloned_key(), "c");
+
+    let test_enum_tuple = TestEnum::TupleVariant { key: "d".into() };
+    assert_eq!(test_enum_tuple.take_key(), "d");
+}
+
+#[test]
+fn test_frozen_option() {
+    struct StructVariant {
+        key: String,
+        seq: u64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(mut key: String, collect)]
+    #[common_field(copy seq: u64)]
+    #[common_fields_group(header(key: String, seq: u64))]
+    #[common_fields(common, frozen = "key, key_mut, collect_keys, seq, header, common")]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String, seq: u64 },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "a".into(),
+        seq: 1,
+    });
+    assert_eq!(test_enum_struct.header(), (&"a".to_string(), &1));
+    assert_eq!(*test_enum_struct.common().key, "a");
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        key: "b".into(),
+        seq: 2,
+    };
+    assert_eq!(test_enum_tuple.seq(), 2);
+}
+
+#[test]
+fn test_as_with_multiple_kinds() {
+    struct StructVariant {
+        identifier: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(own identifier as id: String, collect)]
+    #[common_field(identifier as identifier_str: str)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { identifier: String },
+    }
+
+    let mut test_enum_struct = TestEnum::StructVariant(StructVariant {
+        identifier: "a".into(),
+    });
+    test_enum_struct.id_mut().push('b');
+    assert_eq!(test_enum_struct.id(), "ab");
+    assert_eq!(test_enum_struct.identifier_str(), "ab");
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        identifier: "c".into(),
+    };
+    assert_eq!(
+        TestEnum::collect_ids(&[test_enum_struct, test_enum_tuple]),
+        ["ab", "c"]
+    );
+}
+
+#[test]
+fn test_get_option() {
+    struct StructVariant {
+        key: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key as key_len: usize, get = |key: &String| key.len(), default = 0)]
+    #[common_field(key as key_upper: String, get = |key: &str| key.to_uppercase(), default = String::new())]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String },
+        Unit,
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant { key: "ab".into() });
+    assert_eq!(test_enum_struct.key_len(), 2);
+    assert_eq!(test_enum_struct.key_upper(), "AB");
+
+    let test_enum_tuple = TestEnum::TupleVariant { key: "c".into() };
+    assert_eq!(test_enum_tuple.key_len(), 1);
+    assert_eq!(TestEnum::Unit.key_len(), 0);
+    assert_eq!(TestEnum::Unit.key_upper(), "");
+}
+
+#[test]
+fn test_config_defaults() {
+    struct StructVariant {
+        key: String,
+        seq: u64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(mut key: String)]
+    #[common_field(seq: u64, since = "2.0")]
+    #[common_fields_config(defaults(since = "1.0", collect), deny(default))]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String, seq: u64 },
+    }
+
+    assert_eq!(
+        TestEnum::COMMON_FIELDS_SINCE,
+        &[
+            ("key", "1.0"),
+            ("key_mut", "1.0"),
+            ("collect_keys", "1.0"),
+            ("seq", "2.0"),
+            ("collect_seqs", "2.0"),
+        ]
+    );
+
+    let items = [
+        TestEnum::StructVariant(StructVariant {
+            key: "a".into(),
+            seq: 1,
+        }),
+        TestEnum::TupleVariant {
+            key: "b".into(),
+            seq: 2,
+        },
+    ];
+    assert_eq!(TestEnum::collect_keys(&items), ["a", "b"]);
+    assert_eq!(TestEnum::collect_seqs(&items), [&1, &2]);
+}
+
+#[test]
+fn test_mutable_group_accessor() {
+    struct StructVariant {
+        key: String,
+        seq: u64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_fields_group(mut header(key: str, seq: u64))]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String, seq: u64 },
+    }
+
+    let mut test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "a".into(),
+        seq: 1,
+    });
+    let (key, seq) = test_enum_struct.header_mut();
+    key.make_ascii_uppercase();
+    *seq += 1;
+    assert_eq!(test_enum_struct.header(), ("A", &2));
+
+    let mut test_enum_tuple = TestEnum::TupleVariant {
+        key: "b".into(),
+        seq: 3,
+    };
+    *test_enum_tuple.header_mut().1 = 4;
+    assert_eq!(test_enum_tuple.header(), ("b", &4));
+}
+
+#[test]
+fn test_try_into_accessor() {
+    struct StructVariant {
+        seq: i64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(try_into(i64) seq: u8)]
+    #[common_field(try_into(i64) seq as seq_i128: i128)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { seq: i64 },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant { seq: 255 });
+    assert_eq!(test_enum_struct.seq(), Ok(255));
+
+    let test_enum_tuple = TestEnum::TupleVariant { seq: -1 };
+    assert!(test_enum_tuple.seq().is_err());
+    assert_eq!(test_enum_tuple.seq_i128(), Ok(-1));
+}
+
+#[test]
+fn test_as_ref_accessor() {
+    use std::ffi::OsStr;
+    use std::path::Path;
+
+    struct StructVariant {
+        path: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(as_ref path: Path)]
+    #[common_field(as_ref path as path_os: OsStr)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { path: String },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        path: "/tmp/a".into(),
+    });
+    assert_eq!(test_enum_struct.path().file_name(), Some(OsStr::new("a")));
+
+    let test_enum_tuple = TestEnum::TupleVariant { path: "b".into() };
+    assert_eq!(test_enum_tuple.path_os(), OsStr::new("b"));
+}
+
+#[test]
+fn test_cfg_gated_variants() {
+    struct Always {
+        key: String,
+    }
+
+    #[cfg(feature = "audit")]
+    struct OnlyWithFeature {
+        key: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(own key: String)]
+    #[common_field(map key: String)]
+    #[common_field(indexed key as key_at: String)]
+    #[common_field(key as collected: str, collect)]
+    #[common_fields_group(mut key_group(key: String))]
+    #[common_delegate(len(&self) -> usize via key)]
+    #[common_fields_config(common, common_mut, into_common, columns)]
+    #[common_fields_config(display = "{variant}: {key}", debug)]
+    enum TestEnum {
+        Always(Always),
+        #[cfg(feature = "audit")]
+        WithFeature(OnlyWithFeature),
+        #[cfg(not(feature = "audit"))]
+        WithoutFeature {
+            key: String,
+        },
+        #[cfg(any())]
+        Never(MissingType),
+    }
+
+    #[cfg(feature = "audit")]
+    let gated = TestEnum::WithFeature(OnlyWithFeature { key: "b".into() });
+    #[cfg(not(feature = "audit"))]
+    let gated = TestEnum::WithoutFeature { key: "b".into() };
+    assert_eq!(gated.key(), "b");
+    assert_eq!(gated.key_at(), (1, &"b".to_string()));
+    assert_eq!(gated.len(), 1);
+    #[cfg(feature = "audit")]
+    assert_eq!(gated.to_string(), "WithFeature: b");
+    #[cfg(not(feature = "audit"))]
+    assert_eq!(format!("{gated:?}"), r#"WithoutFeature { key: "b", .. }"#);
+
+    let mut items = vec![TestEnum::Always(Always { key: "a".into() }), gated];
+    items[0].key_mut().push('1');
+    items[1].key_group_mut().0.push('2');
+    assert_eq!(TestEnum::collect_keys(&items), vec!["a1", "b2"]);
+    assert_eq!(items[0].common().key, "a1");
+    items[0].common_mut().key.push('!');
+
+    let items: Vec<_> = items
+        .into_iter()
+        .map(|item| item.map_key(|key| key.to_uppercase()))
+        .collect();
+    assert_eq!(items[1].key_group(), (&"B2".to_string(),));
+    let columns = TestEnum::columns(items);
+    assert_eq!(columns.keys, vec!["A1!", "B2"]);
+}
+
+#[test]
+fn test_delegate() {
+    struct StructVariant {
+        items: Vec<u32>,
+        name: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_delegate(len(&self) -> usize via items, push(&mut self, item: u32) via items)]
+    #[common_delegate(into_iter(self) -> std::vec::IntoIter<u32> via items)]
+    #[common_delegate(starts_with(&self, prefix: &str) -> bool via name)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { items: Vec<u32>, name: String },
+    }
+
+    let mut test_enum_struct = TestEnum::StructVariant(StructVariant {
+        items: vec![1],
+        name: String::new(),
+    });
+    assert!(!test_enum_struct.starts_with("na"));
+    test_enum_struct.push(2);
+    assert_eq!(test_enum_struct.len(), 2);
+    assert_eq!(test_enum_struct.into_iter().sum::<u32>(), 3);
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        items: vec![],
+        name: "name".to_string(),
+    };
+    assert_eq!(test_enum_tuple.len(), 0);
+    assert!(test_enum_tuple.starts_with("na"));
+}
+
+#[test]
+fn test_skipped_variants() {
+    use std::cell::Cell;
+
+    struct StructVariant {
+        key: String,
+        seq: u64,
+        hits: Cell<u32>,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(own key: String)]
+    #[common_field(key as key_str: str)]
+    #[common_field(clone key as key_clone: String)]
+    #[common_field(copy seq: u64)]
+    #[common_field(cell hits: u32)]
+    #[common_field(try_own key as try_key: String)]
+    #[common_field(copy seq as seq_or_zero: u64, default = 0)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        #[common_field_skip(key)]
+        TupleVariant {
+            seq: u64,
+            hits: Cell<u32>,
+        },
+        #[common_field_skip(key, seq, hits)]
+        Empty,
+    }
+
+    let mut test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "a".into(),
+        seq: 1,
+        hits: Cell::new(2),
+    });
+    test_enum_struct.key_mut().unwrap().push('b');
+    assert_eq!(test_enum_struct.key_str(), Some("ab"));
+    assert_eq!(test_enum_struct.key_clone(), Some("ab".to_string()));
+    assert_eq!(test_enum_struct.seq(), Some(1));
+    assert_eq!(test_enum_struct.hits(), Some(2));
+    assert_eq!(test_enum_struct.into_key(), Some("ab".to_string()));
+
+    let test_enum_tuple = TestEnum::TupleVariant {
+        seq: 3,
+        hits: Cell::new(4),
+    };
+    assert_eq!(test_enum_tuple.key(), None);
+    assert_eq!(test_enum_tuple.seq(), Some(3));
+    assert_eq!(test_enum_tuple.hits(), Some(4));
+    assert!(test_enum_tuple.try_key().is_err());
+
+    assert_eq!(TestEnum::Empty.seq_or_zero(), 0);
+    assert_eq!(TestEnum::Empty.into_key(), None);
+}
+
+#[test]
+fn test_get_or_clone_accessor() {
+    struct StructVariant {
+        key: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(get_or_clone key: String)]
+    #[common_field(get_or_clone key as (k, k_cloned): String)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant { key: "a".into() });
+    let owned = test_enum_struct.key_owned();
+    drop(test_enum_struct);
+    assert_eq!(owned, "a");
+
+    let test_enum_tuple = TestEnum::TupleVariant { key: "b".into() };
+    assert_eq!(test_enum_tuple.key(), "b");
+    assert_eq!(test_enum_tuple.k(), "b");
+    assert_eq!(test_enum_tuple.k_cloned(), "b");
+}
+
+#[test]
+fn test_display() {
+    struct StructVariant {
+        key: String,
+        seq: u64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: str)]
+    #[common_field(seq: u64)]
+    #[common_fields_config(display = "{variant} {{{key}}} #{seq:03} {key:?}")]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { key: String, seq: u64 },
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(seq: u64)]
+    #[common_fields_config(display = "<{variant}>")]
+    enum OnlyVariant {
+        Data {
+            seq: u64,
+        },
+        #[common_field_skip(seq)]
+        Empty,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(seq: u64)]
+    #[common_fields_config(display = "#{seq}")]
+    enum NoVariant {
+        First { seq: u64 },
+        Second { seq: u64 },
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "a".into(),
+        seq: 1,
+    });
+    assert_eq!(test_enum_struct.to_string(), "StructVariant {a} #001 \"a\"");
+    let test_enum_tuple = TestEnum::TupleVariant {
+        key: "b".into(),
+        seq: 20,
+    };
+    assert_eq!(format!("{test_enum_tuple}"), "TupleVariant {b} #020 \"b\"");
+    assert_eq!(OnlyVariant::Empty.to_string(), "<Empty>");
+    assert_eq!(OnlyVariant::Data { seq: 1 }.seq(), Some(&1));
+    assert_eq!(NoVariant::First { seq: 1 }.to_string(), "#1");
+    assert_eq!(NoVariant::Second { seq: 2 }.to_string(), "#2");
+}
+
+#[test]
+fn test_renamed_variant_fields() {
+    struct Order {
+        order_id: u32,
+        items: Vec<u32>,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(own id: u32)]
+    #[common_field(map id: u32)]
+    #[common_field(copy id as id_or_zero: u32, default = 0)]
+    #[common_fields_group(mut id_and_items(id: u32, items: Vec<u32>))]
+    #[common_delegate(len(&self) -> usize via items)]
+    #[common_fields_config(common, columns, display = "{variant}#{id}")]
+    enum TestEnum {
+        #[common_field_rename(id = user_id, items = user_items)]
+        User {
+            user_id: u32,
+            user_items: Vec<u32>,
+        },
+        #[common_field_rename(id = order_id)]
+        Order(Order),
+        Other {
+            id: u32,
+            items: Vec<u32>,
+        },
+    }
+
+    let mut user = TestEnum::User {
+        user_id: 1,
+        user_items: vec![],
+    };
+    *user.id_mut() += 10;
+    user.id_and_items_mut().1.push(5);
+    assert_eq!(user.common().id, &11);
+    assert_eq!(user.len(), 1);
+    assert_eq!(user.to_string(), "User#11");
+    let user = user.map_id(|id| id * 2);
+    assert_eq!(user.id_or_zero(), 22);
+
+    let order = TestEnum::Order(Order {
+        order_id: 2,
+        items: vec![1, 2],
+    });
+    assert_eq!(order.id_and_items(), (&2, &vec![1, 2]));
+    let other = TestEnum::Other {
+        id: 3,
+        items: vec![],
+    };
+    let columns = TestEnum::columns([user, order, other]);
+    assert_eq!(columns.ids, vec![22, 2, 3]);
+}
+
+#[test]
+fn test_debug() {
+    struct Secret;
+
+    struct StructVariant {
+        key: String,
+        token: Secret,
+        _other: u8,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: str)]
+    #[common_field(seq: u64)]
+    #[common_field(token: Secret, redact)]
+    #[common_fields_config(debug)]
+    enum TestEnum {
+        #[common_field_skip(seq)]
+        StructVariant(StructVariant),
+        TupleVariant {
+            key: String,
+            seq: u64,
+            token: Secret,
+        },
+        #[common_field_skip(key, seq, token)]
+        Empty,
+    }
+
+    let test_enum_struct = TestEnum::StructVariant(StructVariant {
+        key: "a".into(),
+        token: Secret,
+        _other: 0,
+    });
+    assert_eq!(
+        format!("{test_enum_struct:?}"),
+        r#"StructVariant { key: "a", token: <redacted>, .. }"#
+    );
+    let test_enum_tuple = TestEnum::TupleVariant {
+        key: "b".into(),
+        seq: 1,
+        token: Secret,
+    };
+    assert_eq!(
+        format!("{test_enum_tuple:?}"),
+        r#"TupleVariant { key: "b", seq: 1, token: <redacted>, .. }"#
+    );
+    assert_eq!(format!("{:?}", TestEnum::Empty), "Empty");
+    assert!(test_enum_tuple.token().is_some());
+    assert_eq!(test_enum_struct.key(), Some("a"));
+}
+
+#[test]
+fn test_tuple_element_index() {
+    #[derive(Clone)]
+    struct Meta {
+        key: String,
+        seq: u64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(own key: String)]
+    #[common_field(map key: String)]
+    #[common_fields_group(key_and_seq(key: String, seq: u64))]
+    enum TestEnum {
+        First(Meta, u32),
+        #[common_field_at(1)]
+        Second(u32, Meta),
+        #[common_field_at(2)]
+        Third(u8, u16, Meta, u32),
+        Single(Meta),
+    }
+
+    let meta = Meta {
+        key: "a".into(),
+        seq: 1,
+    };
+    let mut first = TestEnum::First(meta.clone(), 2);
+    first.key_mut().push('b');
+    assert_eq!(first.key_and_seq(), (&"ab".to_string(), &1));
+
+    let second = TestEnum::Second(3, meta.clone()).map_key(|key| key + "c");
+    assert_eq!(second.key(), "ac");
+    assert!(matches!(second, TestEnum::Second(3, _)));
+
+    let third = TestEnum::Third(4, 5, meta.clone(), 6).map_key(|key| key + "d");
+    assert!(matches!(third, TestEnum::Third(4, 5, _, 6)));
+    assert_eq!(third.into_key(), "ad");
+    assert_eq!(TestEnum::Single(meta).key_and_seq().1, &1);
+}
+
+#[cfg(feature = "alloc")]
+mod own_str {
+    use enum_common_fields::EnumCommonFields;
+
+    /// Fixed-capacity string, like `heapless::String<N>`
+    struct FixedString<const N: usize> {
+        bytes: [u8; N],
+        len: usize,
+    }
+
+    impl<const N: usize> FixedString<N> {
+        fn new(value: &str) -> Self {
+            let mut bytes = [0; N];
+            bytes[..value.len()].copy_from_slice(value.as_bytes());
+            Self {
+                bytes,
+                len: value.len(),
+            }
+        }
+
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+        }
+    }
+
+    impl<const N: usize> core::ops::Deref for FixedString<N> {
+        type Target = str;
+
+        fn deref(&self) -> &str {
+            self.as_str()
+        }
+    }
+
+    struct StructVariant {
+        name: FixedString<8>,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(name: str)]
+    #[common_field(own_str name: str)]
+    #[common_fields_config(no_std)]
+    enum TestEnum {
+        StructVariant(StructVariant),
+        TupleVariant { name: FixedString<8> },
+    }
+
+    #[test]
+    fn test_own_str_accessor() {
+        let test_enum_struct = TestEnum::StructVariant(StructVariant {
+            name: FixedString::new("a"),
+        });
+        assert_eq!(test_enum_struct.name(), "a");
+        let name: alloc::string::String = test_enum_struct.into_name();
+        assert_eq!(name, "a");
+
+        let test_enum_tuple = TestEnum::TupleVariant {
+            name: FixedString::new("bc"),
+        };
+        assert_eq!(test_enum_tuple.into_name(), "bc");
+    }
+}
+
+#[test]
+fn test_overridden_field_access() {
+    struct Meta {
+        key: String,
+        items: Vec<u32>,
+    }
+
+    struct Legacy {
+        meta: Meta,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(own key: String)]
+    #[common_field(key as key_str: str)]
+    #[common_fields_group(mut key_and_items(key: String, items: Vec<u32>))]
+    #[common_delegate(len(&self) -> usize via items)]
+    #[common_fields_config(common, into_common, display = "{variant}:{key}")]
+    enum TestEnum {
+        Current {
+            key: String,
+            items: Vec<u32>,
+        },
+        #[common_field_via(key = v.meta.key, items = v.meta.items)]
+        Legacy(Legacy),
+        #[common_field_via(key = meta.key)]
+        #[common_field_rename(items = list)]
+        Wrapped {
+            meta: Meta,
+            list: Vec<u32>,
+        },
+    }
+
+    let mut legacy = TestEnum::Legacy(Legacy {
+        meta: Meta {
+            key: "a".into(),
+            items: vec![1],
+        },
+    });
+    legacy.key_mut().push('b');
+    legacy.key_and_items_mut().1.push(2);
+    assert_eq!(legacy.key_str(), "ab");
+    assert_eq!(legacy.len(), 2);
+    assert_eq!(legacy.to_string(), "Legacy:ab");
+    assert_eq!(legacy.into_key(), "ab");
+
+    let wrapped = TestEnum::Wrapped {
+        meta: Meta {
+            key: "c".into(),
+            items: vec![],
+        },
+        list: vec![3],
+    };
+    assert_eq!(wrapped.common().key, "c");
+    assert_eq!(wrapped.key_and_items(), (&"c".to_string(), &vec![3]));
+    assert_eq!(wrapped.into_common().key, "c");
+
+    let current = TestEnum::Current {
+        key: "d".into(),
+        items: vec![],
+    };
+    assert_eq!(current.key(), "d");
+}
+
+#[test]
+fn test_variant_defaults() {
+    #[derive(EnumCommonFields)]
+    #[common_field(copy priority: u8, default = 0)]
+    #[common_field(clone name: String)]
+    #[common_field(priority as priority_ref: u8)]
+    enum TestEnum {
+        Data {
+            priority: u8,
+            name: String,
+        },
+        #[common_field_default(priority = 255, name = "error".to_string())]
+        #[common_field_skip(priority, name)]
+        Error(String),
+        #[common_field_default(name = String::new())]
+        Empty,
+    }
+
+    let data = TestEnum::Data {
+        priority: 1,
+        name: "data".into(),
+    };
+    assert_eq!(data.priority(), 1);
+    assert_eq!(data.name(), "data");
+    assert_eq!(data.priority_ref(), Some(&1));
+
+    let error = TestEnum::Error("message".into());
+    assert_eq!(error.priority(), 255);
+    assert_eq!(error.name(), "error");
+    assert_eq!(error.priority_ref(), None);
+    assert!(matches!(error, TestEnum::Error(message) if message == "message"));
+
+    assert_eq!(TestEnum::Empty.priority(), 0);
+    assert_eq!(TestEnum::Empty.name(), "");
+}
+
+#[test]
+fn test_delegated_variants() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    struct Meta {
+        key: String,
+        hits: AtomicU32,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(own key: String)]
+    #[common_field(replace key: String)]
+    #[common_field(update key: String)]
+    #[common_field(clone key as key_clone: String)]
+    #[common_field(atomic hits: u32)]
+    enum Inner {
+        One(Meta),
+        Two { key: String, hits: AtomicU32 },
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(own key: String)]
+    #[common_field(replace key: String)]
+    #[common_field(update key: String)]
+    #[common_field(clone key as key_clone: String)]
+    #[common_field(key as key_str: str)]
+    #[common_field(atomic hits: u32)]
+    enum Outer {
+        Own(Meta),
+        #[common_field(delegate)]
+        Nested(Inner),
+        #[common_field(delegate)]
+        #[common_field_at(1)]
+        Tagged(u8, Inner),
+    }
+
+    // key_str() of Outer has no counterpart in Inner, so it can't be called for delegated variants
+    impl Inner {
+        fn key_str(&self) -> &str {
+            self.key()
+        }
+    }
+
+    let mut nested = Outer::Nested(Inner::One(Meta {
+        key: "a".into(),
+        hits: AtomicU32::new(0),
+    }));
+    nested.key_mut().push('b');
+    assert_eq!(nested.replace_key("c".into()), "ab");
+    nested.update_key(|key| key.push('d'));
+    assert_eq!(nested.key_clone(), "cd");
+    assert_eq!(nested.key_str(), "cd");
+    nested.set_hits(3, Ordering::Relaxed);
+    assert_eq!(nested.hits(Ordering::Relaxed), 3);
+    assert_eq!(nested.into_key(), "cd");
+
+    let tagged = Outer::Tagged(
+        1,
+        Inner::Two {
+            key: "e".into(),
+            hits: AtomicU32::new(0),
+        },
+    );
+    assert_eq!(tagged.key(), "e");
+    assert!(matches!(tagged, Outer::Tagged(1, _)));
+    let own = Outer::Own(Meta {
+        key: "f".into(),
+        hits: AtomicU32::new(0),
+    });
+    assert_eq!(own.into_key(), "f");
+}
+
+#[test]
+fn test_common_tuple() {
+    #[derive(Debug, PartialEq)]
+    struct Uuid(u128);
+
+    #[derive(EnumCommonFields)]
+    #[common_tuple(0 as id: Uuid)]
+    #[common_tuple(1 as label: &'static str)]
+    enum Id {
+        User(Uuid, &'static str),
+        Org(Uuid, &'static str, u32),
+    }
+
+    let mut user = Id::User(Uuid(1), "user");
+    user.id_mut().0 += 1;
+    assert_eq!(user.id(), &Uuid(2));
+    assert_eq!(*user.label(), "user");
+    let org = Id::Org(Uuid(3), "org", 10);
+    assert!(matches!(org, Id::Org(_, _, 10)));
+    assert_eq!(org.into_id(), Uuid(3));
+}
+
+#[test]
+fn test_metadata() {
+    #[derive(EnumCommonFields)]
+    #[common_field(own key: String)]
+    #[common_field(seq: Option<u64>, collect)]
+    #[common_fields_group(key_and_seq(key: String, seq: Option<u64>))]
+    #[common_fields_config(metadata)]
+    enum TestEnum {
+        One { key: String, seq: Option<u64> },
+        Two { key: String, seq: Option<u64> },
+    }
+
+    let items = [
+        TestEnum::One {
+            key: "a".into(),
+            seq: Some(1),
+        },
+        TestEnum::Two {
+            key: "b".into(),
+            seq: None,
+        },
+    ];
+    assert_eq!(TestEnum::collect_seqs(&items), vec![&Some(1), &None]);
+    assert_eq!(items[1].key_and_seq(), (&"b".to_string(), &None));
+    assert_eq!(
+        TestEnum::COMMON_FIELDS_METADATA,
+        concat!(
+            r#"{"enum":"TestEnum","variants":["One","Two"],"#,
+            r#""fields":[{"name":"key","type":"String"},{"name":"seq","type":"Option<u64>"}],"#,
+            r#""methods":["collect_seqs","into_key","key","key_and_seq","key_mut","seq"]}"#
+        )
+    );
+}
+
+#[test]
+fn test_via_method() {
+    mod external {
+        pub struct Request {
+            key: String,
+            tags: Vec<String>,
+        }
+
+        impl Request {
+            pub fn new(key: &str) -> Self {
+                Self {
+                    key: key.to_string(),
+                    tags: vec!["a".to_string(), "b".to_string()],
+                }
+            }
+
+            pub fn key(&self) -> &String {
+                &self.key
+            }
+
+            pub fn key_mut(&mut self) -> &mut String {
+                &mut self.key
+            }
+
+            pub fn tag(&self, index: usize) -> &String {
+                &self.tags[index]
+            }
+        }
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key via key(): str)]
+    #[common_field(mut_only key via key_mut(): String)]
+    #[common_field(replace key via key_mut(): String)]
+    #[common_field(clone key via key() as key_clone: String, collect)]
+    #[common_field(tag via tag(1): String)]
+    enum TestEnum {
+        External(external::Request),
+        Local { key: String, tag: String },
+    }
+
+    let mut external = TestEnum::External(external::Request::new("key"));
+    external.key_mut().push('!');
+    assert_eq!(external.key(), "key!");
+    assert_eq!(external.replace_key("new".to_string()), "key!");
+    assert_eq!(external.key_clone(), "new");
+    assert_eq!(external.tag(), "b");
+    let local = TestEnum::Local {
+        key: "local".to_string(),
+        tag: "c".to_string(),
+    };
+    assert_eq!(local.tag(), "c");
+    assert_eq!(
+        TestEnum::collect_keys(&[external, local]),
+        vec!["new", "local"]
+    );
+}
+
+#[test]
+fn test_cfg_attr() {
+    #[cfg_attr(all(), derive(EnumCommonFields), common_field(mut key: String))]
+    #[cfg_attr(all(), cfg_attr(all(), common_field(copy seq: u64)))]
+    #[cfg_attr(any(), common_field(own key: String))]
+    #[cfg_attr(feature = "audit", common_fields_config(display = "{key}"))]
+    enum TestEnum {
+        One {
+            key: String,
+            seq: u64,
+        },
+        #[cfg_attr(all(), common_field_rename(key = name))]
+        Two {
+            name: String,
+            seq: u64,
+        },
+    }
+
+    let mut two = TestEnum::Two {
+        name: "name".into(),
+        seq: 2,
+    };
+    two.key_mut().push('!');
+    assert_eq!(two.key(), "name!");
+    assert_eq!(two.seq(), 2);
+    #[cfg(feature = "audit")]
+    assert_eq!(two.to_string(), "name!");
+    let one = TestEnum::One {
+        key: "key".into(),
+        seq: 1,
+    };
+    assert_eq!(one.seq(), 1);
+}
+
+#[test]
+fn test_empty_enum() {
+    #[derive(EnumCommonFields)]
+    #[common_field(own key: String)]
+    #[common_field(replace key: String)]
+    #[common_field(update key: String)]
+    #[common_field(copy seq: u64, collect)]
+    #[common_field(map seq: u64)]
+    #[common_fields_group(mut key_and_seq(key: String, seq: u64))]
+    #[common_delegate(len(&self) -> usize via key)]
+    #[common_tuple(0 as id: u64)]
+    #[common_fields_config(common, common_mut, into_common, columns)]
+    #[common_fields_config(display = "{variant}: {key}", debug)]
+    #[common_fields_hash(key)]
+    #[common_fields_eq(key)]
+    #[common_fields_ord(key)]
+    enum Empty {}
+
+    fn accepts_accessors(value: &Empty) -> &str {
+        value.key()
+    }
+
+    assert!(Empty::collect_seqs(&[]).is_empty());
+    assert!(Empty::columns(Vec::new()).keys.is_empty());
+    let _ = accepts_accessors;
+}
+
+#[test]
+fn test_common_fields_trait() {
+    #[derive(EnumCommonFields)]
+    #[common_field(own key: String)]
+    #[common_field(replace key: String)]
+    #[common_field(copy seq: u64, collect)]
+    #[common_field(map seq: u64)]
+    #[common_fields_group(key_and_seq(key: String, seq: u64))]
+    #[common_fields_trait(Envelope)]
+    enum Request {
+        Create { key: String, seq: u64 },
+        Delete { key: String, seq: u64 },
+    }
+
+    struct Plain {
+        key: String,
+        seq: u64,
+    }
+
+    fn bump<T: Envelope>(value: T) -> T {
+        let seq = value.seq();
+        value.map_seq(|_| seq + 1)
+    }
+
+    fn describe(value: &mut impl Envelope) -> String {
+        let old = value.replace_key("new".to_string());
+        let (key, seq) = value.key_and_seq();
+        format!("{old} -> {key} {seq}")
+    }
+
+    let request = bump(Request::Create {
+        key: "a".to_string(),
+        seq: 1,
+    });
+    assert_eq!(Envelope::seq(&request), 2);
+    let mut request = request;
+    assert_eq!(describe(&mut request), "a -> new 2");
+    assert_eq!(Envelope::into_key(request), "new");
+    let delete = Request::Delete {
+        key: "b".to_string(),
+        seq: 3,
+    };
+    assert_eq!(Request::collect_seqs(&[delete]), vec![&3]);
+
+    // collect_seqs() doesn't take self, so Plain doesn't need to implement it
+    impl Envelope for Plain {
+        fn key(&self) -> &String {
+            &self.key
+        }
+        fn key_mut(&mut self) -> &mut String {
+            &mut self.key
+        }
+        fn into_key(self) -> String {
+            self.key
+        }
+        fn replace_key(&mut self, new: String) -> String {
+            std::mem::replace(&mut self.key, new)
+        }
+        fn seq(&self) -> u64 {
+            self.seq
+        }
+        fn map_seq(self, f: impl FnOnce(u64) -> u64) -> Self {
+            Self {
+                seq: f(self.seq),
+                ..self
+            }
+        }
+        fn key_and_seq(&self) -> (&String, &u64) {
+            (&self.key, &self.seq)
+        }
+    }
+
+    let mut plain = bump(Plain {
+        key: "c".to_string(),
+        seq: 5,
+    });
+    assert_eq!(describe(&mut plain), "c -> new 6");
+}
+
+#[test]
+fn test_object_safe_trait() {
+    #[derive(EnumCommonFields)]
+    #[common_field(mut + own key: String)]
+    #[common_field(copy seq: u64)]
+    #[common_fields_trait(Envelope, object_safe)]
+    enum Request {
+        Create { key: String, seq: u64 },
+        Delete { key: String, seq: u64 },
+    }
+
+    let mut requests: Vec<Box<dyn Envelope>> = vec![
+        Box::new(Request::Create {
+            key: "a".to_string(),
+            seq: 1,
+        }),
+        Box::new(Request::Delete {
+            key: "b".to_string(),
+            seq: 2,
+        }),
+    ];
+    requests[1].key_mut().push('!');
+    let keys: Vec<_> = requests
+        .iter()
+        .map(|request| format!("{}{}", request.key(), request.seq()))
+        .collect();
+    assert_eq!(keys, ["a1", "b!2"]);
+}
+
+#[test]
+fn test_trait_implementation() {
+    trait Identifiable {
+        fn id(&self) -> &u64;
+        fn id_mut(&mut self) -> &mut u64;
+
+        fn next_id(&mut self) -> u64 {
+            *self.id_mut() += 1;
+            *self.id()
+        }
+    }
+
+    trait Named {
+        fn name(&self) -> &str;
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(id: u64, impl = Identifiable::id)]
+    #[common_field(mut_only id: u64, impl = Identifiable::id_mut)]
+    #[common_field(key: str, impl = Named::name)]
+    #[common_field(own_only key: String)]
+    #[common_fields_trait(HasKey)]
+    enum TestEnum {
+        One { id: u64, key: String },
+        Two { id: u64, key: String },
+    }
+
+    // Only into_key() is inherent, so it's the only method of the generated trait
+    fn into_name(value: impl HasKey + Named) -> String {
+        assert!(!value.name().is_empty());
+        value.into_key()
+    }
+
+    let mut one = TestEnum::One {
+        id: 1,
+        key: "one".to_string(),
+    };
+    assert_eq!(one.next_id(), 2);
+    assert_eq!(Identifiable::id(&one), &2);
+    assert_eq!(into_name(one), "one");
+    let two = TestEnum::Two {
+        id: 3,
+        key: "two".to_string(),
+    };
+    assert_eq!(two.name(), "two");
+}
+
+#[test]
+fn test_as_mut_impl() {
+    struct Payload {
+        name: String,
+        count: u32,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(name: str, as_mut)]
+    #[common_field(mut_only count: u32, as_mut)]
+    enum TestEnum {
+        One { name: String, count: u32 },
+        Two(Payload),
+    }
+
+    // TestEnum implements AsMut for two types, so the delegated call has to name the one it needs
+    #[derive(EnumCommonFields)]
+    #[common_field(mut_only count: u32, as_mut)]
+    enum Outer {
+        #[common_field(delegate)]
+        Inner(TestEnum),
+        Own {
+            count: u32,
+        },
+    }
+
+    fn shout(mut value: impl AsMut<str>) {
+        value.as_mut().make_ascii_uppercase();
+    }
+
+    let mut one = TestEnum::One {
+        name: "one".to_string(),
+        count: 0,
+    };
+    shout(&mut one);
+    *AsMut::<u32>::as_mut(&mut one) += 2;
+    assert_eq!(one.name(), "ONE");
+    assert_eq!(*one.count_mut(), 2);
+    let mut two = TestEnum::Two(Payload {
+        name: "two".to_string(),
+        count: 5,
+    });
+    shout(&mut two);
+    assert_eq!(two.name(), "TWO");
+    assert_eq!(AsMut::<u32>::as_mut(&mut two), &5);
+    let mut outer = Outer::Inner(two);
+    *AsMut::<u32>::as_mut(&mut outer) += 1;
+    assert_eq!(*outer.count_mut(), 6);
+    let mut own = Outer::Own { count: 7 };
+    assert_eq!(AsMut::<u32>::as_mut(&mut own), &7);
+}
+
+#[test]
+fn test_borrow_impl() {
+    use std::borrow::Borrow;
+    use std::collections::BTreeSet;
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: str, borrow)]
+    #[common_field(copy seq: u64)]
+    #[common_fields_eq(key)]
+    #[common_fields_ord(key)]
+    enum TestEnum {
+        One { key: String, seq: u64 },
+        Two { key: String, seq: u64 },
+    }
+
+    // Every type borrows as itself too, so the delegated call has to name the borrowed type
+    #[derive(EnumCommonFields)]
+    #[common_field(key: str, borrow)]
+    enum Outer {
+        #[common_field(delegate)]
+        Inner(TestEnum),
+        Own {
+            key: String,
+        },
+    }
+
+    let set = BTreeSet::from([
+        TestEnum::One {
+            key: "b".to_string(),
+            seq: 1,
+        },
+        TestEnum::Two {
+            key: "a".to_string(),
+            seq: 2,
+        },
+    ]);
+    assert_eq!(set.get("a").map(TestEnum::seq), Some(2));
+    assert!(!set.contains("c"));
+    let borrowed: &str = set.first().unwrap().borrow();
+    assert_eq!(borrowed, "a");
+    let outer = Outer::Inner(set.into_iter().next().unwrap());
+    assert_eq!(Borrow::<str>::borrow(&outer), "a");
+    let own = Outer::Own {
+        key: "own".to_string(),
+    };
+    assert_eq!(Borrow::<str>::borrow(&own), "own");
+}
+
+#[test]
+fn test_borrow_mut_impl() {
+    use std::borrow::{Borrow, BorrowMut};
+
+    #[derive(EnumCommonFields)]
+    #[common_field(mut key: String, borrow_mut)]
+    #[common_field(seq: u64, borrow)]
+    enum TestEnum {
+        One { key: String, seq: u64 },
+        Two { key: String, seq: u64 },
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(mut key: String, borrow_mut, as_mut)]
+    #[common_field(seq: u64, borrow)]
+    enum Outer {
+        #[common_field(delegate)]
+        Inner(TestEnum),
+        Own {
+            key: String,
+            seq: u64,
+        },
+    }
+
+    impl AsMut<String> for TestEnum {
+        fn as_mut(&mut self) -> &mut String {
+            self.key_mut()
+        }
+    }
+
+    fn append<T: BorrowMut<String>>(target: &mut T, suffix: &str) {
+        target.borrow_mut().push_str(suffix);
+    }
+
+    let mut two = TestEnum::Two {
+        key: "two".to_string(),
+        seq: 2,
+    };
+    append(&mut two, "!");
+    assert_eq!(Borrow::<String>::borrow(&two), "two!");
+    assert_eq!(Borrow::<u64>::borrow(&two), &2);
+    let one = TestEnum::One {
+        key: "one".to_string(),
+        seq: 1,
+    };
+    assert_eq!(one.key(), "one");
+    assert_eq!(one.seq(), &1);
+
+    let mut outer = Outer::Inner(one);
+    append(&mut outer, "?");
+    AsMut::<String>::as_mut(&mut outer).push('!');
+    assert_eq!(Borrow::<String>::borrow(&outer), "one?!");
+    assert_eq!(Borrow::<u64>::borrow(&outer), &1);
+    let own = Outer::Own {
+        key: "own".to_string(),
+        seq: 3,
+    };
+    assert_eq!(own.key(), "own");
+    assert_eq!(own.seq(), &3);
+}
+
+#[test]
+fn test_from_impl() {
+    #[derive(EnumCommonFields)]
+    #[common_field(own key: String, from)]
+    #[common_field(own_only seq: u64, default = 0, from)]
+    enum TestEnum {
+        One {
+            key: String,
+            seq: u64,
+        },
+        #[common_field_skip(seq)]
+        Two {
+            key: String,
+        },
+    }
+
+    fn keys<T: Into<String>>(items: Vec<T>) -> Vec<String> {
+        items.into_iter().map(Into::into).collect()
+    }
+
+    let items = vec![
+        TestEnum::One {
+            key: "one".to_string(),
+            seq: 1,
+        },
+        TestEnum::Two {
+            key: "two".to_string(),
+        },
+    ];
+    assert_eq!(keys(items), ["one", "two"]);
+    let seq: u64 = TestEnum::Two {
+        key: "two".to_string(),
+    }
+    .into();
+    assert_eq!(seq, 0);
+    assert_eq!(
+        u64::from(TestEnum::One {
+            key: "one".to_string(),
+            seq: 1,
+        }),
+        1
+    );
+}
+
+#[test]
+fn test_deref_impl() {
+    struct Payload {
+        items: Vec<u32>,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(deref + ref items: [u32])]
+    #[common_fields(frozen = "items")]
+    enum TestEnum {
+        Owned { items: Vec<u32> },
+        Wrapped(Payload),
+    }
+
+    fn total(items: &[u32]) -> u32 {
+        items.iter().sum()
+    }
+
+    let owned = TestEnum::Owned {
+        items: vec![1, 2, 3],
+    };
+    assert_eq!(owned.len(), 3);
+    assert_eq!(total(&owned), 6);
+    let wrapped = TestEnum::Wrapped(Payload { items: vec![4] });
+    assert_eq!(wrapped.first(), Some(&4));
+    assert_eq!(wrapped.items(), &[4]);
+}
+
+#[test]
+fn test_deref_mut_impl() {
+    #[derive(EnumCommonFields)]
+    #[common_field(deref name: str)]
+    #[common_field(mut name: String)]
+    enum TestEnum {
+        One { name: String },
+        Two { name: String, id: u32 },
+    }
+
+    fn shout(text: &mut str) {
+        text.make_ascii_uppercase();
+    }
+
+    let mut two = TestEnum::Two {
+        name: "two".to_string(),
+        id: 2,
+    };
+    shout(&mut two);
+    assert_eq!(&*two, "TWO");
+    two.name_mut().push('!');
+    assert_eq!(two.len(), 4);
+    assert!(matches!(two, TestEnum::Two { id: 2, .. }));
+    let one = TestEnum::One {
+        name: "one".to_string(),
+    };
+    assert_eq!(one.name(), "one");
+}
+
+#[test]
+fn test_display_field() {
+    #[derive(EnumCommonFields)]
+    #[common_field(id: String, display)]
+    enum TestEnum {
+        One { id: String },
+        Two(TwoPayload),
+    }
+
+    struct TwoPayload {
+        id: String,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(id: String, display)]
+    enum Outer {
+        #[common_field(delegate)]
+        Inner(TestEnum),
+        Own {
+            id: String,
+        },
+    }
+
+    let one = TestEnum::One {
+        id: "one".to_string(),
+    };
+    assert_eq!(one.to_string(), "one");
+    let two = TestEnum::Two(TwoPayload {
+        id: "two".to_string(),
+    });
+    assert_eq!(format!("[{two:>5}]"), "[  two]");
+    assert_eq!(Outer::Inner(two).to_string(), "two");
+    let own = Outer::Own {
+        id: "own".to_string(),
+    };
+    assert_eq!(own.id(), "own");
+    assert_eq!(own.to_string(), "own");
+}
+
+#[test]
+fn test_common_fields_hash() {
+    use std::collections::HashSet;
+    use std::hash::{BuildHasher, RandomState};
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String)]
+    #[common_field(seq: u64)]
+    #[common_fields_hash(key, seq)]
+    enum TestEnum {
+        One { key: String, seq: u64 },
+        Two(TwoPayload),
+    }
+
+    struct TwoPayload {
+        key: String,
+        seq: u64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String, borrow)]
+    #[common_fields_hash(key)]
+    #[common_fields_eq(key)]
+    enum Keyed {
+        One { key: String },
+        Two { key: String, value: u32 },
+    }
+
+    let state = RandomState::new();
+    let one = TestEnum::One {
+        key: "a".to_string(),
+        seq: 1,
+    };
+    let two = TestEnum::Two(TwoPayload {
+        key: "a".to_string(),
+        seq: 1,
+    });
+    assert_eq!(state.hash_one(&one), state.hash_one(&two));
+    assert_eq!(
+        state.hash_one(&one),
+        state.hash_one(("a".to_string(), 1u64))
+    );
+    assert_eq!(one.key(), two.key());
+    assert_eq!(one.seq(), two.seq());
+
+    let set = HashSet::from([
+        Keyed::One {
+            key: "a".to_string(),
+        },
+        Keyed::Two {
+            key: "b".to_string(),
+            value: 2,
+        },
+    ]);
+    let (found, missing) = ("b".to_string(), "c".to_string());
+    assert!(matches!(set.get(&found), Some(Keyed::Two { value: 2, .. })));
+    assert!(!set.contains(&missing));
+}
+
+#[test]
+fn test_common_fields_eq() {
+    #[derive(EnumCommonFields)]
+    #[common_field(key: str)]
+    #[common_field(seq: u64)]
+    #[common_fields_eq(key, seq)]
+    enum TestEnum {
+        One {
+            key: String,
+            seq: u64,
+        },
+        Two(TwoPayload),
+        #[common_field_rename(key = name)]
+        Three {
+            name: String,
+            seq: u64,
+            extra: Vec<u8>,
+        },
+    }
+
+    struct TwoPayload {
+        key: String,
+        seq: u64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(seq: u64)]
+    #[common_fields_eq(variant)]
+    enum VariantOnly {
+        One { seq: u64 },
+        Two { seq: u64 },
+    }
+
+    let one = TestEnum::One {
+        key: "a".to_string(),
+        seq: 1,
+    };
+    let two = TestEnum::Two(TwoPayload {
+        key: "a".to_string(),
+        seq: 1,
+    });
+    let three = TestEnum::Three {
+        name: "a".to_string(),
+        seq: 2,
+        extra: vec![1],
+    };
+    assert!(one == two);
+    assert!(two != three);
+    assert_eq!(one.key(), three.key());
+    if let TestEnum::Three { extra, .. } = &three {
+        assert_eq!(extra, &[1]);
+    }
+    assert!(VariantOnly::One { seq: 1 } == VariantOnly::One { seq: 2 });
+    assert!(VariantOnly::One { seq: 1 } != VariantOnly::Two { seq: 1 });
+    assert_eq!(VariantOnly::Two { seq: 3 }.seq(), &3);
+}
+
+#[test]
+fn test_common_fields_ord() {
+    #[derive(EnumCommonFields)]
+    #[common_field(priority: u8)]
+    #[common_field(name: str)]
+    #[common_fields_eq(priority, name)]
+    #[common_fields_ord(priority, name)]
+    enum TestEnum {
+        One { priority: u8, name: String },
+        Two(TwoPayload),
+    }
+
+    struct TwoPayload {
+        priority: u8,
+        name: String,
+    }
+
+    let mut values = [
+        TestEnum::Two(TwoPayload {
+            priority: 2,
+            name: "b".to_string(),
+        }),
+        TestEnum::One {
+            priority: 2,
+            name: "a".to_string(),
+        },
+        TestEnum::One {
+            priority: 1,
+            name: "c".to_string(),
+        },
+    ];
+    values.sort();
+    let names: Vec<_> = values.iter().map(TestEnum::name).collect();
+    assert_eq!(names, ["c", "a", "b"]);
+    assert_eq!(values[0].priority(), &1);
+    assert!(values[1] < values[2]);
+}
+
+#[test]
+fn test_eq_with_field() {
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String, eq)]
+    #[common_field(seq: u64, eq)]
+    enum TestEnum {
+        One { key: String, seq: u64 },
+        Two(TwoPayload),
+    }
+
+    struct TwoPayload {
+        key: String,
+        seq: u64,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String, eq)]
+    enum Outer {
+        #[common_field(delegate)]
+        Inner(TestEnum),
+        Own {
+            key: String,
+        },
+    }
+
+    let one = TestEnum::One {
+        key: "one".to_string(),
+        seq: 1,
+    };
+    let key = "one".to_string();
+    assert!(one == key);
+    assert!(key == one);
+    assert!(one == 1);
+    assert!(2 != one);
+    assert_eq!(one.seq(), &1);
+    let two = TestEnum::Two(TwoPayload {
+        key: "two".to_string(),
+        seq: 2,
+    });
+    assert!(two == 2);
+    assert!(two != key);
+    assert!(Outer::Inner(two) == "two".to_string());
+    let own = Outer::Own {
+        key: "own".to_string(),
+    };
+    assert_eq!(own.key(), "own");
+    assert!(own != key);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_common_serde() {
+    #[derive(EnumCommonFields)]
+    #[common_field(key: str)]
+    #[common_field(sequence_number: u64)]
+    #[common_fields_config(common(serde), rename_map = "sequence_number => seq")]
+    enum TestEnum {
+        One { key: String, sequence_number: u64 },
+        Two(TwoPayload),
+    }
+
+    struct TwoPayload {
+        key: String,
+        sequence_number: u64,
+        payload: Vec<u8>,
+    }
+
+    let two = TestEnum::Two(TwoPayload {
+        key: "two".to_string(),
+        sequence_number: 2,
+        payload: vec![0; 16],
+    });
+    assert_eq!(
+        serde_json::to_string(&two.common()).unwrap(),
+        r#"{"key":"two","seq":2}"#
+    );
+    if let TestEnum::Two(payload) = &two {
+        assert_eq!(payload.payload.len(), 16);
+    }
+    let one = TestEnum::One {
+        key: "one".to_string(),
+        sequence_number: 1,
+    };
+    assert_eq!(one.common().seq, &1);
+}
+
+#[test]
+fn test_common_fields_trait_payloads() {
+    #[derive(EnumCommonFields)]
+    #[common_field(mut + own key: String)]
+    #[common_field(copy seq: u64)]
+    #[common_fields_trait(Envelope, payloads)]
+    enum TestEnum {
+        Create(CreatePayload),
+        Recreate(CreatePayload),
+        #[common_field_at(1)]
+        Delete(u8, DeletePayload),
+        Own {
+            key: String,
+            seq: u64,
+        },
+    }
+
+    struct CreatePayload {
+        key: String,
+        seq: u64,
+        body: Vec<u8>,
+    }
+
+    struct DeletePayload {
+        key: String,
+        seq: u64,
+    }
+
+    fn describe(value: &impl Envelope) -> String {
+        format!("{}#{}", value.key(), value.seq())
+    }
+
+    let mut create = CreatePayload {
+        key: "create".to_string(),
+        seq: 1,
+        body: vec![1, 2],
+    };
+    create.key_mut().push('d');
+    assert_eq!(describe(&create), "created#1");
+    assert_eq!(create.body.len(), 2);
+    let delete = DeletePayload {
+        key: "delete".to_string(),
+        seq: 2,
+    };
+    assert_eq!(describe(&delete), "delete#2");
+    assert_eq!(Envelope::into_key(delete), "delete");
+    let recreate = TestEnum::Recreate(create);
+    assert_eq!(describe(&recreate), "created#1");
+    assert_eq!(recreate.into_key(), "created");
+    let own = TestEnum::Own {
+        key: "own".to_string(),
+        seq: 3,
+    };
+    assert_eq!(describe(&own), "own#3");
+    let created = TestEnum::Create(CreatePayload {
+        key: "c".to_string(),
+        seq: 4,
+        body: Vec::new(),
+    });
+    assert_eq!(created.seq(), 4);
+    if let TestEnum::Delete(tag, _) = TestEnum::Delete(
+        7,
+        DeletePayload {
+            key: String::new(),
+            seq: 0,
+        },
+    ) {
+        assert_eq!(tag, 7);
+    }
+}
+
+mod foreign {
+    pub struct Deletion {
+        pub key: String,
+        pub version: u32,
+    }
+
+    pub enum Event {
+        Created { key: String, version: u32 },
+        Renamed { new_key: String, version: u32 },
+        Deleted(Deletion, bool),
+    }
+
+    pub enum Command {
+        Run { seq: u64 },
+        Stop { seq: u64 },
+    }
+}
+
+enum_common_fields::enum_common_fields! {
+    for foreign::Event {
+        mut key: str in Created { key }, Renamed { new_key }, Deleted(d => d.key);
+        version: u32 in Created { version }, Renamed { version }, Deleted(d => d.version),
+    }
+    pub(crate) for foreign::Command as CommandSeq {
+        seq: u64 in Run { seq }, Stop { seq }
+    }
+}
+
+#[test]
+fn test_foreign_enum() {
+    let mut deleted = foreign::Event::Deleted(
+        foreign::Deletion {
+            key: "deleted".to_string(),
+            version: 3,
+        },
+        true,
+    );
+    deleted.key_mut().make_ascii_uppercase();
+    assert_eq!(deleted.key(), "DELETED");
+    assert_eq!(deleted.version(), &3);
+    let renamed = foreign::Event::Renamed {
+        new_key: "renamed".to_string(),
+        version: 2,
+    };
+    assert_eq!(renamed.key(), "renamed");
+    let created = foreign::Event::Created {
+        key: "created".to_string(),
+        version: 1,
+    };
+    assert_eq!(EventCommonFields::version(&created), &1);
+    assert_eq!(foreign::Command::Stop { seq: 2 }.seq(), &2);
+    assert_eq!(CommandSeq::seq(&foreign::Command::Run { seq: 1 }), &1);
+    if let foreign::Event::Deleted(_, flag) = deleted {
+        assert!(flag);
+    }
+}
+
+#[test]
+fn test_remote_enum() {
+    mod remote {
+        pub struct Deletion {
+            pub key: String,
+            pub version: u32,
+        }
+
+        pub enum Event {
+            Created { key: String, version: u32 },
+            Renamed { new_key: String, version: u32 },
+            Deleted(Deletion),
+        }
+
+        pub enum Command {
+            Run { seq: u64 },
+            Stop { seq: u64 },
+        }
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(mut key: str)]
+    #[common_field(own_only key: String)]
+    #[common_field(copy version: u32)]
+    #[common_field(key as key_len: usize, get = |key: &String| key.len())]
+    #[common_fields_config(remote = "remote::Event", common)]
+    #[allow(dead_code)]
+    enum Event {
+        Created {
+            key: String,
+            version: u32,
+        },
+        #[common_field_rename(key = new_key)]
+        Renamed {
+            new_key: String,
+            version: u32,
+        },
+        Deleted(remote::Deletion),
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(copy seq: u64)]
+    #[common_fields_trait(HasSeq)]
+    #[common_fields_config(remote = "remote::Command")]
+    #[allow(dead_code)]
+    enum Command {
+        Run { seq: u64 },
+        Stop { seq: u64 },
+    }
+
+    let mut renamed = remote::Event::Renamed {
+        new_key: "renamed".to_string(),
+        version: 2,
+    };
+    renamed.key_mut().make_ascii_uppercase();
+    assert_eq!(renamed.key(), "RENAMED");
+    assert_eq!(renamed.version(), 2);
+    assert_eq!(renamed.common().version, &2);
+    let deleted = remote::Event::Deleted(remote::Deletion {
+        key: "deleted".to_string(),
+        version: 3,
+    });
+    assert_eq!(deleted.key_len(), 7);
+    assert_eq!(EventCommonFields::into_key(deleted), "deleted");
+    let created = remote::Event::Created {
+        key: "created".to_string(),
+        version: 1,
+    };
+    assert_eq!(created.version(), 1);
+    assert_eq!(remote::Command::Run { seq: 5 }.seq(), 5);
+    assert_eq!(HasSeq::seq(&remote::Command::Stop { seq: 6 }), 6);
+}
+
+enum_common_fields::common_fields_enum! {
+    #[derive(Debug, PartialEq)]
+    #[common_fields_config(common)]
+    enum HoistedJob {
+        common { id: u64, mut + own name: String, copy attempts: u8 }
+        Pending,
+        /// Job that is running
+        Running { pid: u32 },
+        Failed { error: String, },
+    }
+}
+
+#[test]
+fn test_common_fields_enum() {
+    let mut running = HoistedJob::Running {
+        id: 1,
+        name: "build".to_string(),
+        attempts: 2,
+        pid: 42,
+    };
+    running.name_mut().push_str("-all");
+    assert_eq!(running.name(), "build-all");
+    assert_eq!(running.attempts(), 2);
+    assert_eq!(running.common().id, &1);
+    assert!(matches!(running, HoistedJob::Running { pid: 42, .. }));
+    let pending = HoistedJob::Pending {
+        id: 2,
+        name: "test".to_string(),
+        attempts: 0,
+    };
+    assert_eq!(pending.id(), &2);
+    assert_eq!(pending.into_name(), "test");
+    let failed = HoistedJob::Failed {
+        id: 3,
+        name: String::new(),
+        attempts: 1,
+        error: "oops".to_string(),
+    };
+    assert!(format!("{failed:?}")
+        .starts_with("Failed { id: 3, name: \"\", attempts: 1, error: \"oops\""));
+    assert_ne!(failed, running);
+}
+
+#[test]
+fn test_flatten() {
+    struct CommonData {
+        id: u64,
+        created_at: u64,
+    }
+
+    struct Job {
+        common: CommonData,
+        pid: u32,
+    }
+
+    struct Legacy {
+        meta: Job,
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(flatten mut + own common: CommonData { id: u64, created_at: u64, })]
+    enum TestEnum {
+        Pending {
+            common: CommonData,
+        },
+        Running(Job),
+        #[common_field_rename(common = data)]
+        Renamed {
+            data: CommonData,
+        },
+        #[common_field_via(common = v.meta.common)]
+        Legacy(Legacy),
+    }
+
+    let mut running = TestEnum::Running(Job {
+        common: CommonData {
+            id: 1,
+            created_at: 100,
+        },
+        pid: 42,
+    });
+    *running.id_mut() += 1;
+    assert_eq!(running.id(), &2);
+    assert_eq!(running.common().created_at, 100);
+    assert!(matches!(&running, TestEnum::Running(Job { pid: 42, .. })));
+    assert_eq!(running.into_created_at(), 100);
+    let renamed = TestEnum::Renamed {
+        data: CommonData {
+            id: 3,
+            created_at: 300,
+        },
+    };
+    assert_eq!(renamed.created_at(), &300);
+    assert_eq!(renamed.into_common().id, 3);
+    let legacy = TestEnum::Legacy(Legacy {
+        meta: Job {
+            common: CommonData {
+                id: 4,
+                created_at: 400,
+            },
+            pid: 0,
+        },
+    });
+    assert_eq!(legacy.id(), &4);
+    assert_eq!(legacy.created_at(), &400);
+    let pending = TestEnum::Pending {
+        common: CommonData {
+            id: 5,
+            created_at: 500,
+        },
+    };
+    assert_eq!(pending.id(), &5);
+}
+
+#[test]
+fn test_constructors() {
+    #[derive(EnumCommonFields)]
+    #[common_field(id: u64)]
+    #[common_field(created_at: u64)]
+    #[common_fields_config(constructors)]
+    enum TestEnum {
+        Queued {
+            created_at: u64,
+            id: u64,
+        },
+        Running {
+            id: u64,
+            pid: u32,
+            created_at: u64,
+        },
+        #[common_field_via(created_at = &0)]
+        Unscheduled {
+            id: u64,
+            reason: String,
+        },
+    }
+
+    let running = TestEnum::new_running(1, 100, 42);
+    assert_eq!(running.id(), &1);
+    assert_eq!(running.created_at(), &100);
+    assert!(matches!(running, TestEnum::Running { pid: 42, .. }));
+    assert_eq!(TestEnum::new_queued(2, 200).created_at(), &200);
+    let unscheduled = TestEnum::new_unscheduled(3, "paused".to_string());
+    assert_eq!(unscheduled.id(), &3);
+    assert!(matches!(unscheduled, TestEnum::Unscheduled { reason, .. } if reason == "paused"));
+}
+
+#[test]
+fn test_common_owned() {
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String)]
+    #[common_field(seq: u64)]
+    #[common_fields_config(common_owned, into_common)]
+    enum TestEnum {
+        VariantOne { key: String, seq: u64 },
+        VariantTwo { seq: u64, key: String, flag: bool },
+    }
+
+    let test_enum = TestEnum::VariantTwo {
+        seq: 1,
+        key: "a".to_string(),
+        flag: true,
+    };
+    let TestEnumCommon { key, seq } = test_enum.common_owned();
+    assert_eq!((key.as_str(), seq), ("a", 1));
+    assert!(matches!(test_enum, TestEnum::VariantTwo { flag: true, .. }));
+    assert_eq!(test_enum.into_common().key, "a");
+    let test_enum = TestEnum::VariantOne {
+        key: "b".to_string(),
+        seq: 2,
+    };
+    assert_eq!(test_enum.common_owned().seq, 2);
+}
+
+#[test]
+fn test_split() {
+    #[derive(EnumCommonFields)]
+    #[common_field(id: u64)]
+    #[common_field(name: String)]
+    #[common_fields_config(split, common_owned)]
+    enum TestEnum {
+        Queued {
+            id: u64,
+            name: String,
+        },
+        #[common_field_rename(id = job_id)]
+        Running {
+            pid: u32,
+            job_id: u64,
+            name: String,
+            args: Vec<String>,
+        },
+    }
+
+    let running = TestEnum::Running {
+        pid: 42,
+        job_id: 1,
+        name: "build".to_string(),
+        args: vec!["--release".to_string()],
+    };
+    assert_eq!(running.common_owned().id, 1);
+    let (common, rest) = running.split();
+    assert_eq!((common.id, common.name.as_str()), (1, "build"));
+    let TestEnumRest::Running { pid, args } = rest else {
+        panic!("Expected running job")
+    };
+    assert_eq!((pid, args), (42, vec!["--release".to_string()]));
+    let queued = TestEnum::Queued {
+        id: 2,
+        name: "test".to_string(),
+    };
+    assert!(matches!(
+        queued.split(),
+        (TestEnumCommon { id: 2, .. }, TestEnumRest::Queued)
+    ));
+}
+
+#[test]
+fn test_field_visibility() {
+    mod jobs {
+        use enum_common_fields::EnumCommonFields;
+
+        #[derive(EnumCommonFields)]
+        #[common_field(id: u64)]
+        #[common_field(mut retries: u32, vis = pub(super))]
+        #[common_field(secret: String, vis =)]
+        pub enum Job {
+            Queued {
+                id: u64,
+                retries: u32,
+                secret: String,
+            },
+            Running {
+                id: u64,
+                retries: u32,
+                secret: String,
+            },
+        }
+
+        impl Job {
+            pub fn secret_len(&self) -> usize {
+                self.secret().len()
+            }
+        }
+    }
+
+    let mut job = jobs::Job::Running {
+        id: 1,
+        retries: 0,
+        secret: "abc".to_string(),
+    };
+    *job.retries_mut() += 1;
+    assert_eq!((job.id(), job.retries()), (&1, &1));
+    assert_eq!(job.secret_len(), 3);
+    let job = jobs::Job::Queued {
+        id: 2,
+        retries: 0,
+        secret: String::new(),
+    };
+    assert_eq!(job.secret_len(), 0);
+}
+
+#[test]
+fn test_enum_visibility() {
+    mod jobs {
+        use enum_common_fields::EnumCommonFields;
+
+        #[derive(EnumCommonFields)]
+        #[common_field(id: u64, vis = pub)]
+        #[common_field(mut retries: u32)]
+        #[common_field(name: String, vis =)]
+        #[common_fields(vis = pub(super), common, constructors)]
+        pub enum Job {
+            Queued { id: u64, retries: u32, name: String },
+            Running { id: u64, retries: u32, name: String },
+        }
+
+        impl Job {
+            pub fn name_len(&self) -> usize {
+                self.name().len()
+            }
+        }
+    }
+
+    let mut job = jobs::Job::new_running(1, 0, "build".to_string());
+    *job.retries_mut() += 1;
+    assert_eq!((job.id(), job.common().retries), (&1, &1));
+    assert_eq!(job.name_len(), 5);
+    assert_eq!(jobs::Job::new_queued(2, 0, String::new()).name_len(), 0);
+}
+
+#[test]
+fn test_name_templates() {
+    #[derive(EnumCommonFields)]
+    #[common_field(mut + own identifier: String)]
+    #[common_field(copy seq: u64)]
+    #[common_field(mut payload as (data, data_mut): Vec<u8>)]
+    #[common_fields(
+        getter = "get_{field}",
+        mutter = "{field}_ref_mut",
+        owner = "take_{field}"
+    )]
+    #[common_fields(rename_map = "identifier => id")]
+    enum TestEnum {
+        VariantOne {
+            identifier: String,
+            seq: u64,
+            payload: Vec<u8>,
+        },
+        VariantTwo {
+            identifier: String,
+            seq: u64,
+            payload: Vec<u8>,
+        },
+    }
+
+    let mut test_enum = TestEnum::VariantTwo {
+        identifier: "a".to_string(),
+        seq: 1,
+        payload: vec![],
+    };
+    test_enum.id_ref_mut().push('b');
+    test_enum.data_mut().push(1);
+    assert_eq!(test_enum.get_id(), "ab");
+    assert_eq!(test_enum.seq(), 1);
+    assert_eq!(test_enum.data(), &[1]);
+    assert_eq!(test_enum.take_id(), "ab");
+    let test_enum = TestEnum::VariantOne {
+        identifier: "c".to_string(),
+        seq: 2,
+        payload: vec![],
+    };
+    assert_eq!(test_enum.get_id(), "c");
+}
+
+#[test]
+fn test_default_kinds() {
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String)]
+    #[common_field(copy seq: u64)]
+    #[common_field(flags: Vec<u8>, since = "1.0")]
+    #[common_fields(defaults(kinds = mut))]
+    enum TestEnum {
+        VariantOne {
+            key: String,
+            seq: u64,
+            flags: Vec<u8>,
+        },
+        VariantTwo {
+            key: String,
+            seq: u64,
+            flags: Vec<u8>,
+        },
+    }
+
+    let mut test_enum = TestEnum::VariantOne {
+        key: "a".to_string(),
+        seq: 1,
+        flags: vec![],
+    };
+    test_enum.key_mut().push('b');
+    test_enum.flags_mut().push(1);
+    assert_eq!((test_enum.key().as_str(), test_enum.seq()), ("ab", 1));
+    assert_eq!(test_enum.flags(), &[1]);
+    let test_enum = TestEnum::VariantTwo {
+        key: "c".to_string(),
+        seq: 2,
+        flags: vec![],
+    };
+    assert_eq!(test_enum.seq(), 2);
+}
+
+#[test]
+fn test_several_field_names() {
+    #[derive(EnumCommonFields)]
+    #[common_field(mut width, height: u32)]
+    #[common_field(copy x, y: i32)]
+    enum Shape {
+        Rectangle {
+            x: i32,
+            y: i32,
+            width: u32,
+            height: u32,
+        },
+        Ellipse {
+            width: u32,
+            height: u32,
+            x: i32,
+            y: i32,
+        },
+    }
+
+    let mut shape = Shape::Rectangle {
+        x: -1,
+        y: 1,
+        width: 2,
+        height: 3,
+    };
+    *shape.width_mut() += 1;
+    assert_eq!((shape.width(), shape.height()), (&3, &3));
+    assert_eq!((shape.x(), shape.y()), (-1, 1));
+    let shape = Shape::Ellipse {
+        width: 1,
+        height: 1,
+        x: 0,
+        y: 5,
+    };
+    assert_eq!(shape.y(), 5);
+}
+
+#[test]
+fn test_common_markers() {
+    #[derive(EnumCommonFields)]
+    #[common_field(name: str)]
+    enum TestEnum {
+        VariantOne {
+            #[common(mut + own)]
+            key: String,
+            name: String,
+            #[common(copy as seq)]
+            sequence_number: u64,
+        },
+        VariantTwo {
+            #[common]
+            key: String,
+            sequence_number: u64,
+            name: String,
+        },
+    }
+
+    let mut test_enum = TestEnum::VariantOne {
+        key: "a".to_string(),
+        name: "first".to_string(),
+        sequence_number: 1,
+    };
+    test_enum.key_mut().push('b');
+    assert_eq!((test_enum.key().as_str(), test_enum.seq()), ("ab", 1));
+    assert_eq!(test_enum.name(), "first");
+    let test_enum = TestEnum::VariantTwo {
+        key: "c".to_string(),
+        sequence_number: 2,
+        name: "second".to_string(),
+    };
+    assert_eq!(test_enum.seq(), 2);
+    assert_eq!(test_enum.into_key(), "c");
+}
+
+#[enum_common_fields::with_common_fields(fields(id: u64, mut name: String), common)]
+#[common_field(own_only name: String)]
+#[derive(Debug)]
+enum AttributeJob {
+    Pending,
+    Running { pid: u32 },
+}
+
+#[test]
+fn test_with_common_fields() {
+    let mut job = AttributeJob::Running {
+        id: 1,
+        name: "build".to_string(),
+        pid: 42,
+    };
+    job.name_mut().push_str("-all");
+    assert_eq!(job.common().id, &1);
+    assert!(matches!(job, AttributeJob::Running { pid: 42, .. }));
+    assert!(format!("{job:?}").starts_with("Running"));
+    assert_eq!(job.into_name(), "build-all");
+    let job = AttributeJob::Pending {
+        id: 2,
+        name: String::new(),
+    };
+    assert_eq!(job.id(), &2);
+}
+
+#[test]
+fn test_auto_with_exclude() {
+    #[derive(EnumCommonFields)]
+    #[common_fields(auto, exclude(cache), defaults(kinds = mut))]
+    #[common_field(own_only key: String)]
+    enum TestEnum {
+        VariantOne {
+            key: String,
+            seq: u64,
+            cache: Vec<u8>,
+        },
+        VariantTwo {
+            seq: u64,
+            key: String,
+            cache: Vec<u8>,
+            value: u32,
+        },
+    }
+
+    let mut test_enum = TestEnum::VariantTwo {
+        seq: 1,
+        key: "a".to_string(),
+        cache: Vec::new(),
+        value: 2,
+    };
+    *test_enum.seq_mut() += 1;
+    assert_eq!(test_enum.seq(), &2);
+    assert!(matches!(&test_enum, TestEnum::VariantTwo { cache, value: 2, .. } if cache.is_empty()));
+    assert_eq!(test_enum.into_key(), "a");
+    let test_enum = TestEnum::VariantOne {
+        key: "b".to_string(),
+        seq: 3,
+        cache: vec![1],
+    };
+    assert!(matches!(&test_enum, TestEnum::VariantOne { cache, .. } if cache.len() == 1));
+    assert_eq!(test_enum.seq(), &3);
+}
+
+#[test]
+fn test_inline() {
+    #[derive(EnumCommonFields)]
+    #[common_field(mut key: String, inline(always))]
+    #[common_field(copy seq: u64, inline(never))]
+    #[common_fields(inline, common, constructors)]
+    #[common_fields_hash(key)]
+    enum TestEnum {
+        VariantOne { key: String, seq: u64 },
+        VariantTwo { key: String, seq: u64 },
+    }
+
+    let mut test_enum = TestEnum::new_variant_one("a".to_string(), 1);
+    test_enum.key_mut().push('b');
+    assert_eq!((test_enum.key().as_str(), test_enum.seq()), ("ab", 1));
+    assert_eq!(
+        TestEnum::new_variant_two("c".to_string(), 2).common().seq,
+        &2
+    );
+}
+
+#[test]
+fn test_const_accessors() {
+    #[derive(EnumCommonFields)]
+    #[common_field(copy id: u64, const)]
+    #[common_field(name: &'static str, const)]
+    #[common_field(copy priority: u8, const, default = 0)]
+    #[common_fields_trait(HasId)]
+    enum TestEnum {
+        VariantOne {
+            id: u64,
+            name: &'static str,
+            priority: u8,
+        },
+        #[common_field_skip(priority)]
+        VariantTwo { name: &'static str, id: u64 },
+    }
+
+    const FIRST: TestEnum = TestEnum::VariantOne {
+        id: 1,
+        name: "first",
+        priority: 3,
+    };
+    const SECOND: TestEnum = TestEnum::VariantTwo {
+        name: "second",
+        id: 2,
+    };
+    const IDS: [u64; 2] = [FIRST.id(), SECOND.id()];
+    const PRIORITIES: [u8; 2] = [FIRST.priority(), SECOND.priority()];
+    assert_eq!(IDS, [1, 2]);
+    assert_eq!(PRIORITIES, [3, 0]);
+    assert_eq!(SECOND.name(), &"second");
+    fn id_of(value: &impl HasId) -> u64 {
+        value.id()
+    }
+    assert_eq!(id_of(&FIRST), 1);
+}
+
+/// Enum with documented fields and accessors
+#[deny(missing_docs)]
+pub mod documented {
+    use enum_common_fields::EnumCommonFields;
+
+    /// Record with documented accessors
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String, doc = "Stable identifier of the record")]
+    #[common_field(copy seq: u64, since = "1.0")]
+    pub enum Record {
+        /// Created record
+        Created {
+            /// Key of the record
+            key: String,
+            /// Sequence number of the change
+            seq: u64,
+        },
+        /// Deleted record
+        Deleted {
+            /// Key of the record
+            key: String,
+            /// Sequence number of the change
+            seq: u64,
+        },
+    }
+
+    /// Event with accessors documented by the default doc comment
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String)]
+    #[common_field(copy seq: u64)]
+    #[common_fields(defaults(doc = "Returns {field} of the event"))]
+    pub enum Event {
+        /// Started event
+        Started {
+            /// Key of the event, that the default doc comment doesn't replace
+            key: String,
+            #[allow(missing_docs)]
+            seq: u64,
+        },
+    }
+}
+
+#[test]
+fn test_documented_accessors() {
+    let record = documented::Record::Deleted {
+        key: "a".to_string(),
+        seq: 1,
+    };
+    assert_eq!((record.key().as_str(), record.seq()), ("a", 1));
+    let record = documented::Record::Created {
+        key: "b".to_string(),
+        seq: 2,
+    };
+    assert_eq!(record.seq(), 2);
+}
+
+#[test]
+fn test_alias() {
+    #[derive(EnumCommonFields)]
+    #[common_field(key: str, alias = "identifier", alias = "id", eq)]
+    #[common_field(mut + own seq: u64, alias = "sequence_number")]
+    #[common_fields_trait(HasKey)]
+    enum TestEnum {
+        VariantOne { key: String, seq: u64 },
+        VariantTwo { key: String, seq: u64 },
+    }
+
+    let mut test_enum = TestEnum::VariantOne {
+        key: "a".to_string(),
+        seq: 1,
+    };
+    *test_enum.seq_mut() += 1;
+    assert!(test_enum == "a");
+    assert_eq!(HasKey::seq(&test_enum), &2);
+    let test_enum = TestEnum::VariantTwo {
+        key: "b".to_string(),
+        seq: 3,
+    };
+    assert_eq!(test_enum.into_seq(), 3);
+}
+
+#[test]
+fn test_cfg_prefix() {
+    #[derive(EnumCommonFields)]
+    #[common_field(cfg(all()) mut key: String, eq)]
+    #[common_field(cfg(any()) secret: String, eq)]
+    #[common_fields_trait(HasKey)]
+    enum TestEnum {
+        VariantOne { key: String, secret: String },
+        VariantTwo { key: String, secret: String },
+    }
+
+    // Would conflict with the accessor, if the cfg didn't remove it
+    impl TestEnum {
+        fn secret(&self) -> &str {
+            match self {
+                TestEnum::VariantOne { secret, .. } | TestEnum::VariantTwo { secret, .. } => {
+                    secret.split_at(1).0
+                }
+            }
+        }
+    }
+
+    let mut test_enum = TestEnum::VariantOne {
+        key: "a".to_string(),
+        secret: "password".to_string(),
+    };
+    test_enum.key_mut().push('b');
+    assert!(test_enum == "ab".to_string());
+    assert_eq!(HasKey::key(&test_enum), "ab");
+    assert_eq!(test_enum.secret(), "p");
+    let test_enum = TestEnum::VariantTwo {
+        key: "c".to_string(),
+        secret: "hunter2".to_string(),
+    };
+    assert_eq!(test_enum.secret(), "h");
+}
+
+#[test]
+fn test_default_cfg() {
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String)]
+    #[common_field(cfg(all()) copy seq: u64)]
+    #[common_fields(defaults(cfg(any())))]
+    enum TestEnum {
+        VariantOne { key: String, seq: u64 },
+        VariantTwo { key: String, seq: u64 },
+    }
+
+    // Would conflict with the accessor, if the default cfg didn't remove it
+    impl TestEnum {
+        fn key(&self) -> usize {
+            match self {
+                TestEnum::VariantOne { key, .. } | TestEnum::VariantTwo { key, .. } => key.len(),
+            }
+        }
+    }
+
+    let test_enum = TestEnum::VariantOne {
+        key: "ab".to_string(),
+        seq: 1,
+    };
+    assert_eq!((test_enum.key(), test_enum.seq()), (2, 1));
+    let test_enum = TestEnum::VariantTwo {
+        key: "c".to_string(),
+        seq: 2,
+    };
+    assert_eq!((test_enum.key(), test_enum.seq()), (1, 2));
+}
+
+#[test]
+fn test_default_cfg_enum_options() {
+    trait Keyed {
+        fn key(&self) -> &String;
+    }
+
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String, impl = Keyed::key)]
+    #[common_field(copy seq: u64, since = "1.0")]
+    #[common_fields_config(defaults(cfg(any())), common, debug, display = "{key}#{seq}", metadata)]
+    #[common_fields_hash(key)]
+    #[common_fields_eq(key)]
+    enum TestEnum {
+        VariantOne { key: String, seq: u64 },
+        VariantTwo { key: String, seq: u64 },
+    }
+
+    // Would conflict with the accessor, if the default cfg didn't remove it
+    impl TestEnum {
+        fn seq(&self) -> usize {
+            0
+        }
+    }
+
+    let one = TestEnum::VariantOne {
+        key: "a".to_string(),
+        seq: 1,
+    };
+    let two = TestEnum::VariantTwo {
+        key: "a".to_string(),
+        seq: 2,
+    };
+    // The accessor implementing the trait method isn't gated, and the fields stay common for enum options
+    assert_eq!(Keyed::key(&one), "a");
+    assert_eq!(one.seq(), 0);
+    assert_eq!(one.common().seq, &1);
+    assert!(one == two);
+    assert_eq!(one.to_string(), "a#1");
+    assert_eq!(format!("{two:?}"), r#"VariantTwo { key: "a", seq: 2, .. }"#);
+    // Gated methods aren't listed
+    assert_eq!(
+        TestEnum::COMMON_FIELDS_METADATA,
+        concat!(
+            r#"{"enum":"TestEnum","variants":["VariantOne","VariantTwo"],"#,
+            r#""fields":[{"name":"key","type":"String"},{"name":"seq","type":"u64"}],"methods":["common","key"]}"#
+        )
+    );
+}
+
+#[test]
+fn test_cfg_prefix_enum_options() {
+    #[derive(EnumCommonFields)]
+    #[common_field(key: String, since = "1.0")]
+    #[common_field(cfg(any()) secret: String, since = "1.0")]
+    #[common_fields_config(common, debug, metadata, frozen = "key, common")]
+    enum TestEnum {
+        VariantOne { key: String, secret: String },
+        VariantTwo { key: String, secret: String },
+    }
+
+    // The gated field isn't a common one, so the common struct can be built without it
+    let _ = TestEnumCommonRef {
+        key: &"a".to_string(),
+    };
+    let test_enum = TestEnum::VariantOne {
+        key: "a".to_string(),
+        secret: "password".to_string(),
+    };
+    assert_eq!(test_enum.common().key, "a");
+    assert_eq!(format!("{test_enum:?}"), r#"VariantOne { key: "a", .. }"#);
+    assert_eq!(TestEnum::COMMON_FIELDS_SINCE, &[("key", "1.0")]);
+    assert_eq!(
+        TestEnum::COMMON_FIELDS_METADATA,
+        concat!(
+            r#"{"enum":"TestEnum","variants":["VariantOne","VariantTwo"],"#,
+            r#""fields":[{"name":"key","type":"String"}],"methods":["common","key"]}"#
+        )
+    );
+    let (TestEnum::VariantOne { secret, .. } | TestEnum::VariantTwo { secret, .. }) = test_enum;
+    assert_eq!(secret, "password");
+    let test_enum = TestEnum::VariantTwo {
+        key: "b".to_string(),
+        secret: String::new(),
+    };
+    assert_eq!(test_enum.key(), "b");
+}